@@ -7,6 +7,25 @@
  */
 export function add(a: number, b: number): number;
 
+/**
+ * Regenerate the held grid's heightmap from a template (library id/name or
+ * template text) with the grid's seed, resetting entity indices like
+ * `reset_heightmap_h`. Returns the new `cells.h`; throws a `TemplateError`
+ * (leaving the grid untouched) if the template is malformed.
+ *
+ * Exposed as `apply_heightmap_template_h(template)` to JS.
+ */
+export function apply_heightmap_template_h(template: string): Uint8Array;
+
+/**
+ * Regenerate the held grid's heightmap by plate tectonics with the grid's
+ * seed, resetting entity indices like `reset_heightmap_h`. Returns the new
+ * `cells.h`.
+ *
+ * Exposed as `apply_tectonics_h(opts)` to JS.
+ */
+export function apply_tectonics_h(opts_js: any): Uint8Array;
+
 /**
  * Step 1.2 (world-assembly form): build a `Grid` from a deserialized `Mesh`
  * and store the generated heightmap into `grid.cells.h`. Returns a `Grid`
@@ -19,6 +38,56 @@ export function add(a: number, b: number): number;
  */
 export function build_grid_with_heightmap(mesh_js: any, seed: number): any;
 
+/**
+ * Merge a mesh's cells by a per-cell label array (`Int32Array`, `-1` =
+ * unassigned) into one multipolygon per label plus the once-only borders
+ * between labels. Returns an `Outlines` (`{ labels, borders }`); rings are
+ * vertex ids into `mesh.vertices.p`.
+ *
+ * Exposed as `cell_outlines(mesh, labels)` to JS.
+ */
+export function cell_outlines(mesh_js: any, labels_js: any): any;
+
+/**
+ * `cell_outlines` over the held grid's mesh. No Grid serde.
+ *
+ * Exposed as `cell_outlines_h(labels)` to JS.
+ */
+export function cell_outlines_h(labels_js: any): any;
+
+/**
+ * Cells of the held grid whose centre lies inside `polygon` (an array of
+ * `[x, y]` vertices, implicitly closed, even-odd rule), as an ascending
+ * `Uint32Array`.
+ *
+ * Exposed as `cells_in_polygon_h(polygon)` to JS.
+ */
+export function cells_in_polygon_h(polygon_js: any): any;
+
+/**
+ * Cells of the held grid whose centre is within `radius` of `(x, y)`, as an
+ * ascending `Uint32Array`. Seam-aware on wrapping topologies.
+ *
+ * Exposed as `cells_in_radius_h(x, y, radius)` to JS.
+ */
+export function cells_in_radius_h(x: number, y: number, radius: number): any;
+
+/**
+ * Cells of the held grid whose centre lies in the rectangle with corners
+ * `(x0, y0)` / `(x1, y1)`, as an ascending `Uint32Array`.
+ *
+ * Exposed as `cells_in_rect_h(x0, y0, x1, y1)` to JS.
+ */
+export function cells_in_rect_h(x0: number, y0: number, x1: number, y1: number): any;
+
+/**
+ * Drop the held grid's edit history (the grid itself is unchanged). The
+ * applied edits stay in its journal.
+ *
+ * Exposed as `clear_history_h()` to JS.
+ */
+export function clear_history_h(): void;
+
 /**
  * Step 2.5.1: apply a batch of heightmap edit ops (brush + macro tools) to
  * `grid.cells.h` in place. Deterministic: same `grid` + same `ops` yields
@@ -33,10 +102,54 @@ export function edit_heightmap(grid_js: any, ops_js: any): any;
  * its `heldGrid.cells.h` from the returned array (or just use the array
  * directly for the texture upload).
  *
+ * Each op is recorded as its own undo step (see `undo_h`), snapshotting
+ * only the cells its `heightmap_edit::footprint` covers when it has one.
+ *
  * Exposed as `edit_heightmap_h(ops)` to JS.
  */
 export function edit_heightmap_h(ops_js: any): Uint8Array;
 
+/**
+ * The held grid as a `journal::EditJournal`: how its base world was
+ * generated, the `EditOp`s applied since (up to the current undo step) and a
+ * checksum of its `cells.h`. `null` if it can't be journaled: the grid was
+ * stored or imported rather than generated, or an applied step wasn't an
+ * `EditOp` (erosion, reset, template, tectonics, image import).
+ *
+ * Exposed as `edit_journal_h()` to JS.
+ */
+export function edit_journal_h(): any;
+
+/**
+ * Erode a heightmap (`h`, any generator's output) over `mesh`: flux-based
+ * hydraulic erosion with deposition plus talus thermal erosion, keeping the
+ * coastline. `opts` is an `ErosionOpts`; absent fields take their defaults.
+ * Returns the eroded copy of `h`.
+ */
+export function erode_heightmap(mesh_js: any, h: Uint8Array, opts_js: any): Uint8Array;
+
+/**
+ * `erode_heightmap` on the held grid's `cells.h`, in place, leaving locked
+ * cells alone. The coastline is unchanged, so entity indices are kept;
+ * follow with `recompute_dependents_h` to refresh climate, biomes and
+ * drainage.
+ *
+ * Exposed as `erode_heightmap_h(opts)` to JS.
+ */
+export function erode_heightmap_h(opts_js: any): Uint8Array;
+
+/**
+ * Rasterize one layer of the held grid (`h`, `temp`, `prec`, `fl`, `biome`,
+ * `state`, `culture`, `religion`) at `opts.width × opts.height` pixels and
+ * return PNG bytes: 16-bit grayscale for continuous layers, paletted RGBA
+ * for categorical ones. `pack_js` (an `entities::Pack`, or `null`) supplies
+ * the state / culture / religion colors. Throws an `ExportError` on a bad
+ * size or unreadable options / pack.
+ *
+ * Exposed as `export_layer_png_h(opts, pack)` to JS.
+ */
+export function export_layer_png_h(opts_js: any, pack_js: any): Uint8Array;
+
 /**
  * Step 1.4: produce `cells.biome` (Uint8Array, `0..=12`, `0` = Marine/water)
  * from a deserialized `Mesh` + the climate `{ temp, prec }` + the heightmap
@@ -96,6 +209,13 @@ export function generate_cultures_religions(grid_js: any, seed: number, culture_
  */
 export function generate_heightmap(mesh_js: any, seed: number): Uint8Array;
 
+/**
+ * `generate_heightmap` driven by a template (library id/name or template
+ * text) instead of the built-in default. Throws a `TemplateError` if the
+ * template is malformed.
+ */
+export function generate_heightmap_from_template(mesh_js: any, seed: number, template: string): Uint8Array;
+
 /**
  * Step 1.1: generate a deterministic Voronoi mesh from `cell_count` seeded
  * points. Returns a `JsValue` with fields `{ points, cells, vertices }`
@@ -103,6 +223,19 @@ export function generate_heightmap(mesh_js: any, seed: number): Uint8Array;
  */
 export function generate_mesh(cell_count: number, seed: number): any;
 
+/**
+ * Build a flat mesh over caller-supplied `[x, y]` seed points (no jitter —
+ * cell `i` sits exactly at `points[i]`). Throws `{ kind, ... }` describing
+ * the first problem if the points are rejected.
+ */
+export function generate_mesh_from_points(points_js: any, world_w: number, world_h: number): any;
+
+/**
+ * `generate_mesh` for an explicit surface topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+ * Globe meshes additionally carry per-cell `lat_lon`.
+ */
+export function generate_mesh_with_topology(cell_count: number, seed: number, topology_js: any): any;
+
 /**
  * Phase 3 Step 3.2: generate states, provinces, and burgs for a fully-built
  * `Grid` (mesh + heightmap + climate + biomes + drainage). Returns a
@@ -117,6 +250,14 @@ export function generate_mesh(cell_count: number, seed: number): any;
  */
 export function generate_states(grid_js: any, seed: number, count: number): any;
 
+/**
+ * `generate_heightmap` by plate tectonics instead of template floods.
+ * Returns `{ h, plate, plates }`: the heightmap, each cell's plate index and
+ * the plates (`{ seed_cell, velocity, continental, area }`). `opts` is a
+ * `TectonicOpts`; absent fields take their defaults.
+ */
+export function generate_tectonics(mesh_js: any, seed: number, opts_js: any): any;
+
 /**
  * Phase 4.2: generate a deterministic `Timeline` from a year-0 `Pack` + cell
  * arrays + era bounds + seed. Each module (succession, war, plague, golden
@@ -128,9 +269,11 @@ export function generate_states(grid_js: any, seed: number, count: number): any;
  * heightmap (`u8`, `< 20` = water). `params` is an optional `TimelineParams`
  * object (defaults if omitted). All RNG is `StdRng::seed_from_u64(seed)`.
  *
- * `mesh_js` is the optional Voronoi `Mesh` (or just its `cells` topology).
- * When provided, the war module's cell-neighbor lookups use the true
- * Delaunay adjacency instead of the legacy square-grid assumption.
+ * `mesh_js` is the Voronoi `Mesh` (or just its `cells` topology) — REQUIRED.
+ * The engine uses the true Delaunay adjacency from the mesh; there is no
+ * square-grid fallback. If `mesh_js` is undefined or fails to deserialize to
+ * a valid `Mesh`, this function panics (`expect`), which surfaces as a thrown
+ * JS error to the caller.
  *
  * Exposed as `generate_timeline(pack, cells_state, cells_culture, cells_religion,
  * cells_burg, cells_h, mesh, seed, params)` to JS.
@@ -152,6 +295,29 @@ export function generate_timeline(pack_js: any, cells_state: Int32Array, cells_c
  */
 export function generate_world(seed: number, cell_count: number, opts_js: any): any;
 
+/**
+ * `generate_world_with_topology` with the heightmap drawn from a template
+ * (library id/name such as `"archipelago"`, or template text). `topology`
+ * and `opts` fall back to `Flat` / defaults when absent. Throws a
+ * `TemplateError` if the template is malformed; otherwise the result is
+ * also stored as the held grid.
+ */
+export function generate_world_from_template(seed: number, cell_count: number, template: string, topology_js: any, opts_js: any): any;
+
+/**
+ * `generate_world_with_topology` with a plate-tectonic heightmap
+ * (`tectonic_opts` a `TectonicOpts`). `topology` and both option objects
+ * fall back to `Flat` / defaults when absent. Like `generate_world`, the
+ * result is also stored as the held grid.
+ */
+export function generate_world_tectonic(seed: number, cell_count: number, topology_js: any, tectonic_opts_js: any, opts_js: any): any;
+
+/**
+ * `generate_world` on a mesh of the given topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+ * Like `generate_world`, the result is also stored as the held grid.
+ */
+export function generate_world_with_topology(seed: number, cell_count: number, topology_js: any, opts_js: any): any;
+
 /**
  * Step 2.5.6: compute river + lake geometry from the held Grid and return it
  * as a serde-encoded `{ rivers: RiverGeo[], lakes: LakeGeo[] }` object.
@@ -178,6 +344,52 @@ export function get_drainage_geometry_h(): any;
  */
 export function has_grid_h(): boolean;
 
+/**
+ * The named heightmap template library (FMG's templates) as an array of
+ * `{ id, name, template }`. Any `id` or `name` can be passed wherever a
+ * template is accepted.
+ */
+export function heightmap_templates(): any;
+
+/**
+ * The held grid's edit history as `{ cursor, steps: [{ label, cells }] }`,
+ * for a history panel.
+ *
+ * Exposed as `history_h()` to JS.
+ */
+export function history_h(): any;
+
+/**
+ * Undo or redo until `step` edits are applied (`0` = before the oldest
+ * kept edit, `history_h().steps.length` = latest). Returns the new
+ * `cells.h`, or `undefined` if `step` is out of range.
+ *
+ * Exposed as `history_jump_h(step)` to JS.
+ */
+export function history_jump_h(step: number): Uint8Array | undefined;
+
+/**
+ * Build a world whose heightmap comes from a grayscale image (PNG or PGM
+ * bytes, or a raw buffer described by `import_opts.raw`) instead of the
+ * generator: a fresh mesh of `topology` (default `Flat`), the image
+ * resampled onto it, then climate, biomes and drainage as in
+ * `generate_world`. Throws an `ImageError` (`{ kind, ... }`) if the image or
+ * `import_opts` is rejected; otherwise the result is also stored as the held
+ * grid.
+ */
+export function import_heightmap(bytes: Uint8Array, cell_count: number, seed: number, topology_js: any, import_opts_js: any, opts_js: any): any;
+
+/**
+ * Replace the held grid's heightmap with a grayscale image resampled onto
+ * its mesh, resetting entity indices like `reset_heightmap_h`. Returns the
+ * new `cells.h`; follow with `recompute_dependents_h` to refresh climate,
+ * biomes and drainage. Throws an `ImageError` (leaving the grid untouched)
+ * if the image or `import_opts` is rejected.
+ *
+ * Exposed as `import_heightmap_h(bytes, importOpts)` to JS.
+ */
+export function import_heightmap_h(bytes: Uint8Array, import_opts_js: any): Uint8Array;
+
 /**
  * Initialize the panic hook so Rust panics surface in the browser console
  * instead of silently failing. Called once on startup.
@@ -185,9 +397,47 @@ export function has_grid_h(): boolean;
 export function init(): void;
 
 /**
- * Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Uses the
- * `cells.spacing` spatial grid + neighbor refinement. Returns the cell id
- * as a `u32`, or `-1` if the grid has no cells. O(1)-ish, deterministic.
+ * Köppen–Geiger class per cell of the held grid (ids into
+ * `koppen_table()`, `0` = ocean). With `opts.seasonal` the classes come from
+ * the monthly climate (`climate::generate_seasons`); otherwise from the held
+ * `cells.temp` / `cells.prec` alone (`koppen::classify_annual`), which can't
+ * tell dry seasons apart. Returns a `Uint8Array`.
+ *
+ * Exposed as `koppen_h(opts)` to JS.
+ */
+export function koppen_h(opts_js: any): any;
+
+/**
+ * The Köppen–Geiger classes: `{ id, code, name, color }[]` in id order
+ * (`color` packed `0xRRGGBB`).
+ *
+ * Exposed as `koppen_table()` to JS.
+ */
+export function koppen_table(): any;
+
+/**
+ * The held grid's lock mask, `1` per locked cell, for an overlay. Cells are
+ * locked and unlocked with `Lock` / `Unlock` ops through `edit_heightmap_h`,
+ * so locking is undoable and journaled like any edit.
+ *
+ * Exposed as `locked_h()` to JS.
+ */
+export function locked_h(): Uint8Array;
+
+/**
+ * Surface ocean currents of the held grid (`climate::ocean_currents`).
+ * Returns `{ flow: Float32Array, anomaly: Float32Array }`: the current per
+ * cell as interleaved `x, y` pairs (`y` south, zero on land), and the sea
+ * surface temperature anomaly (°C) with the share carried onto the coasts.
+ *
+ * Exposed as `ocean_currents_h(opts)` to JS.
+ */
+export function ocean_currents_h(opts_js: any): any;
+
+/**
+ * Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Exact search
+ * over the mesh spatial index. Returns the cell id as a `u32`, or `-1` if the
+ * grid has no cells. Deterministic (ties go to the lower cell id).
  *
  * Exposed as `pick_cell(grid, x, y)` to JS.
  */
@@ -200,6 +450,30 @@ export function pick_cell(grid_js: any, x: number, y: number): number;
  */
 export function pick_cell_h(x: number, y: number): number;
 
+/**
+ * The wind passes behind the held grid's precipitation
+ * (`climate::generate_precipitation_traced`, over the held `cells.h` /
+ * `cells.temp`). Returns `{ wind: Float32Array, humidity: Float32Array,
+ * orographic: Float32Array }`: the prevailing wind per cell as interleaved
+ * `x, y` pairs (`y` south, length = strength), the humidity left in the air
+ * leaving the cell, and the orographic share of its rain.
+ *
+ * Exposed as `precipitation_trace_h(opts)` to JS.
+ */
+export function precipitation_trace_h(opts_js: any): any;
+
+/**
+ * Project the held grid's climate at `target_year` (`timeline::project_climate`),
+ * to show alongside `WorldAt(target_year)`, under `drift_js`, the
+ * `ClimateKeyframe[]` the timeline was generated with
+ * (`TimelineParams.climate`). `undefined` or `[]` means no drift, and the
+ * year-0 climate comes back. Returns `{ year, temp_offset, prec_scale, temp:
+ * Int8Array, prec: Uint8Array, biome: Uint8Array }`.
+ *
+ * Exposed as `project_climate_h(targetYear, drift)` to JS.
+ */
+export function project_climate_h(target_year: number, drift_js: any): any;
+
 /**
  * Phase 4.1: incremental forward scrubbing. Applies only the events in
  * `(prev_year, target_year]` to a `WorldAt`, mutating it in place and
@@ -278,6 +552,24 @@ export function recompute_dependents_h(opts_js: any): any;
  */
 export function recompute_dependents_h2(opts_js: any): any;
 
+/**
+ * Mid-stroke drainage update on the held grid: the river counterpart of
+ * `recompute_temp_biome_local_h`. `cellIds` are the cells edited since the
+ * last drainage pass; `rivers::update_drainage` recomputes only the
+ * catchments they can affect, starting from the drainage the last
+ * `recompute_dependents_h`/`_h2` or `get_drainage_geometry_h` left in
+ * `HELD_DRAINAGE` (or a full pass if there is none). Writes `fl`/`r`/`conf`
+ * back into the held grid.
+ *
+ * Returns `{ cells: Uint32Array, fl: Uint16Array, r: Uint16Array,
+ * conf: Uint16Array, rivers: RiverGeo[], lakes: LakeGeo[] }`: the
+ * recomputed cells with their new values (in `cells` order), and the full
+ * river and lake lists. River ids outside the recomputed cells don't change.
+ *
+ * Exposed as `recompute_drainage_local_h(cellIds)` to JS.
+ */
+export function recompute_drainage_local_h(cell_ids_js: any): any;
+
 /**
  * Step 2.5.2: Tier-1 local recompute of temp + biome for an affected cell
  * set. Runs `recompute_temp_local` then `recompute_biome_local` in place on
@@ -299,18 +591,47 @@ export function recompute_temp_biome_local(grid_js: any, cell_ids_js: any, opts_
  */
 export function recompute_temp_biome_local_h(cell_ids_js: any, opts_js: any): any;
 
+/**
+ * Re-apply the latest undone edit. Returns the new `cells.h`, or
+ * `undefined` if there is nothing to redo. Dependent layers follow as for
+ * `undo_h`.
+ *
+ * Exposed as `redo_h()` to JS.
+ */
+export function redo_h(): Uint8Array | undefined;
+
+/**
+ * Refine a region of the Rust-side held grid at higher density. `region` is
+ * `{ kind: "Rect", x0, y0, x1, y1 }` or `{ kind: "Cells", cells }`. Returns a
+ * `RegionGrid` (`{ grid, origin, parent_cell }`), or `null` if the region is
+ * empty. The held grid is left untouched.
+ *
+ * Exposed as `refine_region_h(region, cellCount, seed)` to JS.
+ */
+export function refine_region_h(region_js: any, cell_count: number, seed: number): any;
+
 /**
  * Release the held grid (drops it). Called when the worker is done with a
  * world or before loading a new one.
  */
 export function release_grid_h(): void;
 
+/**
+ * Rebuild the world of an `EditJournal` (from `edit_journal_h`) and return
+ * it like `generate_world`, also storing it as the held grid; its edits
+ * carry over into the new grid's journal. Throws a `JournalError`
+ * (`{ kind, ... }`) on a generator version mismatch, a bad template or a
+ * checksum mismatch.
+ */
+export function replay_edit_journal(journal_js: any): any;
+
 /**
  * Step 2.5.4: reset `grid.cells.h` back to the original seeded heightmap.
  * Regenerates `h` from `grid.seed` + `grid.mesh` using the same
  * `heightmap::generate` used by `generate_world`. Also reinitializes the
  * entity index arrays (`state`/`province`/`culture`/`religion`/`burg`) to
  * their "unassigned" sentinels, since Reset means "discard all edits".
+ * Locked cells (`CellData::locked`) keep both their height and entities.
  * Returns the updated `Grid` as `JsValue`.
  *
  * Exposed as `reset_heightmap(grid)` to JS.
@@ -325,6 +646,30 @@ export function reset_heightmap(grid_js: any): any;
  */
 export function reset_heightmap_h(): Uint8Array;
 
+/**
+ * Monthly climate of the held grid (`climate::generate_seasons`; set
+ * `opts.axial_tilt` for other worlds). Returns `{ temp: Int8Array[12],
+ * prec: Uint8Array[12], frost: Uint8Array, onset: Uint8Array }`: per-month
+ * cell layers, months below freezing per cell (snowbound passes), and the
+ * month the wet season arrives (`255` where there is none).
+ *
+ * Exposed as `seasons_h(opts)` to JS.
+ */
+export function seasons_h(opts_js: any): any;
+
+/**
+ * Ordered sea and lake shorelines of the held grid, each Chaikin-smoothed
+ * `smoothing` times (`0` = raw Voronoi edges; capped at
+ * `coast::MAX_SMOOTHING`). Traced on request only, from the lakes of the
+ * drainage kept in `HELD_DRAINAGE` (a full `rivers::compute_drainage` when
+ * there is none) numbered like `get_drainage_geometry_h`, so
+ * `Shoreline.feature` of a lake shore matches `LakeGeo.id` there. Returns
+ * `Shoreline[]`.
+ *
+ * Exposed as `shorelines_h(smoothing)` to JS.
+ */
+export function shorelines_h(smoothing: number): any;
+
 /**
  * Store a Grid (deserialized from JS) into the Rust-side handle slot.
  * Replaces any previously held grid. The held grid is owned by Rust after
@@ -332,46 +677,103 @@ export function reset_heightmap_h(): Uint8Array;
  */
 export function store_grid_h(grid_js: any): void;
 
+/**
+ * Undo the latest heightmap edit on the held grid (brush op, erosion,
+ * reset, template, tectonics or image import). Returns the restored
+ * `cells.h`, or `undefined` if there is nothing to undo. Entity indices
+ * come back too; climate, biomes and drainage are recomputed on their next
+ * read, or call `recompute_dependents_h` to get them all at once.
+ *
+ * Exposed as `undo_h()` to JS.
+ */
+export function undo_h(): Uint8Array | undefined;
+
+/**
+ * Check a heightmap template (library id/name or template text) without
+ * running it. Returns the step count, or throws a `{ kind, line, ... }`
+ * `TemplateError` for the first malformed line.
+ */
+export function validate_heightmap_template(template: string): number;
+
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
     readonly memory: WebAssembly.Memory;
     readonly add: (a: number, b: number) => number;
+    readonly apply_heightmap_template_h: (a: number, b: number) => [number, number, number];
+    readonly apply_tectonics_h: (a: any) => any;
     readonly build_grid_with_heightmap: (a: any, b: number) => any;
+    readonly cell_outlines: (a: any, b: any) => any;
+    readonly cell_outlines_h: (a: any) => any;
+    readonly cells_in_polygon_h: (a: any) => any;
+    readonly cells_in_radius_h: (a: number, b: number, c: number) => any;
+    readonly cells_in_rect_h: (a: number, b: number, c: number, d: number) => any;
+    readonly clear_history_h: () => void;
     readonly edit_heightmap: (a: any, b: any) => any;
     readonly edit_heightmap_h: (a: any) => any;
+    readonly edit_journal_h: () => any;
+    readonly erode_heightmap: (a: any, b: number, c: number, d: any) => any;
+    readonly erode_heightmap_h: (a: any) => any;
+    readonly export_layer_png_h: (a: any, b: any) => [number, number, number];
     readonly generate_biomes: (a: any, b: any, c: any) => any;
     readonly generate_biomes_for_grid: (a: any) => any;
     readonly generate_climate: (a: any, b: any, c: any) => any;
     readonly generate_climate_for_grid: (a: any, b: any) => any;
     readonly generate_cultures_religions: (a: any, b: number, c: number, d: number, e: any) => any;
     readonly generate_heightmap: (a: any, b: number) => any;
+    readonly generate_heightmap_from_template: (a: any, b: number, c: number, d: number) => [number, number, number];
     readonly generate_mesh: (a: number, b: number) => any;
+    readonly generate_mesh_from_points: (a: any, b: number, c: number) => [number, number, number];
+    readonly generate_mesh_with_topology: (a: number, b: number, c: any) => any;
     readonly generate_states: (a: any, b: number, c: number) => any;
+    readonly generate_tectonics: (a: any, b: number, c: any) => any;
     readonly generate_timeline: (a: any, b: any, c: any, d: any, e: any, f: any, g: any, h: any, i: bigint, j: any) => any;
     readonly generate_world: (a: number, b: number, c: any) => any;
+    readonly generate_world_from_template: (a: number, b: number, c: number, d: number, e: any, f: any) => [number, number, number];
+    readonly generate_world_tectonic: (a: number, b: number, c: any, d: any, e: any) => any;
+    readonly generate_world_with_topology: (a: number, b: number, c: any, d: any) => any;
     readonly get_drainage_geometry_h: () => any;
     readonly has_grid_h: () => number;
+    readonly heightmap_templates: () => any;
+    readonly history_h: () => any;
+    readonly history_jump_h: (a: number) => any;
+    readonly import_heightmap: (a: number, b: number, c: number, d: number, e: any, f: any, g: any) => [number, number, number];
+    readonly import_heightmap_h: (a: number, b: number, c: any) => [number, number, number];
     readonly init: () => void;
+    readonly koppen_h: (a: any) => any;
+    readonly koppen_table: () => any;
+    readonly locked_h: () => any;
+    readonly ocean_currents_h: (a: any) => any;
     readonly pick_cell: (a: any, b: number, c: number) => number;
     readonly pick_cell_h: (a: number, b: number) => number;
+    readonly precipitation_trace_h: (a: any) => any;
+    readonly project_climate_h: (a: number, b: any) => any;
     readonly project_delta: (a: any, b: any, c: number, d: number) => any;
     readonly project_world: (a: any, b: any, c: any, d: any, e: any, f: any, g: number) => any;
     readonly recompute_dependents: (a: any, b: any) => any;
     readonly recompute_dependents_h: (a: any) => any;
     readonly recompute_dependents_h2: (a: any) => any;
+    readonly recompute_drainage_local_h: (a: any) => any;
     readonly recompute_temp_biome_local: (a: any, b: any, c: any) => any;
     readonly recompute_temp_biome_local_h: (a: any, b: any) => any;
+    readonly redo_h: () => any;
+    readonly refine_region_h: (a: any, b: number, c: number) => any;
     readonly release_grid_h: () => void;
+    readonly replay_edit_journal: (a: any) => [number, number, number];
     readonly reset_heightmap: (a: any) => any;
     readonly reset_heightmap_h: () => any;
+    readonly seasons_h: (a: any) => any;
+    readonly shorelines_h: (a: number) => any;
     readonly store_grid_h: (a: any) => void;
+    readonly undo_h: () => any;
+    readonly validate_heightmap_template: (a: number, b: number) => [number, number, number];
     readonly __wbindgen_malloc: (a: number, b: number) => number;
     readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
     readonly __wbindgen_exn_store: (a: number) => void;
     readonly __externref_table_alloc: () => number;
     readonly __wbindgen_externrefs: WebAssembly.Table;
     readonly __wbindgen_free: (a: number, b: number, c: number) => void;
+    readonly __externref_table_dealloc: (a: number) => void;
     readonly __wbindgen_start: () => void;
 }
 
@@ -12,6 +12,40 @@ export function add(a, b) {
     return ret;
 }
 
+/**
+ * Regenerate the held grid's heightmap from a template (library id/name or
+ * template text) with the grid's seed, resetting entity indices like
+ * `reset_heightmap_h`. Returns the new `cells.h`; throws a `TemplateError`
+ * (leaving the grid untouched) if the template is malformed.
+ *
+ * Exposed as `apply_heightmap_template_h(template)` to JS.
+ * @param {string} template
+ * @returns {Uint8Array}
+ */
+export function apply_heightmap_template_h(template) {
+    const ptr0 = passStringToWasm0(template, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.apply_heightmap_template_h(ptr0, len0);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Regenerate the held grid's heightmap by plate tectonics with the grid's
+ * seed, resetting entity indices like `reset_heightmap_h`. Returns the new
+ * `cells.h`.
+ *
+ * Exposed as `apply_tectonics_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {Uint8Array}
+ */
+export function apply_tectonics_h(opts_js) {
+    const ret = wasm.apply_tectonics_h(opts_js);
+    return ret;
+}
+
 /**
  * Step 1.2 (world-assembly form): build a `Grid` from a deserialized `Mesh`
  * and store the generated heightmap into `grid.cells.h`. Returns a `Grid`
@@ -30,6 +64,89 @@ export function build_grid_with_heightmap(mesh_js, seed) {
     return ret;
 }
 
+/**
+ * Merge a mesh's cells by a per-cell label array (`Int32Array`, `-1` =
+ * unassigned) into one multipolygon per label plus the once-only borders
+ * between labels. Returns an `Outlines` (`{ labels, borders }`); rings are
+ * vertex ids into `mesh.vertices.p`.
+ *
+ * Exposed as `cell_outlines(mesh, labels)` to JS.
+ * @param {any} mesh_js
+ * @param {any} labels_js
+ * @returns {any}
+ */
+export function cell_outlines(mesh_js, labels_js) {
+    const ret = wasm.cell_outlines(mesh_js, labels_js);
+    return ret;
+}
+
+/**
+ * `cell_outlines` over the held grid's mesh. No Grid serde.
+ *
+ * Exposed as `cell_outlines_h(labels)` to JS.
+ * @param {any} labels_js
+ * @returns {any}
+ */
+export function cell_outlines_h(labels_js) {
+    const ret = wasm.cell_outlines_h(labels_js);
+    return ret;
+}
+
+/**
+ * Cells of the held grid whose centre lies inside `polygon` (an array of
+ * `[x, y]` vertices, implicitly closed, even-odd rule), as an ascending
+ * `Uint32Array`.
+ *
+ * Exposed as `cells_in_polygon_h(polygon)` to JS.
+ * @param {any} polygon_js
+ * @returns {any}
+ */
+export function cells_in_polygon_h(polygon_js) {
+    const ret = wasm.cells_in_polygon_h(polygon_js);
+    return ret;
+}
+
+/**
+ * Cells of the held grid whose centre is within `radius` of `(x, y)`, as an
+ * ascending `Uint32Array`. Seam-aware on wrapping topologies.
+ *
+ * Exposed as `cells_in_radius_h(x, y, radius)` to JS.
+ * @param {number} x
+ * @param {number} y
+ * @param {number} radius
+ * @returns {any}
+ */
+export function cells_in_radius_h(x, y, radius) {
+    const ret = wasm.cells_in_radius_h(x, y, radius);
+    return ret;
+}
+
+/**
+ * Cells of the held grid whose centre lies in the rectangle with corners
+ * `(x0, y0)` / `(x1, y1)`, as an ascending `Uint32Array`.
+ *
+ * Exposed as `cells_in_rect_h(x0, y0, x1, y1)` to JS.
+ * @param {number} x0
+ * @param {number} y0
+ * @param {number} x1
+ * @param {number} y1
+ * @returns {any}
+ */
+export function cells_in_rect_h(x0, y0, x1, y1) {
+    const ret = wasm.cells_in_rect_h(x0, y0, x1, y1);
+    return ret;
+}
+
+/**
+ * Drop the held grid's edit history (the grid itself is unchanged). The
+ * applied edits stay in its journal.
+ *
+ * Exposed as `clear_history_h()` to JS.
+ */
+export function clear_history_h() {
+    wasm.clear_history_h();
+}
+
 /**
  * Step 2.5.1: apply a batch of heightmap edit ops (brush + macro tools) to
  * `grid.cells.h` in place. Deterministic: same `grid` + same `ops` yields
@@ -50,6 +167,9 @@ export function edit_heightmap(grid_js, ops_js) {
  * its `heldGrid.cells.h` from the returned array (or just use the array
  * directly for the texture upload).
  *
+ * Each op is recorded as its own undo step (see `undo_h`), snapshotting
+ * only the cells its `heightmap_edit::footprint` covers when it has one.
+ *
  * Exposed as `edit_heightmap_h(ops)` to JS.
  * @param {any} ops_js
  * @returns {Uint8Array}
@@ -59,6 +179,74 @@ export function edit_heightmap_h(ops_js) {
     return ret;
 }
 
+/**
+ * The held grid as a `journal::EditJournal`: how its base world was
+ * generated, the `EditOp`s applied since (up to the current undo step) and a
+ * checksum of its `cells.h`. `null` if it can't be journaled: the grid was
+ * stored or imported rather than generated, or an applied step wasn't an
+ * `EditOp` (erosion, reset, template, tectonics, image import).
+ *
+ * Exposed as `edit_journal_h()` to JS.
+ * @returns {any}
+ */
+export function edit_journal_h() {
+    const ret = wasm.edit_journal_h();
+    return ret;
+}
+
+/**
+ * Erode a heightmap (`h`, any generator's output) over `mesh`: flux-based
+ * hydraulic erosion with deposition plus talus thermal erosion, keeping the
+ * coastline. `opts` is an `ErosionOpts`; absent fields take their defaults.
+ * Returns the eroded copy of `h`.
+ * @param {any} mesh_js
+ * @param {Uint8Array} h
+ * @param {any} opts_js
+ * @returns {Uint8Array}
+ */
+export function erode_heightmap(mesh_js, h, opts_js) {
+    const ptr0 = passArray8ToWasm0(h, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.erode_heightmap(mesh_js, ptr0, len0, opts_js);
+    return ret;
+}
+
+/**
+ * `erode_heightmap` on the held grid's `cells.h`, in place, leaving locked
+ * cells alone. The coastline is unchanged, so entity indices are kept;
+ * follow with `recompute_dependents_h` to refresh climate, biomes and
+ * drainage.
+ *
+ * Exposed as `erode_heightmap_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {Uint8Array}
+ */
+export function erode_heightmap_h(opts_js) {
+    const ret = wasm.erode_heightmap_h(opts_js);
+    return ret;
+}
+
+/**
+ * Rasterize one layer of the held grid (`h`, `temp`, `prec`, `fl`, `biome`,
+ * `state`, `culture`, `religion`) at `opts.width × opts.height` pixels and
+ * return PNG bytes: 16-bit grayscale for continuous layers, paletted RGBA
+ * for categorical ones. `pack_js` (an `entities::Pack`, or `null`) supplies
+ * the state / culture / religion colors. Throws an `ExportError` on a bad
+ * size or unreadable options / pack.
+ *
+ * Exposed as `export_layer_png_h(opts, pack)` to JS.
+ * @param {any} opts_js
+ * @param {any} pack_js
+ * @returns {Uint8Array}
+ */
+export function export_layer_png_h(opts_js, pack_js) {
+    const ret = wasm.export_layer_png_h(opts_js, pack_js);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
  * Step 1.4: produce `cells.biome` (Uint8Array, `0..=12`, `0` = Marine/water)
  * from a deserialized `Mesh` + the climate `{ temp, prec }` + the heightmap
@@ -158,6 +346,25 @@ export function generate_heightmap(mesh_js, seed) {
     return ret;
 }
 
+/**
+ * `generate_heightmap` driven by a template (library id/name or template
+ * text) instead of the built-in default. Throws a `TemplateError` if the
+ * template is malformed.
+ * @param {any} mesh_js
+ * @param {number} seed
+ * @param {string} template
+ * @returns {Uint8Array}
+ */
+export function generate_heightmap_from_template(mesh_js, seed, template) {
+    const ptr0 = passStringToWasm0(template, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.generate_heightmap_from_template(mesh_js, seed, ptr0, len0);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
  * Step 1.1: generate a deterministic Voronoi mesh from `cell_count` seeded
  * points. Returns a `JsValue` with fields `{ points, cells, vertices }`
@@ -171,6 +378,36 @@ export function generate_mesh(cell_count, seed) {
     return ret;
 }
 
+/**
+ * Build a flat mesh over caller-supplied `[x, y]` seed points (no jitter —
+ * cell `i` sits exactly at `points[i]`). Throws `{ kind, ... }` describing
+ * the first problem if the points are rejected.
+ * @param {any} points_js
+ * @param {number} world_w
+ * @param {number} world_h
+ * @returns {any}
+ */
+export function generate_mesh_from_points(points_js, world_w, world_h) {
+    const ret = wasm.generate_mesh_from_points(points_js, world_w, world_h);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * `generate_mesh` for an explicit surface topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+ * Globe meshes additionally carry per-cell `lat_lon`.
+ * @param {number} cell_count
+ * @param {number} seed
+ * @param {any} topology_js
+ * @returns {any}
+ */
+export function generate_mesh_with_topology(cell_count, seed, topology_js) {
+    const ret = wasm.generate_mesh_with_topology(cell_count, seed, topology_js);
+    return ret;
+}
+
 /**
  * Phase 3 Step 3.2: generate states, provinces, and burgs for a fully-built
  * `Grid` (mesh + heightmap + climate + biomes + drainage). Returns a
@@ -192,6 +429,21 @@ export function generate_states(grid_js, seed, count) {
     return ret;
 }
 
+/**
+ * `generate_heightmap` by plate tectonics instead of template floods.
+ * Returns `{ h, plate, plates }`: the heightmap, each cell's plate index and
+ * the plates (`{ seed_cell, velocity, continental, area }`). `opts` is a
+ * `TectonicOpts`; absent fields take their defaults.
+ * @param {any} mesh_js
+ * @param {number} seed
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function generate_tectonics(mesh_js, seed, opts_js) {
+    const ret = wasm.generate_tectonics(mesh_js, seed, opts_js);
+    return ret;
+}
+
 /**
  * Phase 4.2: generate a deterministic `Timeline` from a year-0 `Pack` + cell
  * arrays + era bounds + seed. Each module (succession, war, plague, golden
@@ -203,9 +455,11 @@ export function generate_states(grid_js, seed, count) {
  * heightmap (`u8`, `< 20` = water). `params` is an optional `TimelineParams`
  * object (defaults if omitted). All RNG is `StdRng::seed_from_u64(seed)`.
  *
- * `mesh_js` is the optional Voronoi `Mesh` (or just its `cells` topology).
- * When provided, the war module's cell-neighbor lookups use the true
- * Delaunay adjacency instead of the legacy square-grid assumption.
+ * `mesh_js` is the Voronoi `Mesh` (or just its `cells` topology) — REQUIRED.
+ * The engine uses the true Delaunay adjacency from the mesh; there is no
+ * square-grid fallback. If `mesh_js` is undefined or fails to deserialize to
+ * a valid `Mesh`, this function panics (`expect`), which surfaces as a thrown
+ * JS error to the caller.
  *
  * Exposed as `generate_timeline(pack, cells_state, cells_culture, cells_religion,
  * cells_burg, cells_h, mesh, seed, params)` to JS.
@@ -248,6 +502,60 @@ export function generate_world(seed, cell_count, opts_js) {
     return ret;
 }
 
+/**
+ * `generate_world_with_topology` with the heightmap drawn from a template
+ * (library id/name such as `"archipelago"`, or template text). `topology`
+ * and `opts` fall back to `Flat` / defaults when absent. Throws a
+ * `TemplateError` if the template is malformed; otherwise the result is
+ * also stored as the held grid.
+ * @param {number} seed
+ * @param {number} cell_count
+ * @param {string} template
+ * @param {any} topology_js
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function generate_world_from_template(seed, cell_count, template, topology_js, opts_js) {
+    const ptr0 = passStringToWasm0(template, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.generate_world_from_template(seed, cell_count, ptr0, len0, topology_js, opts_js);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * `generate_world_with_topology` with a plate-tectonic heightmap
+ * (`tectonic_opts` a `TectonicOpts`). `topology` and both option objects
+ * fall back to `Flat` / defaults when absent. Like `generate_world`, the
+ * result is also stored as the held grid.
+ * @param {number} seed
+ * @param {number} cell_count
+ * @param {any} topology_js
+ * @param {any} tectonic_opts_js
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function generate_world_tectonic(seed, cell_count, topology_js, tectonic_opts_js, opts_js) {
+    const ret = wasm.generate_world_tectonic(seed, cell_count, topology_js, tectonic_opts_js, opts_js);
+    return ret;
+}
+
+/**
+ * `generate_world` on a mesh of the given topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+ * Like `generate_world`, the result is also stored as the held grid.
+ * @param {number} seed
+ * @param {number} cell_count
+ * @param {any} topology_js
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function generate_world_with_topology(seed, cell_count, topology_js, opts_js) {
+    const ret = wasm.generate_world_with_topology(seed, cell_count, topology_js, opts_js);
+    return ret;
+}
+
 /**
  * Step 2.5.6: compute river + lake geometry from the held Grid and return it
  * as a serde-encoded `{ rivers: RiverGeo[], lakes: LakeGeo[] }` object.
@@ -282,6 +590,91 @@ export function has_grid_h() {
     return ret !== 0;
 }
 
+/**
+ * The named heightmap template library (FMG's templates) as an array of
+ * `{ id, name, template }`. Any `id` or `name` can be passed wherever a
+ * template is accepted.
+ * @returns {any}
+ */
+export function heightmap_templates() {
+    const ret = wasm.heightmap_templates();
+    return ret;
+}
+
+/**
+ * The held grid's edit history as `{ cursor, steps: [{ label, cells }] }`,
+ * for a history panel.
+ *
+ * Exposed as `history_h()` to JS.
+ * @returns {any}
+ */
+export function history_h() {
+    const ret = wasm.history_h();
+    return ret;
+}
+
+/**
+ * Undo or redo until `step` edits are applied (`0` = before the oldest
+ * kept edit, `history_h().steps.length` = latest). Returns the new
+ * `cells.h`, or `undefined` if `step` is out of range.
+ *
+ * Exposed as `history_jump_h(step)` to JS.
+ * @param {number} step
+ * @returns {Uint8Array | undefined}
+ */
+export function history_jump_h(step) {
+    const ret = wasm.history_jump_h(step);
+    return ret;
+}
+
+/**
+ * Build a world whose heightmap comes from a grayscale image (PNG or PGM
+ * bytes, or a raw buffer described by `import_opts.raw`) instead of the
+ * generator: a fresh mesh of `topology` (default `Flat`), the image
+ * resampled onto it, then climate, biomes and drainage as in
+ * `generate_world`. Throws an `ImageError` (`{ kind, ... }`) if the image or
+ * `import_opts` is rejected; otherwise the result is also stored as the held
+ * grid.
+ * @param {Uint8Array} bytes
+ * @param {number} cell_count
+ * @param {number} seed
+ * @param {any} topology_js
+ * @param {any} import_opts_js
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function import_heightmap(bytes, cell_count, seed, topology_js, import_opts_js, opts_js) {
+    const ptr0 = passArray8ToWasm0(bytes, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.import_heightmap(ptr0, len0, cell_count, seed, topology_js, import_opts_js, opts_js);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
+/**
+ * Replace the held grid's heightmap with a grayscale image resampled onto
+ * its mesh, resetting entity indices like `reset_heightmap_h`. Returns the
+ * new `cells.h`; follow with `recompute_dependents_h` to refresh climate,
+ * biomes and drainage. Throws an `ImageError` (leaving the grid untouched)
+ * if the image or `import_opts` is rejected.
+ *
+ * Exposed as `import_heightmap_h(bytes, importOpts)` to JS.
+ * @param {Uint8Array} bytes
+ * @param {any} import_opts_js
+ * @returns {Uint8Array}
+ */
+export function import_heightmap_h(bytes, import_opts_js) {
+    const ptr0 = passArray8ToWasm0(bytes, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.import_heightmap_h(ptr0, len0, import_opts_js);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
  * Initialize the panic hook so Rust panics surface in the browser console
  * instead of silently failing. Called once on startup.
@@ -291,9 +684,65 @@ export function init() {
 }
 
 /**
- * Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Uses the
- * `cells.spacing` spatial grid + neighbor refinement. Returns the cell id
- * as a `u32`, or `-1` if the grid has no cells. O(1)-ish, deterministic.
+ * Köppen–Geiger class per cell of the held grid (ids into
+ * `koppen_table()`, `0` = ocean). With `opts.seasonal` the classes come from
+ * the monthly climate (`climate::generate_seasons`); otherwise from the held
+ * `cells.temp` / `cells.prec` alone (`koppen::classify_annual`), which can't
+ * tell dry seasons apart. Returns a `Uint8Array`.
+ *
+ * Exposed as `koppen_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function koppen_h(opts_js) {
+    const ret = wasm.koppen_h(opts_js);
+    return ret;
+}
+
+/**
+ * The Köppen–Geiger classes: `{ id, code, name, color }[]` in id order
+ * (`color` packed `0xRRGGBB`).
+ *
+ * Exposed as `koppen_table()` to JS.
+ * @returns {any}
+ */
+export function koppen_table() {
+    const ret = wasm.koppen_table();
+    return ret;
+}
+
+/**
+ * The held grid's lock mask, `1` per locked cell, for an overlay. Cells are
+ * locked and unlocked with `Lock` / `Unlock` ops through `edit_heightmap_h`,
+ * so locking is undoable and journaled like any edit.
+ *
+ * Exposed as `locked_h()` to JS.
+ * @returns {Uint8Array}
+ */
+export function locked_h() {
+    const ret = wasm.locked_h();
+    return ret;
+}
+
+/**
+ * Surface ocean currents of the held grid (`climate::ocean_currents`).
+ * Returns `{ flow: Float32Array, anomaly: Float32Array }`: the current per
+ * cell as interleaved `x, y` pairs (`y` south, zero on land), and the sea
+ * surface temperature anomaly (°C) with the share carried onto the coasts.
+ *
+ * Exposed as `ocean_currents_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function ocean_currents_h(opts_js) {
+    const ret = wasm.ocean_currents_h(opts_js);
+    return ret;
+}
+
+/**
+ * Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Exact search
+ * over the mesh spatial index. Returns the cell id as a `u32`, or `-1` if the
+ * grid has no cells. Deterministic (ties go to the lower cell id).
  *
  * Exposed as `pick_cell(grid, x, y)` to JS.
  * @param {any} grid_js
@@ -319,6 +768,41 @@ export function pick_cell_h(x, y) {
     return ret;
 }
 
+/**
+ * The wind passes behind the held grid's precipitation
+ * (`climate::generate_precipitation_traced`, over the held `cells.h` /
+ * `cells.temp`). Returns `{ wind: Float32Array, humidity: Float32Array,
+ * orographic: Float32Array }`: the prevailing wind per cell as interleaved
+ * `x, y` pairs (`y` south, length = strength), the humidity left in the air
+ * leaving the cell, and the orographic share of its rain.
+ *
+ * Exposed as `precipitation_trace_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function precipitation_trace_h(opts_js) {
+    const ret = wasm.precipitation_trace_h(opts_js);
+    return ret;
+}
+
+/**
+ * Project the held grid's climate at `target_year` (`timeline::project_climate`),
+ * to show alongside `WorldAt(target_year)`, under `drift_js`, the
+ * `ClimateKeyframe[]` the timeline was generated with
+ * (`TimelineParams.climate`). `undefined` or `[]` means no drift, and the
+ * year-0 climate comes back. Returns `{ year, temp_offset, prec_scale, temp:
+ * Int8Array, prec: Uint8Array, biome: Uint8Array }`.
+ *
+ * Exposed as `project_climate_h(targetYear, drift)` to JS.
+ * @param {number} target_year
+ * @param {any} drift_js
+ * @returns {any}
+ */
+export function project_climate_h(target_year, drift_js) {
+    const ret = wasm.project_climate_h(target_year, drift_js);
+    return ret;
+}
+
 /**
  * Phase 4.1: incremental forward scrubbing. Applies only the events in
  * `(prev_year, target_year]` to a `WorldAt`, mutating it in place and
@@ -432,6 +916,29 @@ export function recompute_dependents_h2(opts_js) {
     return ret;
 }
 
+/**
+ * Mid-stroke drainage update on the held grid: the river counterpart of
+ * `recompute_temp_biome_local_h`. `cellIds` are the cells edited since the
+ * last drainage pass; `rivers::update_drainage` recomputes only the
+ * catchments they can affect, starting from the drainage the last
+ * `recompute_dependents_h`/`_h2` or `get_drainage_geometry_h` left in
+ * `HELD_DRAINAGE` (or a full pass if there is none). Writes `fl`/`r`/`conf`
+ * back into the held grid.
+ *
+ * Returns `{ cells: Uint32Array, fl: Uint16Array, r: Uint16Array,
+ * conf: Uint16Array, rivers: RiverGeo[], lakes: LakeGeo[] }`: the
+ * recomputed cells with their new values (in `cells` order), and the full
+ * river and lake lists. River ids outside the recomputed cells don't change.
+ *
+ * Exposed as `recompute_drainage_local_h(cellIds)` to JS.
+ * @param {any} cell_ids_js
+ * @returns {any}
+ */
+export function recompute_drainage_local_h(cell_ids_js) {
+    const ret = wasm.recompute_drainage_local_h(cell_ids_js);
+    return ret;
+}
+
 /**
  * Step 2.5.2: Tier-1 local recompute of temp + biome for an affected cell
  * set. Runs `recompute_temp_local` then `recompute_biome_local` in place on
@@ -466,6 +973,36 @@ export function recompute_temp_biome_local_h(cell_ids_js, opts_js) {
     return ret;
 }
 
+/**
+ * Re-apply the latest undone edit. Returns the new `cells.h`, or
+ * `undefined` if there is nothing to redo. Dependent layers follow as for
+ * `undo_h`.
+ *
+ * Exposed as `redo_h()` to JS.
+ * @returns {Uint8Array | undefined}
+ */
+export function redo_h() {
+    const ret = wasm.redo_h();
+    return ret;
+}
+
+/**
+ * Refine a region of the Rust-side held grid at higher density. `region` is
+ * `{ kind: "Rect", x0, y0, x1, y1 }` or `{ kind: "Cells", cells }`. Returns a
+ * `RegionGrid` (`{ grid, origin, parent_cell }`), or `null` if the region is
+ * empty. The held grid is left untouched.
+ *
+ * Exposed as `refine_region_h(region, cellCount, seed)` to JS.
+ * @param {any} region_js
+ * @param {number} cell_count
+ * @param {number} seed
+ * @returns {any}
+ */
+export function refine_region_h(region_js, cell_count, seed) {
+    const ret = wasm.refine_region_h(region_js, cell_count, seed);
+    return ret;
+}
+
 /**
  * Release the held grid (drops it). Called when the worker is done with a
  * world or before loading a new one.
@@ -474,12 +1011,30 @@ export function release_grid_h() {
     wasm.release_grid_h();
 }
 
+/**
+ * Rebuild the world of an `EditJournal` (from `edit_journal_h`) and return
+ * it like `generate_world`, also storing it as the held grid; its edits
+ * carry over into the new grid's journal. Throws a `JournalError`
+ * (`{ kind, ... }`) on a generator version mismatch, a bad template or a
+ * checksum mismatch.
+ * @param {any} journal_js
+ * @returns {any}
+ */
+export function replay_edit_journal(journal_js) {
+    const ret = wasm.replay_edit_journal(journal_js);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return takeFromExternrefTable0(ret[0]);
+}
+
 /**
  * Step 2.5.4: reset `grid.cells.h` back to the original seeded heightmap.
  * Regenerates `h` from `grid.seed` + `grid.mesh` using the same
  * `heightmap::generate` used by `generate_world`. Also reinitializes the
  * entity index arrays (`state`/`province`/`culture`/`religion`/`burg`) to
  * their "unassigned" sentinels, since Reset means "discard all edits".
+ * Locked cells (`CellData::locked`) keep both their height and entities.
  * Returns the updated `Grid` as `JsValue`.
  *
  * Exposed as `reset_heightmap(grid)` to JS.
@@ -503,6 +1058,40 @@ export function reset_heightmap_h() {
     return ret;
 }
 
+/**
+ * Monthly climate of the held grid (`climate::generate_seasons`; set
+ * `opts.axial_tilt` for other worlds). Returns `{ temp: Int8Array[12],
+ * prec: Uint8Array[12], frost: Uint8Array, onset: Uint8Array }`: per-month
+ * cell layers, months below freezing per cell (snowbound passes), and the
+ * month the wet season arrives (`255` where there is none).
+ *
+ * Exposed as `seasons_h(opts)` to JS.
+ * @param {any} opts_js
+ * @returns {any}
+ */
+export function seasons_h(opts_js) {
+    const ret = wasm.seasons_h(opts_js);
+    return ret;
+}
+
+/**
+ * Ordered sea and lake shorelines of the held grid, each Chaikin-smoothed
+ * `smoothing` times (`0` = raw Voronoi edges; capped at
+ * `coast::MAX_SMOOTHING`). Traced on request only, from the lakes of the
+ * drainage kept in `HELD_DRAINAGE` (a full `rivers::compute_drainage` when
+ * there is none) numbered like `get_drainage_geometry_h`, so
+ * `Shoreline.feature` of a lake shore matches `LakeGeo.id` there. Returns
+ * `Shoreline[]`.
+ *
+ * Exposed as `shorelines_h(smoothing)` to JS.
+ * @param {number} smoothing
+ * @returns {any}
+ */
+export function shorelines_h(smoothing) {
+    const ret = wasm.shorelines_h(smoothing);
+    return ret;
+}
+
 /**
  * Store a Grid (deserialized from JS) into the Rust-side handle slot.
  * Replaces any previously held grid. The held grid is owned by Rust after
@@ -512,6 +1101,38 @@ export function reset_heightmap_h() {
 export function store_grid_h(grid_js) {
     wasm.store_grid_h(grid_js);
 }
+
+/**
+ * Undo the latest heightmap edit on the held grid (brush op, erosion,
+ * reset, template, tectonics or image import). Returns the restored
+ * `cells.h`, or `undefined` if there is nothing to undo. Entity indices
+ * come back too; climate, biomes and drainage are recomputed on their next
+ * read, or call `recompute_dependents_h` to get them all at once.
+ *
+ * Exposed as `undo_h()` to JS.
+ * @returns {Uint8Array | undefined}
+ */
+export function undo_h() {
+    const ret = wasm.undo_h();
+    return ret;
+}
+
+/**
+ * Check a heightmap template (library id/name or template text) without
+ * running it. Returns the step count, or throws a `{ kind, line, ... }`
+ * `TemplateError` for the first malformed line.
+ * @param {string} template
+ * @returns {number}
+ */
+export function validate_heightmap_template(template) {
+    const ptr0 = passStringToWasm0(template, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.validate_heightmap_template(ptr0, len0);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return ret[0] >>> 0;
+}
 function __wbg_get_imports() {
     const import0 = {
         __proto__: null,
@@ -523,6 +1144,13 @@ function __wbg_get_imports() {
             const ret = Number(arg0);
             return ret;
         },
+        __wbg_String_8564e559799eccda: function(arg0, arg1) {
+            const ret = String(arg1);
+            const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len1 = WASM_VECTOR_LEN;
+            getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+            getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+        },
         __wbg___wbindgen_bigint_get_as_i64_c4ecf48528083721: function(arg0, arg1) {
             const v = arg1;
             const ret = typeof(v) === 'bigint' ? v : undefined;
@@ -724,6 +1352,10 @@ function __wbg_get_imports() {
             const ret = new Int16Array(getArrayI16FromWasm0(arg0, arg1));
             return ret;
         },
+        __wbg_new_from_slice_709ab7061ebcc5da: function(arg0, arg1) {
+            const ret = new Float32Array(getArrayF32FromWasm0(arg0, arg1));
+            return ret;
+        },
         __wbg_new_from_slice_8aed4f0384605526: function(arg0, arg1) {
             const ret = new Uint32Array(getArrayU32FromWasm0(arg0, arg1));
             return ret;
@@ -757,6 +1389,10 @@ function __wbg_get_imports() {
         __wbg_prototypesetcall_e30a3abb428d3d47: function(arg0, arg1, arg2) {
             Int32Array.prototype.set.call(getArrayI32FromWasm0(arg0, arg1), arg2);
         },
+        __wbg_push_adb0107829f02d75: function(arg0, arg1) {
+            const ret = arg0.push(arg1);
+            return ret;
+        },
         __wbg_set_6be42768c690e380: function(arg0, arg1, arg2) {
             arg0[arg1] = arg2;
         },
@@ -891,6 +1527,11 @@ function debugString(val) {
     return className;
 }
 
+function getArrayF32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getFloat32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
 function getArrayI16FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getInt16ArrayMemory0().subarray(ptr / 2, ptr / 2 + len);
@@ -929,6 +1570,14 @@ function getDataViewMemory0() {
     return cachedDataViewMemory0;
 }
 
+let cachedFloat32ArrayMemory0 = null;
+function getFloat32ArrayMemory0() {
+    if (cachedFloat32ArrayMemory0 === null || cachedFloat32ArrayMemory0.byteLength === 0) {
+        cachedFloat32ArrayMemory0 = new Float32Array(wasm.memory.buffer);
+    }
+    return cachedFloat32ArrayMemory0;
+}
+
 let cachedInt16ArrayMemory0 = null;
 function getInt16ArrayMemory0() {
     if (cachedInt16ArrayMemory0 === null || cachedInt16ArrayMemory0.byteLength === 0) {
@@ -994,6 +1643,13 @@ function isLikeNone(x) {
     return x === undefined || x === null;
 }
 
+function passArray8ToWasm0(arg, malloc) {
+    const ptr = malloc(arg.length * 1, 1) >>> 0;
+    getUint8ArrayMemory0().set(arg, ptr / 1);
+    WASM_VECTOR_LEN = arg.length;
+    return ptr;
+}
+
 function passStringToWasm0(arg, malloc, realloc) {
     if (realloc === undefined) {
         const buf = cachedTextEncoder.encode(arg);
@@ -1031,6 +1687,12 @@ function passStringToWasm0(arg, malloc, realloc) {
     return ptr;
 }
 
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_externrefs.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
+}
+
 let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
 cachedTextDecoder.decode();
 const MAX_SAFARI_DECODE_BYTES = 2146435072;
@@ -1066,6 +1728,7 @@ function __wbg_finalize_init(instance, module) {
     wasm = instance.exports;
     wasmModule = module;
     cachedDataViewMemory0 = null;
+    cachedFloat32ArrayMemory0 = null;
     cachedInt16ArrayMemory0 = null;
     cachedInt32ArrayMemory0 = null;
     cachedInt8ArrayMemory0 = null;
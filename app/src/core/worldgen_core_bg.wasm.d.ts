@@ -2,39 +2,78 @@
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
 export const add: (a: number, b: number) => number;
+export const apply_heightmap_template_h: (a: number, b: number) => [number, number, number];
+export const apply_tectonics_h: (a: any) => any;
 export const build_grid_with_heightmap: (a: any, b: number) => any;
+export const cell_outlines: (a: any, b: any) => any;
+export const cell_outlines_h: (a: any) => any;
+export const cells_in_polygon_h: (a: any) => any;
+export const cells_in_radius_h: (a: number, b: number, c: number) => any;
+export const cells_in_rect_h: (a: number, b: number, c: number, d: number) => any;
+export const clear_history_h: () => void;
 export const edit_heightmap: (a: any, b: any) => any;
 export const edit_heightmap_h: (a: any) => any;
+export const edit_journal_h: () => any;
+export const erode_heightmap: (a: any, b: number, c: number, d: any) => any;
+export const erode_heightmap_h: (a: any) => any;
+export const export_layer_png_h: (a: any, b: any) => [number, number, number];
 export const generate_biomes: (a: any, b: any, c: any) => any;
 export const generate_biomes_for_grid: (a: any) => any;
 export const generate_climate: (a: any, b: any, c: any) => any;
 export const generate_climate_for_grid: (a: any, b: any) => any;
 export const generate_cultures_religions: (a: any, b: number, c: number, d: number, e: any) => any;
 export const generate_heightmap: (a: any, b: number) => any;
+export const generate_heightmap_from_template: (a: any, b: number, c: number, d: number) => [number, number, number];
 export const generate_mesh: (a: number, b: number) => any;
+export const generate_mesh_from_points: (a: any, b: number, c: number) => [number, number, number];
+export const generate_mesh_with_topology: (a: number, b: number, c: any) => any;
 export const generate_states: (a: any, b: number, c: number) => any;
+export const generate_tectonics: (a: any, b: number, c: any) => any;
 export const generate_timeline: (a: any, b: any, c: any, d: any, e: any, f: any, g: any, h: any, i: bigint, j: any) => any;
 export const generate_world: (a: number, b: number, c: any) => any;
+export const generate_world_from_template: (a: number, b: number, c: number, d: number, e: any, f: any) => [number, number, number];
+export const generate_world_tectonic: (a: number, b: number, c: any, d: any, e: any) => any;
+export const generate_world_with_topology: (a: number, b: number, c: any, d: any) => any;
 export const get_drainage_geometry_h: () => any;
 export const has_grid_h: () => number;
+export const heightmap_templates: () => any;
+export const history_h: () => any;
+export const history_jump_h: (a: number) => any;
+export const import_heightmap: (a: number, b: number, c: number, d: number, e: any, f: any, g: any) => [number, number, number];
+export const import_heightmap_h: (a: number, b: number, c: any) => [number, number, number];
 export const init: () => void;
+export const koppen_h: (a: any) => any;
+export const koppen_table: () => any;
+export const locked_h: () => any;
+export const ocean_currents_h: (a: any) => any;
 export const pick_cell: (a: any, b: number, c: number) => number;
 export const pick_cell_h: (a: number, b: number) => number;
+export const precipitation_trace_h: (a: any) => any;
+export const project_climate_h: (a: number, b: any) => any;
 export const project_delta: (a: any, b: any, c: number, d: number) => any;
 export const project_world: (a: any, b: any, c: any, d: any, e: any, f: any, g: number) => any;
 export const recompute_dependents: (a: any, b: any) => any;
 export const recompute_dependents_h: (a: any) => any;
 export const recompute_dependents_h2: (a: any) => any;
+export const recompute_drainage_local_h: (a: any) => any;
 export const recompute_temp_biome_local: (a: any, b: any, c: any) => any;
 export const recompute_temp_biome_local_h: (a: any, b: any) => any;
+export const redo_h: () => any;
+export const refine_region_h: (a: any, b: number, c: number) => any;
 export const release_grid_h: () => void;
+export const replay_edit_journal: (a: any) => [number, number, number];
 export const reset_heightmap: (a: any) => any;
 export const reset_heightmap_h: () => any;
+export const seasons_h: (a: any) => any;
+export const shorelines_h: (a: number) => any;
 export const store_grid_h: (a: any) => void;
+export const undo_h: () => any;
+export const validate_heightmap_template: (a: number, b: number) => [number, number, number];
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
 export const __wbindgen_exn_store: (a: number) => void;
 export const __externref_table_alloc: () => number;
 export const __wbindgen_externrefs: WebAssembly.Table;
 export const __wbindgen_free: (a: number, b: number, c: number) => void;
+export const __externref_table_dealloc: (a: number) => void;
 export const __wbindgen_start: () => void;
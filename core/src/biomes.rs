@@ -57,63 +57,79 @@ const MIN_LAND_HEIGHT: u8 = 20;
 pub struct BiomeDef {
     pub id: u8,
     pub name: &'static str,
+    /// FMG default fill, packed `0xRRGGBB` like the entity colors.
+    pub color: u32,
 }
 
-/// 13 biomes, FMG `getDefaultBiomes()` names in id order. Retained as a table
-/// for the renderer (color/habitability/icon-density) in later phases.
+/// 13 biomes, FMG `getDefaultBiomes()` names and colors in id order. Retained
+/// as a table for the renderer (habitability/icon-density) in later phases;
+/// the colors already drive `raster` layer export.
 #[allow(dead_code)]
 pub const BIOMES: [BiomeDef; 13] = [
     BiomeDef {
         id: 0,
         name: "Marine",
+        color: 0x466eab,
     },
     BiomeDef {
         id: 1,
         name: "Hot desert",
+        color: 0xfbe79f,
     },
     BiomeDef {
         id: 2,
         name: "Cold desert",
+        color: 0xb5b887,
     },
     BiomeDef {
         id: 3,
         name: "Savanna",
+        color: 0xd2d082,
     },
     BiomeDef {
         id: 4,
         name: "Grassland",
+        color: 0xc8d68f,
     },
     BiomeDef {
         id: 5,
         name: "Tropical seasonal forest",
+        color: 0xb6d95d,
     },
     BiomeDef {
         id: 6,
         name: "Temperate deciduous forest",
+        color: 0x29bc56,
     },
     BiomeDef {
         id: 7,
         name: "Tropical rainforest",
+        color: 0x7dcb35,
     },
     BiomeDef {
         id: 8,
         name: "Temperate rainforest",
+        color: 0x409c43,
     },
     BiomeDef {
         id: 9,
         name: "Taiga",
+        color: 0x4b6b32,
     },
     BiomeDef {
         id: 10,
         name: "Tundra",
+        color: 0x96784b,
     },
     BiomeDef {
         id: 11,
         name: "Glacier",
+        color: 0xd5e7eb,
     },
     BiomeDef {
         id: 12,
         name: "Wetland",
+        color: 0x0b9131,
     },
 ];
 
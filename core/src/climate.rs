@@ -43,12 +43,21 @@
 //!    westerlies). Prevailing winds (seeded from `options.winds[tier]`) blow
 //!    across the spacing grid; moisture is deposited on windward slopes
 //!    (orographic) and picked up over water (FMG `passWind`/`getPrecipitation`).
+//!    With `solver: "mesh"` the same rules instead advect moisture along the
+//!    Voronoi adjacency, so no spacing-grid rows show through.
+//! 4. `generate_seasons` (optional) — twelve monthly temperature and
+//!    precipitation fields. The latitude bands follow the sun as the axial
+//!    tilt dictates; with `seasonal` set, their mean becomes the annual
+//!    `prec`.
+//! 5. `ocean_currents` (optional) — wind-driven gyres and boundary currents
+//!    carry warm or cold water along the coasts; with `currents` set, the
+//!    land next to them is warmed or cooled, and wetted or dried, to match.
 
 use js_sys::{Int8Array, Object, Uint8Array};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-use crate::mesh::Mesh;
+use crate::mesh::{Mesh, Topology};
 
 /// Sea level in the height scale. `< 20` is water (heightmap `SEA_LEVEL`).
 pub const SEA_LEVEL: u8 = 20;
@@ -68,9 +77,12 @@ const LATITUDE_MODIFIER_MEAN: f64 = 33.5 / 18.0;
 /// height; above it the wind is blocked and dumps all remaining humidity.
 const MAX_PASSABLE_ELEVATION: f64 = 85.0;
 
+/// Sweeps of the mesh precipitation solver (`precipitation_mesh`).
+const MESH_SWEEPS: usize = 3;
+
 /// Climate options. Defaults mirror FMG's `options` (`public/main.js` /
 /// `src/index.html`). All fields are optional on the wire via `#[serde(default)]`.
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(default)]
 pub struct ClimateOpts {
     /// Map size as % of the world (`options.mapSize`). Drives `latT`.
@@ -92,6 +104,30 @@ pub struct ClimateOpts {
     pub temperature_south_pole: f64,
     /// Prevailing wind direction per 30° tier, N→S (`options.winds`).
     pub winds: Vec<f64>,
+    /// Axial tilt in degrees (Earth: 23.44). Sets how far the sun, and with
+    /// it the temperature and rain belts, moves over the year.
+    pub axial_tilt: f64,
+    /// Derive the annual `prec` from the monthly model (`generate_seasons`)
+    /// instead of a single annual pass.
+    pub seasonal: bool,
+    /// Run the surface-current model (`ocean_currents`) and let it warm or
+    /// cool, and wet or dry, the land along the coasts.
+    pub currents: bool,
+    /// How `generate_precipitation` carries moisture across the map.
+    pub solver: PrecipitationSolver,
+}
+
+/// Precipitation solver (`ClimateOpts::solver`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrecipitationSolver {
+    /// FMG's wind passes along the rows and columns of the `cells.spacing`
+    /// grid. At high cell counts its rows show as straight rain bands.
+    #[default]
+    Grid,
+    /// Moisture advected along the Voronoi adjacency (`cells.c`), following
+    /// each cell's wind (`precipitation_mesh`).
+    Mesh,
 }
 
 impl Default for ClimateOpts {
@@ -106,6 +142,10 @@ impl Default for ClimateOpts {
             temperature_north_pole: -30.0,
             temperature_south_pole: -15.0,
             winds: vec![225.0, 45.0, 225.0, 315.0, 135.0, 315.0],
+            axial_tilt: 23.44,
+            seasonal: false,
+            currents: false,
+            solver: PrecipitationSolver::Grid,
         }
     }
 }
@@ -151,6 +191,22 @@ pub fn calculate_map_coordinates(opts: &ClimateOpts) -> MapCoords {
     }
 }
 
+/// `calculate_map_coordinates`, aware of the mesh topology. A
+/// `Topology::Globe` mesh always spans the whole planet — its `y` is the
+/// equirectangular projection of the cell's true latitude — so the
+/// `mapSize`/`latitude` band options don't apply and the band is fixed at
+/// 90°N..90°S. Flat meshes defer to the options.
+pub fn calculate_mesh_coordinates(mesh: &Mesh, opts: &ClimateOpts) -> MapCoords {
+    match mesh.topology {
+        Topology::Globe => MapCoords {
+            lat_t: 180.0,
+            lat_n: 90.0,
+            lat_s: -90.0,
+        },
+        Topology::Flat | Topology::Cylinder => calculate_map_coordinates(opts),
+    }
+}
+
 /// Latitude (degrees) for a given world `y`. FMG: `latN − (y / graphHeight) *
 /// latT`. This is the technical-requirements §2 latitude formula.
 fn latitude_at_y(y: f64, world_h: f64, coords: &MapCoords) -> f64 {
@@ -280,7 +336,7 @@ pub fn calculate_temperatures(
                     // in-worker and calls a single combined entry; delete the
                     // standalone if no VertX caller materializes.
 pub fn recompute_temp_local(grid: &mut crate::grid::Grid, cell_ids: &[u32], opts: &ClimateOpts) {
-    let coords = calculate_map_coordinates(opts);
+    let coords = calculate_mesh_coordinates(&grid.mesh, opts);
     recompute_temp_local_with_coords(grid, cell_ids, opts, &coords);
 }
 
@@ -335,6 +391,69 @@ fn slot_cell(slot: isize, spacing: &[u32], n: usize) -> Option<usize> {
     Some(c)
 }
 
+/// Advance a wind pass one slot. Vertical steps (`±cells_x`) and flat maps
+/// use plain slot arithmetic (stepping off the grid ends the pass via
+/// `slot_cell`). With `wrap_cols > 0` a horizontal step (`±1`) wraps within
+/// the row, so an east–west-periodic map is one continuous band.
+fn step_slot(current: isize, next: isize, wrap_cols: usize) -> isize {
+    if wrap_cols == 0 || next.abs() != 1 {
+        return current + next;
+    }
+    let cols = wrap_cols as isize;
+    let row = current.div_euclid(cols);
+    row * cols + (current.rem_euclid(cols) + next).rem_euclid(cols)
+}
+
+/// Start slot of a wrapping horizontal pass over `row`. With no edge to start
+/// from, the wind starts where it leaves land for open sea (the first water
+/// slot whose upwind slot is land, scanning from the row's western end), so
+/// it crosses the whole ocean gathering moisture before the next landfall.
+/// All-water / all-land rows start at the upwind edge as on a flat map.
+fn wrap_start_slot(row: usize, cells_x: usize, next: isize, h: &[u8], spacing: &[u32]) -> isize {
+    let n = h.len();
+    let row_start = (row * cells_x) as isize;
+    let is_water = |slot: isize| slot_cell(slot, spacing, n).is_some_and(|c| h[c] < SEA_LEVEL);
+    for col in 0..cells_x {
+        let slot = row_start + col as isize;
+        let upwind = step_slot(slot, -next, cells_x);
+        if is_water(slot) && !is_water(upwind) {
+            return slot;
+        }
+    }
+    if next > 0 {
+        row_start
+    } else {
+        row_start + cells_x as isize - 1
+    }
+}
+
+/// What the wind passes did at each cell, beyond the `prec` they leave
+/// (`generate_precipitation_traced`).
+#[derive(Serialize, Clone, Debug)]
+pub struct PrecipitationTrace {
+    /// Sum of the passes blowing across the cell, in world axes (`y` points
+    /// south). Each pass counts with its moisture capacity relative to a
+    /// horizontal pass at a `latitudeModifier` of 1, so the length is the
+    /// wind's strength and the direction the prevailing one.
+    pub wind: Vec<[f32; 2]>,
+    /// Humidity the passes carry on when they leave the cell. Low downwind of
+    /// a range: the rain shadow.
+    pub humidity: Vec<f32>,
+    /// The part of the cell's deposit wrung out by climbing terrain (or
+    /// dumped against an impassable wall), on the `prec` scale and uncapped.
+    pub orographic: Vec<f32>,
+}
+
+impl PrecipitationTrace {
+    fn new(n: usize) -> Self {
+        PrecipitationTrace {
+            wind: vec![[0.0; 2]; n],
+            humidity: vec![0.0; n],
+            orographic: vec![0.0; n],
+        }
+    }
+}
+
 /// One wind pass from a single source (FMG `passWind` body), running in slot
 /// space.
 ///
@@ -344,6 +463,9 @@ fn slot_cell(slot: isize, spacing: &[u32], n: usize) -> Option<usize> {
 /// `latitudeModifier`; it is `None` for vertical (monsoon) winds, which use a
 /// pre-scaled `base_max_prec`.
 ///
+/// With a `trace`, also records the pass's wind, the humidity it carries on
+/// and its orographic deposit at each cell it crosses.
+///
 /// **Determinism note:** FMG's coastal branch uses `humidity / rand(10, 20)`
 /// (non-deterministic). We use the fixed midpoint `15.0`. This is the only
 /// deviation from FMG behavior and it is required by the determinism contract.
@@ -359,11 +481,28 @@ fn pass_wind_one(
     next: isize,
     steps: usize,
     modifier: f64,
+    wrap_cols: usize,
+    mut trace: Option<&mut PrecipitationTrace>,
 ) {
     let max_prec = match lat_mod {
         Some(lm) => (base_max_prec * lm).min(255.0),
         None => base_max_prec.min(255.0),
     };
+    // The pass's direction in world axes, weighted by its capacity.
+    let strength = (max_prec / (120.0 * modifier)) as f32;
+    let wind = if next.abs() == 1 {
+        [next.signum() as f32 * strength, 0.0]
+    } else {
+        [0.0, next.signum() as f32 * strength]
+    };
+    let mut record = |c: usize, humidity: f64, orographic: f64| {
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.wind[c][0] += wind[0];
+            trace.wind[c][1] += wind[1];
+            trace.humidity[c] += humidity as f32;
+            trace.orographic[c] += orographic as f32;
+        }
+    };
 
     let Some(start_c) = slot_cell(start_slot, spacing, prec.len()) else {
         return;
@@ -381,10 +520,11 @@ fn pass_wind_one(
         };
         // No flux through permafrost (FMG: `if cells.temp[current] < -5 continue`).
         if temp[c] < -5 {
-            current += next;
+            record(c, humidity, 0.0);
+            current = step_slot(current, next, wrap_cols);
             continue;
         }
-        let next_slot = current + next;
+        let next_slot = step_slot(current, next, wrap_cols);
         let next_c = slot_cell(next_slot, spacing, prec.len());
 
         if h[c] < SEA_LEVEL {
@@ -402,7 +542,8 @@ fn pass_wind_one(
                     prec[c] = (prec[c] as f64 + 5.0 * modifier).min(255.0) as u8;
                 }
             }
-            current += next;
+            record(c, humidity, 0.0);
+            current = step_slot(current, next, wrap_cols);
             continue;
         }
 
@@ -419,13 +560,17 @@ fn pass_wind_one(
             humidity
         };
         prec[c] = (prec[c] as f64 + precipitation).min(255.0) as u8;
+        // Anything beyond the flat-ground loss of `get_precipitation`.
+        let flat = (humidity / (10.0 * modifier)).max(1.0);
+        let orographic = (precipitation - flat).max(0.0);
         let evaporation = if precipitation > 1.5 { 1.0 } else { 0.0 };
         humidity = if is_passable {
             (humidity - precipitation + evaporation).clamp(0.0, max_prec)
         } else {
             0.0
         };
-        current += next;
+        record(c, humidity, orographic);
+        current = step_slot(current, next, wrap_cols);
     }
 }
 
@@ -447,6 +592,23 @@ fn wind_directions(angle: f64) -> WindFlags {
     }
 }
 
+/// FMG `Math.abs(lat - 89) / 30`: the `options.winds` tier of a latitude,
+/// 0..=5 from the north pole southward. Tier `k` spans latitudes
+/// `89 − 30(k + 1)..89 − 30k`.
+fn wind_tier(lat: f64) -> usize {
+    clamp(((lat - 89.0).abs() / 30.0).floor(), 0.0, 5.0) as usize
+}
+
+/// Prevailing wind of a tier: the compass bearing (degrees) it blows toward.
+fn tier_angle(tier: usize, opts: &ClimateOpts) -> f64 {
+    opts.winds.get(tier).copied().unwrap_or(225.0)
+}
+
+/// Prevailing wind at a latitude.
+fn wind_angle(lat: f64, opts: &ClimateOpts) -> f64 {
+    tier_angle(wind_tier(lat), opts)
+}
+
 /// Compute `cells.prec` (FMG `generatePrecipitation`).
 pub fn generate_precipitation(
     mesh: &Mesh,
@@ -454,6 +616,48 @@ pub fn generate_precipitation(
     temp: &[i8],
     opts: &ClimateOpts,
     coords: &MapCoords,
+) -> Vec<u8> {
+    precipitation(mesh, h, temp, opts, coords, None)
+}
+
+/// `generate_precipitation`, also returning what the wind passes did at each
+/// cell: its prevailing wind, the humidity left in the air and the
+/// orographic share of its rain. The `prec` is identical.
+pub fn generate_precipitation_traced(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    opts: &ClimateOpts,
+    coords: &MapCoords,
+) -> (Vec<u8>, PrecipitationTrace) {
+    let mut trace = PrecipitationTrace::new(mesh.points.len());
+    let prec = precipitation(mesh, h, temp, opts, coords, Some(&mut trace));
+    (prec, trace)
+}
+
+fn precipitation(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    opts: &ClimateOpts,
+    coords: &MapCoords,
+    trace: Option<&mut PrecipitationTrace>,
+) -> Vec<u8> {
+    match opts.solver {
+        PrecipitationSolver::Grid => precipitation_grid(mesh, h, temp, opts, coords, trace),
+        PrecipitationSolver::Mesh => precipitation_mesh(mesh, h, temp, opts, coords, trace),
+    }
+}
+
+/// The FMG solver: wind passes along the rows and columns of the
+/// `cells.spacing` grid.
+fn precipitation_grid(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    opts: &ClimateOpts,
+    coords: &MapCoords,
+    mut trace: Option<&mut PrecipitationTrace>,
 ) -> Vec<u8> {
     let n = mesh.points.len();
     let cells_x = mesh.cells.cells_x as usize;
@@ -462,11 +666,15 @@ pub fn generate_precipitation(
     let slots = cells_x * cells_y;
 
     let modifier = ((n as f64) / 10000.0).powf(0.25) * (opts.prec / 100.0);
+    // On maps that wrap east–west the horizontal passes run a full loop of the
+    // row instead of starting at the map edge.
+    let wrap_cols = if mesh.topology.wraps_x() { cells_x } else { 0 };
 
     let mut prec = vec![0u8; n];
 
     // Horizontal winds: one source per row at the western (westerly) and
-    // eastern (easterly) edge. Mirror FMG's per-row wind-direction setup.
+    // eastern (easterly) edge (or, on wrapping maps, at `wrap_start_slot`).
+    // Mirror FMG's per-row wind-direction setup.
     let mut northerly: i64 = 0;
     let mut southerly: i64 = 0;
 
@@ -475,13 +683,15 @@ pub fn generate_precipitation(
         let lat = coords.lat_n - (row as f64 / cells_y as f64) * coords.lat_t;
         let lat_band = clamp(((lat.abs() - 1.0) / 5.0).floor(), 0.0, 17.0) as usize;
         let lat_mod = LATITUDE_MODIFIER[lat_band];
-        // FMG: Math.abs(lat - 89) / 30 — distance from north pole, tiers 0..5 pole-to-equator.
-        let wind_tier = clamp(((lat - 89.0).abs() / 30.0).floor(), 0.0, 5.0) as usize;
-        let angle = opts.winds.get(wind_tier).copied().unwrap_or(225.0);
+        let angle = wind_angle(lat, opts);
         let flags = wind_directions(angle);
 
         if flags.is_west {
-            let start = (row * cells_x) as isize;
+            let start = if wrap_cols > 0 {
+                wrap_start_slot(row, cells_x, 1, h, spacing)
+            } else {
+                (row * cells_x) as isize
+            };
             pass_wind_one(
                 &mut prec,
                 h,
@@ -493,10 +703,16 @@ pub fn generate_precipitation(
                 1,
                 cells_x,
                 modifier,
+                wrap_cols,
+                trace.as_deref_mut(),
             );
         }
         if flags.is_east {
-            let start = (row * cells_x + cells_x - 1) as isize;
+            let start = if wrap_cols > 0 {
+                wrap_start_slot(row, cells_x, -1, h, spacing)
+            } else {
+                (row * cells_x + cells_x - 1) as isize
+            };
             pass_wind_one(
                 &mut prec,
                 h,
@@ -508,6 +724,8 @@ pub fn generate_precipitation(
                 -1,
                 cells_x,
                 modifier,
+                wrap_cols,
+                trace.as_deref_mut(),
             );
         }
         if flags.is_north {
@@ -543,6 +761,8 @@ pub fn generate_precipitation(
                 cells_x as isize,
                 cells_y,
                 modifier,
+                0,
+                trace.as_deref_mut(),
             );
         }
     }
@@ -568,6 +788,8 @@ pub fn generate_precipitation(
                 -(cells_x as isize),
                 cells_y,
                 modifier,
+                0,
+                trace.as_deref_mut(),
             );
         }
     }
@@ -578,16 +800,603 @@ pub fn generate_precipitation(
     prec
 }
 
+/// The mesh solver: moisture advected along the Voronoi adjacency.
+///
+/// Each cell's wind is its tier's `options.winds` bearing as a vector, with
+/// the horizontal passes' capacity (`120 × modifier × latitudeModifier`).
+/// A cell takes in the humidity leaving its upwind neighbours (those the
+/// wind blows from, weighted by how squarely), then deposits and picks up
+/// moisture by the rules of `pass_wind_one`: over open water it gains
+/// `5 × modifier`, on land it loses `get_precipitation` against the height
+/// of its downwind neighbours (everything, against a wall above
+/// `MAX_PASSABLE_ELEVATION`), and a coast fed from the sea gets the coastal
+/// deposit. Cells with no upwind neighbour (the map edge) start with a full
+/// load, less their height.
+///
+/// Cells run upwind first (ordered by position along their own wind). Air
+/// crossing into another wind tier, or around a wrapping map, can arrive
+/// from a cell that runs later, so the solver sweeps `MESH_SWEEPS` times and
+/// keeps the last sweep's deposits. There are no separate monsoon passes:
+/// the winds' north–south component carries the moisture instead.
+fn precipitation_mesh(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    opts: &ClimateOpts,
+    coords: &MapCoords,
+    mut trace: Option<&mut PrecipitationTrace>,
+) -> Vec<u8> {
+    let n = mesh.points.len();
+    let modifier = ((n as f64) / 10000.0).powf(0.25) * (opts.prec / 100.0);
+    let (wind, capacity): (Vec<[f64; 2]>, Vec<f64>) = (0..n)
+        .map(|c| {
+            let lat = latitude_at_y(mesh.points[c][1], mesh.world_h, coords);
+            let band = clamp(((lat.abs() - 1.0) / 5.0).floor(), 0.0, 17.0) as usize;
+            let angle = wind_angle(lat, opts).to_radians();
+            let capacity = (120.0 * modifier * LATITUDE_MODIFIER[band]).min(255.0);
+            ([angle.sin(), -angle.cos()], capacity)
+        })
+        .unzip();
+    // How squarely the wind at `c` blows from `from` to `to`.
+    let alignment = |c: usize, from: usize, to: usize| {
+        let ([x0, y0], [x1, y1]) = (mesh.points[from], mesh.points[to]);
+        let d = [mesh.delta_x(x0, x1), y1 - y0];
+        let len = d[0].hypot(d[1]).max(f64::EPSILON);
+        (d[0] * wind[c][0] + d[1] * wind[c][1]) / len
+    };
+
+    // Per cell: its upwind neighbours with their weights, and the weighted
+    // height of its downwind ones (`None` at the map edge).
+    let upwind: Vec<Vec<(usize, f64)>> = (0..n)
+        .map(|c| {
+            let nbs = mesh.cells.neighbors_of_cell(c).iter().map(|&d| d as usize);
+            nbs.filter_map(|d| {
+                let a = alignment(c, d, c);
+                (a > 0.0).then_some((d, a))
+            })
+            .collect()
+        })
+        .collect();
+    let downwind: Vec<Option<f64>> = (0..n)
+        .map(|c| {
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for &d in mesh.cells.neighbors_of_cell(c) {
+                let a = alignment(c, c, d as usize);
+                if a > 0.0 {
+                    sum += a * h[d as usize] as f64;
+                    weight += a;
+                }
+            }
+            (weight > 0.0).then(|| sum / weight)
+        })
+        .collect();
+    let along = |c: usize| mesh.points[c][0] * wind[c][0] + mesh.points[c][1] * wind[c][1];
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| along(a).total_cmp(&along(b)).then(a.cmp(&b)));
+
+    // Humidity leaving each cell, and the last sweep's deposits.
+    let mut leaving = vec![0.0; n];
+    let mut deposit = vec![0.0; n];
+    for sweep in 0..MESH_SWEEPS {
+        let last = sweep + 1 == MESH_SWEEPS;
+        for &c in &order {
+            let max_prec = capacity[c];
+            let weight: f64 = upwind[c].iter().map(|&(_, a)| a).sum();
+            let humidity = if weight > 0.0 {
+                upwind[c].iter().map(|&(d, a)| a * leaving[d]).sum::<f64>() / weight
+            } else {
+                max_prec - h[c] as f64
+            }
+            .clamp(0.0, max_prec);
+
+            let (mut rain, mut orographic) = (0.0, 0.0);
+            leaving[c] = if temp[c] < -5 {
+                // No flux through permafrost.
+                humidity
+            } else if h[c] < SEA_LEVEL {
+                rain = 5.0 * modifier;
+                (humidity + 5.0 * modifier).min(max_prec)
+            } else {
+                if upwind[c].iter().any(|&(d, _)| h[d] < SEA_LEVEL) {
+                    rain += (humidity / 15.0).max(1.0);
+                }
+                let h_next = downwind[c].filter(|&hn| hn <= MAX_PASSABLE_ELEVATION);
+                let precipitation = match h_next {
+                    Some(hn) => get_precipitation(humidity, h[c], hn.round() as u8, modifier),
+                    None => humidity,
+                };
+                rain += precipitation;
+                orographic = (precipitation - (humidity / (10.0 * modifier)).max(1.0)).max(0.0);
+                let evaporation = if precipitation > 1.5 { 1.0 } else { 0.0 };
+                match h_next {
+                    Some(_) => (humidity - precipitation + evaporation).clamp(0.0, max_prec),
+                    None => 0.0,
+                }
+            };
+            if last {
+                deposit[c] = rain;
+                if let Some(trace) = trace.as_deref_mut() {
+                    let strength = max_prec / (120.0 * modifier);
+                    trace.wind[c] = [
+                        (wind[c][0] * strength) as f32,
+                        (wind[c][1] * strength) as f32,
+                    ];
+                    trace.humidity[c] = leaving[c] as f32;
+                    trace.orographic[c] = orographic as f32;
+                }
+            }
+        }
+    }
+    deposit.iter().map(|&p| p.min(255.0) as u8).collect()
+}
+
 /// Public entry: run the full climate pipeline and return `{ temp, prec }` as
 /// a JS object of typed arrays. `heightmap` is the `cells.h` array (0..=100,
 /// `< 20` = water) produced by the heightmap generator (Step 1.2).
+///
+/// With `opts.seasonal` the precipitation is the mean of the monthly
+/// `generate_seasons` passes. Temperature is the same either way: the monthly
+/// swings average out to the annual value. With `opts.currents` the coastal
+/// land takes on the warmth or chill of the sea off it (`ocean_currents`).
 pub fn generate_climate(mesh: &Mesh, heightmap: &[u8], opts: &ClimateOpts) -> (Vec<i8>, Vec<u8>) {
-    let coords = calculate_map_coordinates(opts);
-    let temp = calculate_temperatures(mesh, heightmap, opts, &coords);
-    let prec = generate_precipitation(mesh, heightmap, &temp, opts, &coords);
+    let currents = opts.currents.then(|| ocean_currents(mesh, heightmap, opts));
+    let coords = calculate_mesh_coordinates(mesh, opts);
+    let mut temp = calculate_temperatures(mesh, heightmap, opts, &coords);
+    if let Some(currents) = &currents {
+        currents.warm_land(heightmap, &mut temp);
+    }
+    let prec = if opts.seasonal {
+        seasons(mesh, heightmap, opts, currents.as_ref()).annual_prec()
+    } else {
+        let mut prec = generate_precipitation(mesh, heightmap, &temp, opts, &coords);
+        if let Some(currents) = &currents {
+            currents.wet_land(heightmap, &mut prec);
+        }
+        prec
+    };
     (temp, prec)
 }
 
+// ---------------------------------------------------------------------------
+// Seasons
+// ---------------------------------------------------------------------------
+
+/// Months in the seasonal year. Month 0 is January.
+pub const MONTHS: usize = 12;
+
+/// Where in the year (in months) the climate crosses its mean on the way to
+/// northern summer: the March equinox plus about a month of thermal lag, so
+/// July and January are the extreme months.
+const SPRING: f64 = 3.65;
+
+/// Share of the sun's declination the rain belts (ITCZ, horse latitudes,
+/// westerlies) follow.
+const BELT_SHIFT: f64 = 0.5;
+
+/// How much a fully marine cell damps the seasonal temperature swing.
+const MARINE_DAMPING: f64 = 0.6;
+
+/// Monthly precipitation spread (wettest over driest) below which a cell has
+/// no wet season.
+const WET_SEASON_RATIO: f64 = 2.0;
+
+/// `Seasons::wet_season_onset` for cells without a wet season.
+pub const NO_WET_SEASON: u8 = u8::MAX;
+
+/// Per-month climate from `generate_seasons`.
+#[derive(Serialize, Clone, Debug)]
+pub struct Seasons {
+    /// `temp[month][cell]`, °C.
+    pub temp: Vec<Vec<i8>>,
+    /// `prec[month][cell]`, on the annual `cells.prec` scale (the rainfall
+    /// of a year that stayed like this month).
+    pub prec: Vec<Vec<u8>>,
+}
+
+impl Seasons {
+    /// Mean monthly precipitation: the annual `prec` of the seasonal model.
+    pub fn annual_prec(&self) -> Vec<u8> {
+        let n = self.prec.first().map_or(0, Vec::len);
+        (0..n)
+            .map(|c| {
+                let sum: u32 = self.prec.iter().map(|month| month[c] as u32).sum();
+                (sum as f64 / self.prec.len() as f64).round() as u8
+            })
+            .collect()
+    }
+
+    /// Months each cell spends below freezing: how long snow closes a pass.
+    pub fn frost_months(&self) -> Vec<u8> {
+        let n = self.temp.first().map_or(0, Vec::len);
+        (0..n)
+            .map(|c| self.temp.iter().filter(|month| month[c] < 0).count() as u8)
+            .collect()
+    }
+
+    /// Month each cell's wet season (the monsoon, where there is one)
+    /// arrives: the first month after the driest one whose rainfall reaches
+    /// halfway from the driest to the wettest. `NO_WET_SEASON` where the
+    /// wettest month gets less than `WET_SEASON_RATIO` times the driest.
+    pub fn wet_season_onset(&self) -> Vec<u8> {
+        let n = self.prec.first().map_or(0, Vec::len);
+        let months = self.prec.len();
+        (0..n)
+            .map(|c| {
+                let at = |m: usize| self.prec[m % months][c] as f64;
+                let driest = (0..months)
+                    .min_by(|&a, &b| at(a).total_cmp(&at(b)))
+                    .unwrap_or(0);
+                let (lo, hi) = (at(driest), (0..months).map(at).fold(0.0, f64::max));
+                if hi < lo.max(1.0) * WET_SEASON_RATIO {
+                    return NO_WET_SEASON;
+                }
+                (driest + 1..driest + months)
+                    .find(|&m| at(m) >= (lo + hi) / 2.0)
+                    .map_or(NO_WET_SEASON, |m| (m % months) as u8)
+            })
+            .collect()
+    }
+}
+
+/// The sun's declination in the middle of `month` (lagged by `SPRING`),
+/// degrees north.
+fn declination(tilt: f64, month: usize) -> f64 {
+    let phase = (month as f64 + 0.5 - SPRING) / MONTHS as f64;
+    tilt * (std::f64::consts::TAU * phase).sin()
+}
+
+/// Monthly temperature and precipitation.
+///
+/// Temperature: the sea-level curve is shifted poleward by the sun's
+/// declination (a mid-latitude summer is as warm as the annual climate some
+/// way equatorward), less the cell's annual mean shift, so the months
+/// average back to `calculate_temperatures`. Water damps the swing: a cell
+/// whose neighbourhood is all sea swings `MARINE_DAMPING` less.
+///
+/// Precipitation: `generate_precipitation` runs once per month with the
+/// latitude bands (and so the ITCZ, the dry belts, the wind tiers and the
+/// monsoon passes) shifted by `BELT_SHIFT` of the declination, over that
+/// month's temperatures (cold months freeze more of the map into
+/// permafrost).
+///
+/// With `opts.currents` every month's coastal temperature and rainfall is
+/// adjusted by `ocean_currents`, as in `generate_climate`.
+pub fn generate_seasons(mesh: &Mesh, h: &[u8], opts: &ClimateOpts) -> Seasons {
+    let currents = opts.currents.then(|| ocean_currents(mesh, h, opts));
+    seasons(mesh, h, opts, currents.as_ref())
+}
+
+/// `generate_seasons` with the currents already run.
+fn seasons(mesh: &Mesh, h: &[u8], opts: &ClimateOpts, currents: Option<&Currents>) -> Seasons {
+    let n = mesh.points.len();
+    let coords = calculate_mesh_coordinates(mesh, opts);
+    let curve = TempCurve::from_opts(opts);
+    let shifts: Vec<f64> = (0..MONTHS)
+        .map(|m| declination(opts.axial_tilt, m))
+        .collect();
+
+    // Per cell: its latitude, the damping of its swing, and the annual
+    // temperature less the mean of the shifted curve.
+    let cells: Vec<(f64, f64, f64)> = (0..n)
+        .map(|c| {
+            let lat = latitude_at_y(mesh.points[c][1], mesh.world_h, &coords);
+            let annual = curve.sea_level_temp(lat, opts) - altitude_drop(h[c], curve.exponent);
+            let nbs = mesh.cells.neighbors_of_cell(c);
+            let water = std::iter::once(&(c as u32))
+                .chain(nbs)
+                .filter(|&&d| h[d as usize] < SEA_LEVEL)
+                .count();
+            let damping = 1.0 - MARINE_DAMPING * water as f64 / (nbs.len() + 1) as f64;
+            let shifted = shifts.iter().map(|&s| curve.sea_level_temp(lat - s, opts));
+            let base = annual - shifted.sum::<f64>() / MONTHS as f64 * damping;
+            (lat, damping, base)
+        })
+        .collect();
+
+    let mut temp = Vec::with_capacity(MONTHS);
+    let mut prec = Vec::with_capacity(MONTHS);
+    for &shift in &shifts {
+        let mut month_temp: Vec<i8> = cells
+            .iter()
+            .map(|&(lat, damping, base)| {
+                let t = base + curve.sea_level_temp(lat - shift, opts) * damping;
+                clamp(t, -128.0, 127.0) as i8
+            })
+            .collect();
+        if let Some(currents) = currents {
+            currents.warm_land(h, &mut month_temp);
+        }
+        let belts = MapCoords {
+            lat_t: coords.lat_t,
+            lat_n: coords.lat_n - shift * BELT_SHIFT,
+            lat_s: coords.lat_s - shift * BELT_SHIFT,
+        };
+        let mut month_prec = generate_precipitation(mesh, h, &month_temp, opts, &belts);
+        if let Some(currents) = currents {
+            currents.wet_land(h, &mut month_prec);
+        }
+        prec.push(month_prec);
+        temp.push(month_temp);
+    }
+    Seasons { temp, prec }
+}
+
+// ---------------------------------------------------------------------------
+// Ocean currents
+// ---------------------------------------------------------------------------
+
+/// How far a current carries its water's temperature before taking on the
+/// local one, as a share of the map height (0.15 ≈ 27° of latitude on a
+/// full-world map).
+const CURRENT_REACH: f64 = 0.15;
+
+/// Cooling (°C) of a coastal sea cell on the eastern side of an ocean whose
+/// drift runs straight offshore: the surface water pushed away is replaced
+/// by cold water from below.
+const UPWELLING: f64 = 6.0;
+
+/// Rounds of neighbour averaging that widen the boundary currents offshore.
+const CURRENT_SMOOTHING: usize = 2;
+
+/// Share of the adjacent sea's anomaly a coastal land cell takes on.
+const COAST_SHARE: f64 = 0.8;
+
+/// Share of its upwind neighbours' anomaly an inland cell keeps.
+const INLAND_DECAY: f64 = 0.7;
+
+/// Land cells beyond the coast the anomaly is carried downwind.
+const INLAND_STEPS: usize = 3;
+
+/// Relative change of land precipitation per °C of anomaly: warm water
+/// evaporates more, cold water stabilises the air above it.
+const CURRENT_PREC: f64 = 0.08;
+
+/// Surface currents from `ocean_currents`.
+#[derive(Serialize, Clone, Debug)]
+pub struct Currents {
+    /// Current per cell in world axes (`y` points south), `[0, 0]` on land.
+    /// Open-ocean drift has length 1.
+    pub flow: Vec<[f32; 2]>,
+    /// Sea-surface temperature anomaly (°C) of sea cells, and the share of it
+    /// carried onto the land near them; 0 elsewhere.
+    pub anomaly: Vec<f32>,
+}
+
+impl Currents {
+    /// Add the anomaly to the temperature of land cells.
+    pub fn warm_land(&self, h: &[u8], temp: &mut [i8]) {
+        for (c, t) in temp.iter_mut().enumerate() {
+            if h[c] >= SEA_LEVEL && self.anomaly[c] != 0.0 {
+                *t = clamp(*t as f64 + self.anomaly[c] as f64, -128.0, 127.0) as i8;
+            }
+        }
+    }
+
+    /// Scale the precipitation of land cells by their anomaly: more rain off
+    /// warm water, less off cold.
+    pub fn wet_land(&self, h: &[u8], prec: &mut [u8]) {
+        for (c, p) in prec.iter_mut().enumerate() {
+            if h[c] >= SEA_LEVEL && self.anomaly[c] != 0.0 {
+                let scale = (1.0 + self.anomaly[c] as f64 * CURRENT_PREC).clamp(0.25, 2.0);
+                *p = (*p as f64 * scale).round().min(255.0) as u8;
+            }
+        }
+    }
+}
+
+/// Which way a current blocked by land turns, as the sign of world `y`
+/// (`-1` north, `1` south, `0` if the winds never reverse): toward the
+/// nearest latitude where the zonal wind blows the other way, i.e. where its
+/// gyre closes, equatorward on a tie. The trades turn poleward along the
+/// western side of an ocean and the westerlies equatorward along its eastern
+/// side.
+fn gyre_turn(lat: f64, opts: &ClimateOpts) -> f64 {
+    let eastward = |tier: usize| tier_angle(tier, opts).to_radians().sin() > 0.0;
+    let tier = wind_tier(lat);
+    let own = eastward(tier);
+    let north = (0..tier)
+        .rev()
+        .find(|&k| eastward(k) != own)
+        .map(|k| 89.0 - 30.0 * (k + 1) as f64 - lat);
+    let south = (tier + 1..6)
+        .find(|&k| eastward(k) != own)
+        .map(|k| lat - (89.0 - 30.0 * k as f64));
+    match (north, south) {
+        (Some(n), Some(s)) if n == s => lat.signum(),
+        (Some(n), Some(s)) => (n - s).signum(),
+        (Some(_), None) => -1.0,
+        (None, Some(_)) => 1.0,
+        (None, None) => 0.0,
+    }
+}
+
+/// Wind-driven surface currents and the sea-surface temperature they carry.
+///
+/// 1. Each sea cell drifts east or west with its prevailing wind
+///    (`options.winds`, a bearing the wind blows toward). Where the drift
+///    runs into land, its onshore part turns along the coast (`gyre_turn`):
+///    this is what closes the gyres into warm poleward currents on the
+///    western side of an ocean and cold equatorward ones on its eastern
+///    side. Where the drift leaves the coast, water flows in along it from
+///    the other way, and on the eastern side of an ocean cold water wells up
+///    (`UPWELLING`).
+/// 2. The field is smoothed `CURRENT_SMOOTHING` times so the boundary
+///    currents are a few cells wide.
+/// 3. Each sea cell takes its water from its most upstream neighbour: its
+///    temperature relaxes toward the local sea-level temperature over
+///    `CURRENT_REACH`, so water stays warm (or cold) as it is carried toward
+///    the poles (or the equator), and across the ocean with the westerlies.
+/// 4. Coastal land takes on `COAST_SHARE` of the anomaly of the sea next to
+///    it, and the wind carries it `INLAND_STEPS` cells further inland.
+///
+/// Deterministic: fixed iteration counts, no RNG.
+pub fn ocean_currents(mesh: &Mesh, h: &[u8], opts: &ClimateOpts) -> Currents {
+    let n = mesh.points.len();
+    let coords = calculate_mesh_coordinates(mesh, opts);
+    let curve = TempCurve::from_opts(opts);
+    let lat: Vec<f64> = (0..n)
+        .map(|c| latitude_at_y(mesh.points[c][1], mesh.world_h, &coords))
+        .collect();
+    let wind: Vec<[f64; 2]> = lat
+        .iter()
+        .map(|&l| {
+            let angle = wind_angle(l, opts).to_radians();
+            [angle.sin(), -angle.cos()]
+        })
+        .collect();
+    let water = |c: usize| h[c] < SEA_LEVEL;
+    let dot = |a: [f64; 2], b: [f64; 2]| a[0] * b[0] + a[1] * b[1];
+    // Unit vector from cell `a` to cell `b`, and their distance.
+    let toward = |a: usize, b: usize| {
+        let ([xa, ya], [xb, yb]) = (mesh.points[a], mesh.points[b]);
+        let d = [mesh.delta_x(xa, xb), yb - ya];
+        let len = d[0].hypot(d[1]).max(f64::EPSILON);
+        ([d[0] / len, d[1] / len], len)
+    };
+
+    // 1. Zonal wind drift, turned along the coasts.
+    let mut flow = vec![[0.0; 2]; n];
+    let mut upwelling = vec![0.0; n];
+    let mut shore = vec![false; n];
+    for c in (0..n).filter(|&c| water(c)) {
+        let drift = [wind[c][0].signum(), 0.0];
+        flow[c] = drift;
+        let mut normal = [0.0; 2];
+        for &d in mesh.cells.neighbors_of_cell(c) {
+            if !water(d as usize) {
+                let (u, _) = toward(c, d as usize);
+                normal = [normal[0] + u[0], normal[1] + u[1]];
+            }
+        }
+        let len = normal[0].hypot(normal[1]);
+        if len < 1e-9 {
+            continue;
+        }
+        shore[c] = true;
+        let normal = [normal[0] / len, normal[1] / len];
+        let onshore = dot(drift, normal);
+        if onshore < 0.0 && drift[0] < 0.0 {
+            upwelling[c] = -onshore * UPWELLING;
+        }
+        // Drift into the coast turns the way its gyre closes; drift away from
+        // it is replaced by water arriving along the coast from that side.
+        let turn = gyre_turn(lat[c], opts) * onshore.signum();
+        let mut tangent = [-normal[1], normal[0]];
+        let along = if turn != 0.0 && tangent[1].abs() > 0.1 {
+            turn * tangent[1]
+        } else {
+            dot(drift, tangent)
+        };
+        if along < 0.0 {
+            tangent = [-tangent[0], -tangent[1]];
+        }
+        let (strength, away) = (onshore.abs(), onshore.max(0.0));
+        flow[c] = [
+            drift[0] + strength * tangent[0] - away * normal[0],
+            drift[1] + strength * tangent[1] - away * normal[1],
+        ];
+    }
+
+    // 2. Widen the boundary currents (the shore keeps its own).
+    for _ in 0..CURRENT_SMOOTHING {
+        let prev = flow.clone();
+        for c in (0..n).filter(|&c| water(c) && !shore[c]) {
+            let (mut sum, mut count) = (prev[c], 1.0);
+            for &d in mesh.cells.neighbors_of_cell(c) {
+                if water(d as usize) {
+                    let v = prev[d as usize];
+                    sum = [sum[0] + v[0], sum[1] + v[1]];
+                    count += 1.0;
+                }
+            }
+            flow[c] = [sum[0] / count, sum[1] / count];
+        }
+    }
+
+    // 3. Carry the water's temperature downstream. Per sea cell: the
+    // neighbour its water comes from and how much of that water's
+    // temperature it keeps.
+    let sea_temp: Vec<f64> = lat.iter().map(|&l| curve.sea_level_temp(l, opts)).collect();
+    let reach = mesh.world_h * CURRENT_REACH;
+    let upstream: Vec<Option<(usize, f64)>> = (0..n)
+        .map(|c| {
+            let speed = flow[c][0].hypot(flow[c][1]);
+            if !water(c) || speed < 1e-9 {
+                return None;
+            }
+            mesh.cells
+                .neighbors_of_cell(c)
+                .iter()
+                .map(|&d| d as usize)
+                .filter(|&d| water(d))
+                .map(|d| {
+                    let (u, dist) = toward(d, c);
+                    (d, dot(u, flow[c]) / speed, dist)
+                })
+                .filter(|&(_, align, _)| align > 0.0)
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(d, align, dist)| (d, (-dist / reach).exp() * (align * speed).min(1.0)))
+        })
+        .collect();
+    let spacing = (mesh.world_w * mesh.world_h / n.max(1) as f64).sqrt();
+    let steps = ((3.0 * reach / spacing).ceil() as usize).clamp(1, 200);
+    let local: Vec<f64> = (0..n).map(|c| sea_temp[c] - upwelling[c]).collect();
+    let mut sst = local.clone();
+    for _ in 0..steps {
+        let prev = sst.clone();
+        for (c, up) in upstream.iter().enumerate() {
+            if let &Some((d, keep)) = up {
+                sst[c] = local[c] * (1.0 - keep) + prev[d] * keep;
+            }
+        }
+    }
+
+    // 4. Onto the coast, then downwind inland.
+    let mut anomaly: Vec<f64> = (0..n)
+        .map(|c| if water(c) { sst[c] - sea_temp[c] } else { 0.0 })
+        .collect();
+    let coastal: Vec<bool> = (0..n)
+        .map(|c| {
+            !water(c)
+                && mesh
+                    .cells
+                    .neighbors_of_cell(c)
+                    .iter()
+                    .any(|&d| water(d as usize))
+        })
+        .collect();
+    for c in (0..n).filter(|&c| coastal[c]) {
+        let sea: Vec<f64> = mesh
+            .cells
+            .neighbors_of_cell(c)
+            .iter()
+            .filter(|&&d| water(d as usize))
+            .map(|&d| anomaly[d as usize])
+            .collect();
+        anomaly[c] = COAST_SHARE * sea.iter().sum::<f64>() / sea.len() as f64;
+    }
+    for _ in 0..INLAND_STEPS {
+        let prev = anomaly.clone();
+        for c in (0..n).filter(|&c| !water(c) && !coastal[c]) {
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for &d in mesh.cells.neighbors_of_cell(c) {
+                let (u, _) = toward(d as usize, c);
+                let w = dot(u, wind[c]).max(0.0);
+                sum += w * prev[d as usize];
+                weight += w;
+            }
+            if weight > 0.0 {
+                anomaly[c] = INLAND_DECAY * sum / weight;
+            }
+        }
+    }
+
+    Currents {
+        flow: flow.iter().map(|&[x, y]| [x as f32, y as f32]).collect(),
+        anomaly: anomaly.iter().map(|&a| a as f32).collect(),
+    }
+}
+
 /// `#[wasm_bindgen]` entry point. Takes the `Mesh`, the heightmap (`Uint8Array`),
 /// and climate options (`JsValue`, all fields optional), and returns
 /// `{ temp: Int8Array, prec: Uint8Array }`. Exposed as
@@ -652,6 +1461,37 @@ mod tests {
         }
     }
 
+    /// On a globe the latitude band ignores `mapSize`/`latitude`: a cell's
+    /// temperature follows its true latitude, so polar caps are cold and the
+    /// equator warm even with a "regional" map-size setting.
+    #[test]
+    fn globe_uses_full_latitude_band() {
+        let mesh = mesh::build_globe(3000, 42);
+        let h = vec![10u8; mesh.points.len()];
+        let opts = ClimateOpts {
+            map_size: 20.0,
+            ..ClimateOpts::default()
+        };
+        let (temp, _) = generate_climate(&mesh, &h, &opts);
+        let mean = |lo: f64, hi: f64| {
+            let sel: Vec<f64> = (0..mesh.points.len())
+                .filter(|&c| (lo..hi).contains(&mesh.lat_lon[c][0].abs()))
+                .map(|c| temp[c] as f64)
+                .collect();
+            sel.iter().sum::<f64>() / sel.len() as f64
+        };
+        assert!(
+            mean(0.0, 10.0) > 20.0,
+            "equator too cold: {}",
+            mean(0.0, 10.0)
+        );
+        assert!(
+            mean(75.0, 90.0) < 0.0,
+            "poles too warm: {}",
+            mean(75.0, 90.0)
+        );
+    }
+
     /// Equatorial water cells must be warmer than polar water cells (the
     /// latitude gradient dominates at sea level). We compare the mean temp of
     /// water cells near the equator against water cells near the map edges.
@@ -782,7 +1622,8 @@ mod tests {
         );
     }
 
-    /// Determinism: identical mesh + heightmap + opts → byte-identical climate.
+    /// Determinism: identical mesh + heightmap + opts → byte-identical climate,
+    /// with either precipitation solver.
     #[test]
     fn deterministic_same_inputs() {
         let (mesh, h) = fixture(3000, 42);
@@ -791,9 +1632,15 @@ mod tests {
         let a_t = calculate_temperatures(&mesh, &h, &opts, &coords);
         let b_t = calculate_temperatures(&mesh, &h, &opts, &coords);
         assert_eq!(a_t, b_t, "temperature not deterministic");
-        let a_p = generate_precipitation(&mesh, &h, &a_t, &opts, &coords);
-        let b_p = generate_precipitation(&mesh, &h, &b_t, &opts, &coords);
-        assert_eq!(a_p, b_p, "precipitation not deterministic");
+        for solver in [PrecipitationSolver::Grid, PrecipitationSolver::Mesh] {
+            let opts = ClimateOpts {
+                solver,
+                ..default_opts()
+            };
+            let a_p = generate_precipitation(&mesh, &h, &a_t, &opts, &coords);
+            let b_p = generate_precipitation(&mesh, &h, &b_t, &opts, &coords);
+            assert_eq!(a_p, b_p, "{solver:?}: precipitation not deterministic");
+        }
     }
 
     /// 60k smoke: completes and stays in range.
@@ -1194,9 +2041,18 @@ mod tests {
             let a_t = calculate_temperatures(&mesh, &h, &opts, &coords);
             let b_t = calculate_temperatures(&mesh, &h, &opts, &coords);
             assert_eq!(a_t, b_t, "N={n} seed={seed}: temp not deterministic");
-            let a_p = generate_precipitation(&mesh, &h, &a_t, &opts, &coords);
-            let b_p = generate_precipitation(&mesh, &h, &b_t, &opts, &coords);
-            assert_eq!(a_p, b_p, "N={n} seed={seed}: prec not deterministic");
+            for solver in [PrecipitationSolver::Grid, PrecipitationSolver::Mesh] {
+                let opts = ClimateOpts {
+                    solver,
+                    ..default_opts()
+                };
+                let a_p = generate_precipitation(&mesh, &h, &a_t, &opts, &coords);
+                let b_p = generate_precipitation(&mesh, &h, &b_t, &opts, &coords);
+                assert_eq!(
+                    a_p, b_p,
+                    "N={n} seed={seed} {solver:?}: prec not deterministic"
+                );
+            }
         }
     }
 
@@ -1230,6 +2086,7 @@ mod tests {
         assert_eq!(opts.temperature_south_pole, -15.0);
         assert_eq!(opts.winds.len(), 6);
         assert_eq!(opts.winds, vec![225.0, 45.0, 225.0, 315.0, 135.0, 315.0]);
+        assert_eq!(opts.solver, PrecipitationSolver::Grid);
     }
 
     /// All precipitation values must be in `[0, 255]` (u8 storage range).
@@ -1424,6 +2281,8 @@ mod tests {
             1,
             5,
             1.0,
+            0,
+            None,
         );
 
         // Blocked: h=86 everywhere. Wind dumps at source and stops.
@@ -1440,6 +2299,8 @@ mod tests {
             1,
             5,
             1.0,
+            0,
+            None,
         );
 
         // In the blocked case the first cell receives the full humidity dump
@@ -1470,6 +2331,68 @@ mod tests {
 
     // ── A5: coastal precipitation branch (sea → land transition) ──────────
 
+    /// Horizontal steps wrap within the row when `wrap_cols > 0`; vertical
+    /// steps and flat maps keep plain slot arithmetic.
+    #[test]
+    fn step_slot_wraps_rows() {
+        assert_eq!(step_slot(4, 1, 5), 0);
+        assert_eq!(step_slot(5, -1, 5), 9);
+        assert_eq!(step_slot(7, 1, 5), 8);
+        assert_eq!(step_slot(4, 1, 0), 5);
+        assert_eq!(step_slot(3, 5, 5), 8);
+    }
+
+    /// On a wrapping row the westerly starts where it leaves land, crosses
+    /// the ocean and reaches the coast "behind" the map edge: the land at the
+    /// row's western end gets rain even though the only ocean lies east.
+    #[test]
+    fn wrapped_wind_crosses_the_seam() {
+        // Land ×2, then ocean ×6 running to the eastern edge.
+        let spacing: Vec<u32> = (0..8).collect();
+        let h: Vec<u8> = vec![40, 40, 0, 0, 0, 0, 0, 0];
+        let temp: Vec<i8> = vec![20; 8];
+        let start = wrap_start_slot(0, 8, 1, &h, &spacing);
+        assert_eq!(
+            start, 2,
+            "westerly starts at the first water slot east of land"
+        );
+        let mut wrapped = vec![0u8; 8];
+        pass_wind_one(
+            &mut wrapped,
+            &h,
+            &temp,
+            &spacing,
+            start,
+            100.0,
+            Some(1.0),
+            1,
+            8,
+            1.0,
+            8,
+            None,
+        );
+        let mut flat = vec![0u8; 8];
+        pass_wind_one(
+            &mut flat,
+            &h,
+            &temp,
+            &spacing,
+            0,
+            100.0,
+            Some(1.0),
+            1,
+            8,
+            1.0,
+            0,
+            None,
+        );
+        assert!(
+            wrapped[0] > 0,
+            "coast across the seam got no rain: {wrapped:?}"
+        );
+        assert!(wrapped[0] > flat[0]);
+    }
+
     /// **A5 (coastal precip):** When wind crosses from water (h < 20) to land
     /// (h >= 20), FMG deposits coastal precipitation on the **land** cell
     /// (the *next* cell, not the water cell). We test `pass_wind_one` directly
@@ -1498,6 +2421,8 @@ mod tests {
             1,
             5,
             1.0,
+            0,
+            None,
         );
 
         // Open-water cells 0..2: next cell is also water → open-water branch
@@ -1816,4 +2741,313 @@ mod tests {
         // tempNorthTropic = temperature_equator - t0 * tg
         assert_eq!(curve.tnt, opts.temperature_equator - 16.0 * 0.15);
     }
+
+    /// Lowland in the west, ocean in the east (upwind of the trade winds):
+    /// clean belts to follow.
+    fn coast_fixture() -> (Mesh, Vec<u8>) {
+        let mesh = mesh::build(6000, 7);
+        let h = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| if x < 6000.0 { 30 } else { 10 })
+            .collect();
+        (mesh, h)
+    }
+
+    /// Monthly temperatures average back to the annual ones; the seasonal
+    /// `generate_climate` uses the mean monthly precipitation.
+    #[test]
+    fn seasons_average_back_to_annual() {
+        let (mesh, h) = fixture(3000, 42);
+        let opts = ClimateOpts {
+            seasonal: true,
+            ..ClimateOpts::default()
+        };
+        let seasons = generate_seasons(&mesh, &h, &opts);
+        assert_eq!((seasons.temp.len(), seasons.prec.len()), (MONTHS, MONTHS));
+        let coords = calculate_mesh_coordinates(&mesh, &opts);
+        let annual = calculate_temperatures(&mesh, &h, &opts, &coords);
+        for c in 0..h.len() {
+            let mean = seasons.temp.iter().map(|m| m[c] as f64).sum::<f64>() / MONTHS as f64;
+            // Both the months and the annual value truncate toward zero.
+            assert!((mean - annual[c] as f64).abs() < 2.0, "cell {c}");
+        }
+        let (temp, prec) = generate_climate(&mesh, &h, &opts);
+        assert_eq!(temp, annual);
+        assert_eq!(prec, seasons.annual_prec());
+        let plain = generate_climate(&mesh, &h, &ClimateOpts::default());
+        assert_ne!(prec, plain.1);
+    }
+
+    /// Hemispheres have opposite seasons, land swings more than sea, and
+    /// without tilt there are none.
+    #[test]
+    fn seasons_follow_the_sun() {
+        let (mesh, h) = coast_fixture();
+        let seasons = generate_seasons(&mesh, &h, &default_opts());
+        let lat = |c: usize| 90.0 - mesh.points[c][1] / mesh.world_h * 180.0;
+        let swing = |c: usize| seasons.temp[6][c] as i32 - seasons.temp[0][c] as i32;
+        let mean_swing = |lo: f64, hi: f64, land: bool| {
+            let sel: Vec<i32> = (0..h.len())
+                .filter(|&c| (lo..hi).contains(&lat(c)) && (h[c] >= SEA_LEVEL) == land)
+                .map(swing)
+                .collect();
+            sel.iter().sum::<i32>() as f64 / sel.len() as f64
+        };
+        let (north_land, north_sea) = (mean_swing(35.0, 60.0, true), mean_swing(35.0, 60.0, false));
+        assert!(north_land > 15.0, "{north_land}");
+        assert!(
+            north_sea > 0.0 && north_sea < north_land * 0.7,
+            "{north_sea}"
+        );
+        assert!(mean_swing(-60.0, -35.0, true) < -15.0);
+        assert!(mean_swing(-5.0, 5.0, true).abs() < north_land / 3.0);
+
+        let frost = seasons.frost_months();
+        let far_north = (0..h.len()).filter(|&c| lat(c) > 60.0 && h[c] >= SEA_LEVEL);
+        assert!(far_north.clone().all(|c| frost[c] > 0));
+        assert!(far_north.clone().any(|c| frost[c] < MONTHS as u8));
+        assert!((0..h.len())
+            .filter(|&c| lat(c).abs() < 20.0)
+            .all(|c| frost[c] == 0));
+
+        let flat = ClimateOpts {
+            axial_tilt: 0.0,
+            ..ClimateOpts::default()
+        };
+        let flat = generate_seasons(&mesh, &h, &flat);
+        assert!(flat.temp.iter().all(|m| m == &flat.temp[0]));
+        assert!(flat.prec.iter().all(|m| m == &flat.prec[0]));
+    }
+
+    /// The rain belts move north in July and south in January, giving the
+    /// tropics a wet season.
+    #[test]
+    fn rain_belts_shift_with_the_seasons() {
+        let (mesh, h) = coast_fixture();
+        let seasons = generate_seasons(&mesh, &h, &default_opts());
+        let lat = |c: usize| 90.0 - mesh.points[c][1] / mesh.world_h * 180.0;
+        // Mean land rainfall per 5° band within the tropics.
+        let wettest = |month: usize| {
+            (-3..3)
+                .map(|b| {
+                    let lo = b as f64 * 5.0;
+                    let sel: Vec<f64> = (0..h.len())
+                        .filter(|&c| h[c] >= SEA_LEVEL && (lo..lo + 5.0).contains(&lat(c)))
+                        .map(|c| seasons.prec[month][c] as f64)
+                        .collect();
+                    (sel.iter().sum::<f64>() / sel.len().max(1) as f64, lo)
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .unwrap()
+                .1
+        };
+        assert!(wettest(6) > wettest(0), "{} vs {}", wettest(6), wettest(0));
+
+        assert!(wettest(0) < 0.0);
+    }
+
+    /// Onset is the first month after the driest to get halfway to the
+    /// wettest, wrapping the year; evenly wet or dry cells have none.
+    #[test]
+    fn wet_season_onset_finds_the_rains() {
+        let monthly = [
+            [40, 0, 50, 50],
+            [40, 0, 50, 50],
+            [2, 0, 50, 50],
+            [2, 1, 50, 50],
+            [2, 1, 50, 50],
+            [2, 0, 50, 50],
+            [2, 0, 50, 50],
+            [2, 0, 50, 50],
+            [2, 0, 50, 50],
+            [2, 0, 50, 50],
+            [40, 0, 50, 50],
+            [40, 0, 50, 2],
+        ];
+        let seasons = Seasons {
+            temp: vec![vec![0; 4]; MONTHS],
+            prec: monthly.iter().map(|m| m.to_vec()).collect(),
+        };
+        assert_eq!(
+            seasons.wet_season_onset(),
+            vec![10, NO_WET_SEASON, NO_WET_SEASON, 0]
+        );
+    }
+
+    /// A continent between 3500 and 6500 east, ocean either side.
+    fn continent_fixture() -> (Mesh, Vec<u8>) {
+        let mesh = mesh::build(8000, 3);
+        let h = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| {
+                if (3500.0..6500.0).contains(&x) {
+                    30
+                } else {
+                    10
+                }
+            })
+            .collect();
+        (mesh, h)
+    }
+
+    /// Mean of `value` over the cells within the latitude and `x` ranges.
+    fn strip_mean(
+        mesh: &Mesh,
+        lat: std::ops::Range<f64>,
+        x: std::ops::Range<f64>,
+        value: impl Fn(usize) -> f64,
+    ) -> f64 {
+        let cells: Vec<usize> = (0..mesh.points.len())
+            .filter(|&c| {
+                let [cx, cy] = mesh.points[c];
+                lat.contains(&(90.0 - cy / mesh.world_h * 180.0)) && x.contains(&cx)
+            })
+            .collect();
+        cells.iter().map(|&c| value(c)).sum::<f64>() / cells.len().max(1) as f64
+    }
+
+    /// The trades turn poleward along the western side of an ocean and the
+    /// water off the eastern side flows equatorward, in both hemispheres.
+    #[test]
+    fn currents_close_the_gyres() {
+        let (mesh, h) = continent_fixture();
+        let currents = ocean_currents(&mesh, &h, &default_opts());
+        assert!(h
+            .iter()
+            .zip(&currents.flow)
+            .all(|(&h, f)| h < SEA_LEVEL || *f == [0.0, 0.0]));
+        let south = |c: usize| currents.flow[c][1] as f64;
+        let (off_west, off_east) = (3200.0..3500.0, 6500.0..6800.0);
+        assert!(strip_mean(&mesh, 10.0..30.0, off_east.clone(), south) < -0.3);
+        assert!(strip_mean(&mesh, 10.0..30.0, off_west.clone(), south) > 0.3);
+        assert!(strip_mean(&mesh, -30.0..-10.0, off_east, south) > 0.3);
+        assert!(strip_mean(&mesh, -30.0..-10.0, off_west, south) < -0.3);
+    }
+
+    /// Cold water makes cool, dry west coasts in the trades; warm water
+    /// makes mild coasts in the westerlies. Off by default.
+    #[test]
+    fn currents_shape_coastal_climates() {
+        let (mesh, h) = continent_fixture();
+        let currents = ocean_currents(&mesh, &h, &default_opts());
+        let anomaly = |c: usize| currents.anomaly[c] as f64;
+        let (west_coast, east_coast) = (3500.0..3700.0, 6300.0..6500.0);
+        assert!(strip_mean(&mesh, 10.0..30.0, west_coast.clone(), anomaly) < -1.5);
+        assert!(strip_mean(&mesh, 10.0..30.0, east_coast, anomaly) > 0.5);
+        assert!(strip_mean(&mesh, 50.0..60.0, west_coast.clone(), anomaly) > 1.0);
+        // Deep inland is out of reach.
+        assert_eq!(strip_mean(&mesh, -60.0..60.0, 4800.0..5200.0, anomaly), 0.0);
+
+        let plain = generate_climate(&mesh, &h, &default_opts());
+        let opts = ClimateOpts {
+            currents: true,
+            ..default_opts()
+        };
+        let (temp, prec) = generate_climate(&mesh, &h, &opts);
+        let coast = |values: &dyn Fn(usize) -> f64| {
+            strip_mean(&mesh, 10.0..30.0, west_coast.clone(), values)
+        };
+        assert!(coast(&|c| temp[c] as f64) < coast(&|c| plain.0[c] as f64) - 1.0);
+        assert!(coast(&|c| prec[c] as f64) < coast(&|c| plain.1[c] as f64));
+        let inland = (0..h.len()).filter(|&c| currents.anomaly[c] == 0.0);
+        assert!(inland.clone().all(|c| temp[c] == plain.0[c]));
+    }
+
+    /// The trace leaves `prec` alone and explains it: the trades blow west
+    /// and the westerlies east, and a range across the westerlies wrings
+    /// orographic rain out of its windward side and leaves a rain shadow.
+    #[test]
+    fn precipitation_trace_shows_winds_and_rain_shadow() {
+        let mesh = mesh::build(6000, 9);
+        let ridge = 5000.0..5300.0;
+        let h: Vec<u8> = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| match x {
+                x if ridge.contains(&x) => 70,
+                x if (2000.0..9000.0).contains(&x) => 30,
+                _ => 10,
+            })
+            .collect();
+        let opts = default_opts();
+        let coords = calculate_mesh_coordinates(&mesh, &opts);
+        let temp = calculate_temperatures(&mesh, &h, &opts, &coords);
+        let (prec, trace) = generate_precipitation_traced(&mesh, &h, &temp, &opts, &coords);
+        assert_eq!(
+            prec,
+            generate_precipitation(&mesh, &h, &temp, &opts, &coords)
+        );
+
+        let east = |c: usize| trace.wind[c][0] as f64;
+        assert!(strip_mean(&mesh, 5.0..25.0, 0.0..10000.0, east) < -0.5);
+        assert!(strip_mean(&mesh, 35.0..55.0, 0.0..10000.0, east) > 0.5);
+
+        let westerlies = 35.0..55.0;
+        let orographic = |c: usize| trace.orographic[c] as f64;
+        let humidity = |c: usize| trace.humidity[c] as f64;
+        let (wet, dry) = (4800.0..5000.0, 5300.0..5600.0);
+        let mean = |x: &std::ops::Range<f64>, value: &dyn Fn(usize) -> f64| {
+            strip_mean(&mesh, westerlies.clone(), x.clone(), value)
+        };
+        assert!(mean(&dry, &orographic) < mean(&wet, &orographic) / 10.0);
+        assert!(mean(&dry, &humidity) < mean(&wet, &humidity));
+        let rain = |c: usize| prec[c] as f64;
+        assert!(mean(&dry, &rain) < mean(&wet, &rain) / 2.0);
+    }
+
+    /// The mesh solver keeps the grid solver's winds and rain shadow behind a
+    /// ridge, and its traced and plain runs agree, but its rain is smoother
+    /// from cell to cell than the grid solver's row-by-row bands.
+    #[test]
+    fn mesh_solver_follows_the_wind_without_bands() {
+        let mesh = mesh::build(6000, 9);
+        let ridge = 2500.0..2800.0;
+        let h: Vec<u8> = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| match x {
+                x if ridge.contains(&x) => 70,
+                x if (2000.0..9000.0).contains(&x) => 30,
+                _ => 10,
+            })
+            .collect();
+        let grid = default_opts();
+        let opts = ClimateOpts {
+            solver: PrecipitationSolver::Mesh,
+            ..default_opts()
+        };
+        let coords = calculate_mesh_coordinates(&mesh, &opts);
+        let temp = calculate_temperatures(&mesh, &h, &opts, &coords);
+        let (prec, trace) = generate_precipitation_traced(&mesh, &h, &temp, &opts, &coords);
+        assert_eq!(
+            prec,
+            generate_precipitation(&mesh, &h, &temp, &opts, &coords)
+        );
+
+        // Same winds and rain shadow as the grid solver.
+        let east = |c: usize| trace.wind[c][0] as f64;
+        assert!(strip_mean(&mesh, 5.0..25.0, 0.0..10000.0, east) < -0.5);
+        assert!(strip_mean(&mesh, 35.0..55.0, 0.0..10000.0, east) > 0.5);
+        let rain = |c: usize| prec[c] as f64;
+        let westerlies = |x: std::ops::Range<f64>| strip_mean(&mesh, 35.0..55.0, x, rain);
+        assert!(westerlies(2800.0..3100.0) < westerlies(2300.0..2500.0) / 2.0);
+
+        // Rain varies less from a land cell to its neighbours than on the
+        // grid solver's rows.
+        let roughness = |prec: &[u8]| {
+            let (mut sum, mut count) = (0.0, 0.0);
+            for c in (0..mesh.points.len()).filter(|&c| h[c] >= SEA_LEVEL) {
+                let nbs = mesh.cells.neighbors_of_cell(c);
+                let mean =
+                    nbs.iter().map(|&d| prec[d as usize] as f64).sum::<f64>() / nbs.len() as f64;
+                sum += (prec[c] as f64 - mean).abs();
+                count += 1.0;
+            }
+            sum / count
+        };
+        let banded = generate_precipitation(&mesh, &h, &temp, &grid, &coords);
+        assert!(roughness(&prec) < roughness(&banded));
+    }
 }
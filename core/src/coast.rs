@@ -0,0 +1,302 @@
+//! Ordered shoreline polylines.
+//!
+//! `recompute_dependents` used to expose only a per-cell coastline mask; this
+//! traces the actual shore: the Voronoi edges between land and water cells,
+//! chained into ordered polylines per landmass (against the sea) and per lake.
+//!
+//! Built on `outline::patch_loops` with labels land = `0`, lake cells = their
+//! index in `lakes` plus one (so no lake shares the land label, whatever its
+//! `LakeGeo::id`), ocean = `-1` (no patch):
+//!
+//! - Sea shores are the runs of a land patch's boundary loops whose far side
+//!   is ocean. A landmass's outer ring and any enclosed inland sea each give
+//!   one closed ring; a shore that reaches the map edge is an open polyline.
+//! - Lake shores are the runs of a lake patch's loops whose far side is land,
+//!   reversed so that land is on the same side as for sea shores.
+//!
+//! Optional Chaikin corner cutting (`smoothing` iterations, each doubling the
+//! point count; open polylines keep their end points) rounds the Voronoi
+//! zig-zag. It is pure arithmetic on the traced points, so deterministic.
+
+use crate::grid::{LakeGeo, ShoreKind, Shoreline};
+use crate::heightmap::SEA_LEVEL;
+use crate::mesh::Mesh;
+use crate::outline::{patch_loops, run_vertices, split_runs, PatchLoops};
+
+/// Upper bound on Chaikin iterations (each one doubles the points).
+pub const MAX_SMOOTHING: u32 = 6;
+
+/// Trace all sea and lake shorelines of a heightmap. `lakes` are the lakes of
+/// the same heightmap (`rivers::compute_drainage`, ids assigned); water cells
+/// in none of them count as sea. `smoothing` is the number of Chaikin passes
+/// (`0` = raw Voronoi edges, capped at `MAX_SMOOTHING`).
+pub fn shorelines(mesh: &Mesh, h: &[u8], lakes: &[LakeGeo], smoothing: u32) -> Vec<Shoreline> {
+    let n = mesh.points.len();
+    let mut labels: Vec<i32> = (0..n)
+        .map(|c| if h[c] >= SEA_LEVEL { 0 } else { -1 })
+        .collect();
+    for (i, lake) in lakes.iter().enumerate() {
+        for &c in &lake.cells {
+            labels[c as usize] = i as i32 + 1;
+        }
+    }
+    let PatchLoops {
+        owner,
+        start,
+        loops,
+    } = patch_loops(mesh, &labels);
+    // Hull edges name no real neighbour; they end a run like a land edge.
+    let across = |p: usize| labels.get(mesh.cells.c[p] as usize).copied().unwrap_or(0);
+
+    let mut out = Vec::new();
+    let mut landmass = 0u32;
+    for (&patch, patch_loops) in &loops {
+        let label = labels[patch as usize];
+        for edges in patch_loops {
+            let runs = if label == 0 {
+                split_runs(edges, |p| (across(p) < 0).then_some(()))
+            } else {
+                split_runs(edges, |p| (across(p) == 0).then_some(()))
+            };
+            for ((), run, closed) in runs {
+                debug_assert!(run.iter().all(|&p| labels[owner[p] as usize] == label));
+                let mut vertices = run_vertices(mesh, &start, &run, closed);
+                if label != 0 {
+                    vertices.reverse();
+                }
+                let points = chaikin(&unwrapped_points(mesh, &vertices), closed, smoothing);
+                out.push(Shoreline {
+                    kind: if label == 0 {
+                        ShoreKind::Sea
+                    } else {
+                        ShoreKind::Lake
+                    },
+                    feature: if label == 0 {
+                        landmass
+                    } else {
+                        lakes[label as usize - 1].id
+                    },
+                    vertices,
+                    points,
+                    closed,
+                });
+            }
+        }
+        if label == 0 {
+            landmass += 1;
+        }
+    }
+    out
+}
+
+/// Vertex positions with each `x` placed nearest its predecessor, so a line
+/// crossing the cylinder seam stays continuous.
+fn unwrapped_points(mesh: &Mesh, vertices: &[u32]) -> Vec<[f64; 2]> {
+    let mut points: Vec<[f64; 2]> = Vec::with_capacity(vertices.len());
+    for &v in vertices {
+        let [x, y] = mesh.vertices.p[v as usize];
+        let x = match points.last() {
+            Some(&[px, _]) => px + mesh.delta_x(px, x),
+            None => x,
+        };
+        points.push([x, y]);
+    }
+    points
+}
+
+/// `iterations` passes of Chaikin corner cutting: every segment `P→Q` is
+/// replaced by its ¼ and ¾ points. Open lines keep their first and last point.
+fn chaikin(points: &[[f64; 2]], closed: bool, iterations: u32) -> Vec<[f64; 2]> {
+    let mut pts = points.to_vec();
+    for _ in 0..iterations.min(MAX_SMOOTHING) {
+        let m = pts.len();
+        if m < 3 {
+            break;
+        }
+        let segments = if closed { m } else { m - 1 };
+        let mut next = Vec::with_capacity(2 * m);
+        if !closed {
+            next.push(pts[0]);
+        }
+        for k in 0..segments {
+            let [ax, ay] = pts[k];
+            let [bx, by] = pts[(k + 1) % m];
+            next.push([0.75 * ax + 0.25 * bx, 0.75 * ay + 0.25 * by]);
+            next.push([0.25 * ax + 0.75 * bx, 0.25 * ay + 0.75 * by]);
+        }
+        if !closed {
+            next.push(pts[m - 1]);
+        }
+        pts = next;
+    }
+    pts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::climate;
+    use crate::generate_world_inner;
+    use crate::mesh;
+    use crate::rivers;
+
+    /// Signed shoelace area of a closed point ring.
+    fn area(points: &[[f64; 2]]) -> f64 {
+        let m = points.len();
+        (0..m)
+            .map(|k| {
+                let ([ax, ay], [bx, by]) = (points[k], points[(k + 1) % m]);
+                ax * by - bx * ay
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    /// A single round island in the middle of the map: one closed sea shore,
+    /// wound like the cells, consisting only of land–water edges.
+    #[test]
+    fn island_has_one_closed_shore() {
+        let mesh = mesh::build(2000, 8);
+        let h: Vec<u8> = mesh
+            .points
+            .iter()
+            .map(|&[x, y]| {
+                let d2 = (x - 5000.0).powi(2) + (y - 4000.0).powi(2);
+                if d2 < 2500.0f64.powi(2) {
+                    40
+                } else {
+                    5
+                }
+            })
+            .collect();
+        let shores = shorelines(&mesh, &h, &[], 0);
+        assert_eq!(shores.len(), 1);
+        let s = &shores[0];
+        assert!(s.closed);
+        assert_eq!((s.kind, s.feature), (ShoreKind::Sea, 0));
+        assert_eq!(s.points.len(), s.vertices.len());
+        // Same winding as a land cell ring.
+        let c = (0..mesh.points.len()).find(|&c| h[c] >= SEA_LEVEL).unwrap();
+        let ring: Vec<[f64; 2]> = mesh.cells.v
+            [mesh.cells.i[c] as usize..mesh.cells.i[c + 1] as usize]
+            .iter()
+            .map(|&v| mesh.vertices.p[v as usize])
+            .collect();
+        assert_eq!(area(&s.points).signum(), area(&ring).signum());
+        // Every shore vertex is shared by a land and a water cell.
+        let mut land_v = std::collections::BTreeSet::new();
+        let mut water_v = std::collections::BTreeSet::new();
+        for (cell, &hc) in h.iter().enumerate() {
+            let ring = &mesh.cells.v[mesh.cells.i[cell] as usize..mesh.cells.i[cell + 1] as usize];
+            let set = if hc >= SEA_LEVEL {
+                &mut land_v
+            } else {
+                &mut water_v
+            };
+            set.extend(ring.iter().copied());
+        }
+        assert!(s
+            .vertices
+            .iter()
+            .all(|v| land_v.contains(v) && water_v.contains(v)));
+    }
+
+    /// Lake shores come out with the lake's id, wound opposite to the lake's
+    /// own cells (land on the same side as for sea shores).
+    #[test]
+    fn lake_shores_are_reversed_and_tagged() {
+        let mesh = mesh::build(2000, 8);
+        let lake_cell = crate::heightmap::pick_cell(&mesh, 5000.0, 4000.0).unwrap() as usize;
+        let h: Vec<u8> = (0..mesh.points.len())
+            .map(|c| {
+                let [x, y] = mesh.points[c];
+                let d2 = (x - 5000.0).powi(2) + (y - 4000.0).powi(2);
+                if c == lake_cell {
+                    10
+                } else if d2 < 2500.0f64.powi(2) {
+                    40
+                } else {
+                    5
+                }
+            })
+            .collect();
+        let lake = LakeGeo {
+            id: 3,
+            cells: vec![lake_cell as u32],
+            ..LakeGeo::default()
+        };
+        let shores = shorelines(&mesh, &h, std::slice::from_ref(&lake), 0);
+        let lake_shore: Vec<&Shoreline> = shores
+            .iter()
+            .filter(|s| s.kind == ShoreKind::Lake)
+            .collect();
+        assert_eq!(lake_shore.len(), 1);
+        let s = lake_shore[0];
+        assert_eq!(s.feature, 3);
+        assert!(s.closed);
+        let ring: Vec<[f64; 2]> = mesh.cells.v
+            [mesh.cells.i[lake_cell] as usize..mesh.cells.i[lake_cell + 1] as usize]
+            .iter()
+            .map(|&v| mesh.vertices.p[v as usize])
+            .collect();
+        assert_eq!(s.vertices.len(), ring.len());
+        assert_eq!(area(&s.points).signum(), -area(&ring).signum());
+        // The lake is not reported as sea: one sea shore, the island's.
+        assert_eq!(
+            shores.iter().filter(|s| s.kind == ShoreKind::Sea).count(),
+            1
+        );
+        // An unnumbered lake (id 0) is still its own patch, not land.
+        let unnumbered = LakeGeo { id: 0, ..lake };
+        let shores = shorelines(&mesh, &h, &[unnumbered], 0);
+        assert_eq!(
+            shores
+                .iter()
+                .map(|s| (s.kind, s.feature))
+                .filter(|&(kind, _)| kind == ShoreKind::Lake)
+                .collect::<Vec<_>>(),
+            [(ShoreKind::Lake, 0)]
+        );
+    }
+
+    #[test]
+    fn chaikin_doubles_points_and_keeps_open_ends() {
+        let open = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0]];
+        let once = chaikin(&open, false, 1);
+        assert_eq!(once.len(), 2 + 2 * 2);
+        assert_eq!(once[0], [0.0, 0.0]);
+        assert_eq!(*once.last().unwrap(), [10.0, 10.0]);
+        assert_eq!(once[1], [2.5, 0.0]);
+        let closed = chaikin(&open, true, 2);
+        assert_eq!(closed.len(), 12);
+        assert_eq!(chaikin(&open, true, 0), open.to_vec());
+    }
+
+    /// On a generated world the shores are deterministic and every closed
+    /// sea ring is non-degenerate.
+    #[test]
+    fn generated_world_shores_are_deterministic() {
+        let grid = generate_world_inner(42, 3000, &climate::ClimateOpts::default());
+        let d = rivers::compute_drainage(
+            &grid.mesh,
+            &grid.cells.h,
+            &grid.cells.temp,
+            &grid.cells.prec,
+        );
+        let mut lakes = d.lakes;
+        for (i, lake) in lakes.iter_mut().enumerate() {
+            lake.id = (i + 1) as u32;
+        }
+        let a = shorelines(&grid.mesh, &grid.cells.h, &lakes, 3);
+        let b = shorelines(&grid.mesh, &grid.cells.h, &lakes, 3);
+        assert_eq!(a, b);
+        assert!(a.iter().any(|s| s.kind == ShoreKind::Sea && s.closed));
+        for s in &a {
+            assert!(s.vertices.len() >= 2);
+            if s.closed {
+                assert!(s.vertices.len() >= 3);
+                assert!(area(&s.points).abs() > 0.0);
+            }
+        }
+    }
+}
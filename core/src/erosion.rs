@@ -0,0 +1,301 @@
+//! Hydraulic and thermal erosion over `cells.h`.
+//!
+//! A post-process for any heightmap (`heightmap::generate`, templates,
+//! tectonics, imports) and the engine behind the `EditMode::Erode` brush.
+//! Everything runs on the Voronoi adjacency `cells.c` in `f64` and is
+//! quantized back to `u8` once at the end. No RNG: cells are visited in a
+//! total order (height, then id), so the result is a pure function of
+//! `(mesh, h, opts, weight)`.
+//!
+//! Each iteration runs one hydraulic and one thermal pass.
+//!
+//! - **Hydraulic (flux-based).** Every cell drains to its steepest lower
+//!   neighbour and receives one unit of rain; flux accumulates downhill.
+//!   Visiting cells from high to low, the carried sediment is compared with
+//!   the transport capacity `capacity · √flux · slope`: below capacity the
+//!   cell is eroded (at most `max_step`, never below its receiver), above it
+//!   the excess settles. Sediment reaching the sea settles offshore without
+//!   building new land, and pits fill up to their lowest rim. Land is never
+//!   cut below `SEA_LEVEL`, so the coastline (and what sits on it) stays put.
+//! - **Thermal (talus).** Any neighbour pair steeper than `talus` (height
+//!   units per cell spacing) sheds a `thermal_rate` share of the excess from
+//!   the higher cell to the lower one. Pairs across the coast are skipped.
+//!
+//! Slopes are measured per cell spacing `sqrt(W·H / n)`, so the same options
+//! give comparable results across cell counts.
+
+use serde::{Deserialize, Serialize};
+
+use crate::heightmap::SEA_LEVEL;
+use crate::mesh::Mesh;
+
+/// Upper bound on `ErosionOpts::iterations`.
+pub const MAX_ITERATIONS: u32 = 64;
+
+/// Erosion parameters. All fields are optional on the wire via
+/// `#[serde(default)]`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ErosionOpts {
+    /// Hydraulic + thermal passes (capped at `MAX_ITERATIONS`).
+    pub iterations: u32,
+    /// Transport capacity per `√flux · slope`.
+    pub capacity: f64,
+    /// Share of the unused capacity picked up per pass.
+    pub erosion_rate: f64,
+    /// Share of the excess sediment dropped per pass.
+    pub deposition_rate: f64,
+    /// Most a cell can be lowered per pass, in height units.
+    pub max_step: f64,
+    /// Steepest stable slope for thermal erosion, height units per spacing.
+    pub talus: f64,
+    /// Share of the above-talus excess moved per pass.
+    pub thermal_rate: f64,
+}
+
+impl Default for ErosionOpts {
+    fn default() -> Self {
+        ErosionOpts {
+            iterations: 8,
+            capacity: 0.08,
+            erosion_rate: 0.5,
+            deposition_rate: 0.4,
+            max_step: 1.5,
+            talus: 6.0,
+            thermal_rate: 0.5,
+        }
+    }
+}
+
+/// Erode the whole heightmap in place.
+pub fn erode(mesh: &Mesh, h: &mut [u8], opts: &ErosionOpts) {
+    erode_weighted(mesh, h, opts, &vec![1.0; h.len()]);
+}
+
+/// Erode with a per-cell `weight` in `0..=1` scaling how much each cell may
+/// change (the brush falloff). Flux and sediment still route through
+/// zero-weight cells, which stay untouched.
+pub fn erode_weighted(mesh: &Mesh, h: &mut [u8], opts: &ErosionOpts, weight: &[f64]) {
+    let n = h.len();
+    if n == 0 {
+        return;
+    }
+    let spacing = (mesh.world_w * mesh.world_h / n as f64).sqrt();
+    let mut z: Vec<f64> = h.iter().map(|&v| v as f64).collect();
+    for _ in 0..opts.iterations.min(MAX_ITERATIONS) {
+        hydraulic(mesh, &mut z, opts, weight, spacing);
+        thermal(mesh, &mut z, opts, weight, spacing);
+    }
+    // Rounding must not move a cell across the coast either.
+    for (out, v) in h.iter_mut().zip(&z) {
+        let v = v.clamp(0.0, 100.0).round() as u8;
+        *out = if *out >= SEA_LEVEL {
+            v.max(SEA_LEVEL)
+        } else {
+            v.min(SEA_LEVEL - 1)
+        };
+    }
+}
+
+/// In-range neighbours of `c` with their distance in cell spacings.
+fn neighbors(mesh: &Mesh, c: usize, spacing: f64) -> impl Iterator<Item = (usize, f64)> + '_ {
+    let n = mesh.points.len();
+    mesh.cells
+        .neighbors_of_cell(c)
+        .iter()
+        .map(|&d| d as usize)
+        .filter(move |&d| d < n)
+        .map(move |d| {
+            let dist = mesh.distance_sq(mesh.points[c], mesh.points[d]).sqrt() / spacing;
+            (d, dist.max(1e-6))
+        })
+}
+
+/// One flux-routing pass: rain, steepest-descent receivers, then erosion and
+/// deposition from the highest cell down.
+fn hydraulic(mesh: &Mesh, z: &mut [f64], opts: &ErosionOpts, weight: &[f64], spacing: f64) {
+    let n = z.len();
+    let sea = SEA_LEVEL as f64;
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| z[b].total_cmp(&z[a]).then(a.cmp(&b)));
+
+    // Steepest lower neighbour and the slope towards it.
+    let receiver: Vec<Option<(usize, f64)>> = (0..n)
+        .map(|c| {
+            neighbors(mesh, c, spacing)
+                .filter(|&(d, _)| z[d] < z[c])
+                .map(|(d, dist)| (d, (z[c] - z[d]) / dist))
+                .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+        })
+        .collect();
+
+    let mut flux = vec![1.0f64; n];
+    for &c in &order {
+        if let Some((r, _)) = receiver[c] {
+            flux[r] += flux[c];
+        }
+    }
+
+    let mut sediment = vec![0.0f64; n];
+    for &c in &order {
+        let w = weight[c];
+        let mut carried = sediment[c];
+        match receiver[c] {
+            None => {
+                // Pit: fill towards the lowest rim and lose the rest.
+                let rim = neighbors(mesh, c, spacing)
+                    .map(|(d, _)| z[d])
+                    .fold(f64::INFINITY, f64::min);
+                let mut room = (rim - z[c]).max(0.0);
+                if z[c] < sea {
+                    room = room.min((sea - 0.6 - z[c]).max(0.0));
+                }
+                z[c] += carried.min(room) * w;
+                continue;
+            }
+            Some((r, slope)) => {
+                let capacity = if z[c] < sea {
+                    0.0
+                } else {
+                    opts.capacity * flux[c].sqrt() * slope
+                };
+                if carried > capacity {
+                    let mut drop = (carried - capacity) * opts.deposition_rate;
+                    if z[c] < sea {
+                        // Offshore: settle without building new land.
+                        drop = drop.min((sea - 0.6 - z[c]).max(0.0));
+                    }
+                    drop *= w;
+                    z[c] += drop;
+                    carried -= drop;
+                } else {
+                    let floor = z[r].max(sea);
+                    let cut = ((capacity - carried) * opts.erosion_rate)
+                        .min(opts.max_step)
+                        .min((z[c] - floor).max(0.0))
+                        * w;
+                    z[c] -= cut;
+                    carried += cut;
+                }
+                sediment[r] += carried;
+            }
+        }
+    }
+}
+
+/// One talus pass: every over-steep neighbour pair moves material downhill.
+/// Transfers are accumulated against a snapshot, so visit order is moot.
+fn thermal(mesh: &Mesh, z: &mut [f64], opts: &ErosionOpts, weight: &[f64], spacing: f64) {
+    let n = z.len();
+    let sea = SEA_LEVEL as f64;
+    let mut delta = vec![0.0f64; n];
+    for c in 0..n {
+        let degree = mesh.cells.neighbors_of_cell(c).len().max(1) as f64;
+        for (d, dist) in neighbors(mesh, c, spacing) {
+            let excess = z[c] - z[d] - opts.talus * dist;
+            // Slopes across the coast are left alone (see module doc).
+            if excess <= 0.0 || (z[c] >= sea) != (z[d] >= sea) {
+                continue;
+            }
+            let moved = excess * 0.5 * opts.thermal_rate / degree * weight[c].min(weight[d]);
+            delta[c] -= moved;
+            delta[d] += moved;
+        }
+    }
+    for (v, dv) in z.iter_mut().zip(&delta) {
+        *v += dv;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh;
+
+    fn cone(mesh: &Mesh) -> Vec<u8> {
+        mesh.points
+            .iter()
+            .map(|&[x, y]| {
+                let d = ((x - 5000.0).powi(2) + (y - 4000.0).powi(2)).sqrt();
+                (90.0 - d / 40.0).clamp(5.0, 90.0) as u8
+            })
+            .collect()
+    }
+
+    /// Same input, same output; erosion changes land and keeps the coast.
+    #[test]
+    fn deterministic_and_keeps_coastline() {
+        let mesh = mesh::build(3000, 4);
+        let h0 = cone(&mesh);
+        let mut a = h0.clone();
+        let mut b = h0.clone();
+        erode(&mesh, &mut a, &ErosionOpts::default());
+        erode(&mesh, &mut b, &ErosionOpts::default());
+        assert_eq!(a, b);
+        assert_ne!(a, h0);
+        for (c, (&before, &after)) in h0.iter().zip(&a).enumerate() {
+            assert_eq!(before >= SEA_LEVEL, after >= SEA_LEVEL, "cell {c}");
+        }
+    }
+
+    /// Hydraulic erosion cuts channels: the summit's slopes end up less
+    /// uniform, and no land cell rises above the original peak.
+    #[test]
+    fn hydraulic_carves_and_deposits() {
+        let mesh = mesh::build(3000, 4);
+        let h0 = cone(&mesh);
+        let mut h = h0.clone();
+        let opts = ErosionOpts {
+            talus: f64::INFINITY,
+            ..ErosionOpts::default()
+        };
+        erode(&mesh, &mut h, &opts);
+        let lowered = h0.iter().zip(&h).filter(|(a, b)| b < a).count();
+        let raised = h0.iter().zip(&h).filter(|(a, b)| b > a).count();
+        assert!(
+            lowered > 0 && raised > 0,
+            "{lowered} lowered, {raised} raised"
+        );
+        assert!(h.iter().max() <= h0.iter().max());
+    }
+
+    /// A lone spike slumps under thermal erosion; a gentle slope doesn't.
+    #[test]
+    fn thermal_flattens_only_steep_slopes() {
+        let mesh = mesh::build(1000, 2);
+        let spike = crate::heightmap::pick_cell(&mesh, 5000.0, 4000.0).unwrap() as usize;
+        let mut h = vec![40u8; mesh.points.len()];
+        h[spike] = 90;
+        let opts = ErosionOpts {
+            capacity: 0.0,
+            ..ErosionOpts::default()
+        };
+        let spiked = h.clone();
+        erode(&mesh, &mut h, &opts);
+        assert!(h[spike] < 80);
+        let mut gentle = vec![40u8; mesh.points.len()];
+        erode(&mesh, &mut gentle, &opts);
+        assert_eq!(gentle, vec![40u8; mesh.points.len()]);
+        assert_ne!(h, spiked);
+    }
+
+    /// Zero-weight cells are never modified.
+    #[test]
+    fn weight_confines_changes() {
+        let mesh = mesh::build(2000, 6);
+        let h0 = cone(&mesh);
+        let weight: Vec<f64> = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| if x < 5000.0 { 1.0 } else { 0.0 })
+            .collect();
+        let mut h = h0.clone();
+        erode_weighted(&mesh, &mut h, &ErosionOpts::default(), &weight);
+        for c in 0..h.len() {
+            if weight[c] == 0.0 {
+                assert_eq!(h[c], h0[c], "cell {c}");
+            }
+        }
+        assert_ne!(h, h0);
+    }
+}
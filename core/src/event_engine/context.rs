@@ -23,7 +23,7 @@
 use super::params::TimelineParams;
 use crate::entities::{Burg, Culture, Pack, Religion, State};
 use crate::mesh::Cells;
-use crate::timeline::{EntityType, Event, EventKind, EventPayload};
+use crate::timeline::{climate_shift, ClimateShift, EntityType, Event, EventKind, EventPayload};
 use rand::rngs::StdRng;
 
 /// Simulation/world state: the working `Pack` plus the per-cell entity index
@@ -174,14 +174,17 @@ impl GenMap {
     }
 }
 
-/// Timeline/event state: era bounds, tunable parameters, the emitted event
-/// sink, and the monotonic event-id counter.
+/// Timeline/event state: era bounds, tunable parameters, the era climate,
+/// the emitted event sink, and the monotonic event-id counter.
 pub struct GenTimeline {
     /// The era bounds.
     pub era_start: i32,
     pub era_end: i32,
     /// The parameters.
     pub params: TimelineParams,
+    /// The era climate in the year being generated (from `params.climate`;
+    /// see [`GenTimeline::enter_year`]).
+    pub climate: ClimateShift,
     /// Shared event sink. Modules append events here via [`GenTimeline::emit`]
     /// or [`GenTimeline::push_event`].
     events: Vec<Event>,
@@ -196,12 +199,19 @@ impl GenTimeline {
         GenTimeline {
             era_start,
             era_end,
+            climate: climate_shift(&params.climate, era_start),
             params,
             events: Vec::new(),
             next_id,
         }
     }
 
+    /// Move the era climate to `year`. The engine calls this before running
+    /// the year's modules.
+    pub fn enter_year(&mut self, year: i32) {
+        self.climate = climate_shift(&self.params.climate, year);
+    }
+
     /// Assign the next deterministic event id.
     fn next_event_id(&mut self) -> u64 {
         let id = self.next_id;
@@ -292,6 +302,12 @@ impl GenContext {
         self.map.is_land(idx)
     }
 
+    /// The era climate in the year being generated: how far temperature and
+    /// precipitation have drifted from the year-0 climate.
+    pub fn climate(&self) -> ClimateShift {
+        self.timeline.climate
+    }
+
     /// Get cell neighbors of `cell` from the Voronoi/Delaunay topology.
     pub(crate) fn neighbors_of_cell(&self, cell: u32) -> Vec<u32> {
         self.map.neighbors_of_cell(cell as usize).to_vec()
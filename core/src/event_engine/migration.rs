@@ -1,7 +1,8 @@
 //! Phase 4 Step 4.2 — `Migrate` event module.
 //!
 //! A culture spreads to adjacent cells. Probability is
-//! `ctx.timeline.params.migration_prob` per culture per year.
+//! `ctx.timeline.params.migration_prob` per culture per year, raised while the
+//! era climate runs colder or drier than year 0 (`ctx.climate()`).
 //!
 //! Extracted from the monolithic `event_engine.rs` (refactor §P4.2-modular).
 
@@ -12,6 +13,12 @@ use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::Rng;
 
+/// Extra migration odds per °C the era runs colder than year 0.
+const COLD_MIGRATION: f64 = 0.1;
+
+/// Extra migration odds per unit of precipitation scale below year 0.
+const DRY_MIGRATION: f64 = 1.0;
+
 /// The migration event module.
 pub struct MigrationModule;
 
@@ -35,8 +42,14 @@ impl EventModule for MigrationModule {
             .map(|c| c.id)
             .collect();
 
+        let climate = ctx.climate();
+        let odds = 1.0
+            + COLD_MIGRATION * (-climate.temp_offset).max(0.0)
+            + DRY_MIGRATION * (1.0 - climate.prec_scale).max(0.0);
+        let prob = (ctx.timeline.params.migration_prob * odds).min(1.0);
+
         for culture_id in eligible {
-            if !rng.gen_bool(ctx.timeline.params.migration_prob) {
+            if !rng.gen_bool(prob) {
                 continue;
             }
 
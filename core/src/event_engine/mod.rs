@@ -234,6 +234,7 @@ pub fn generate_timeline_with_modules(
         // year Y+1 (a determinism hazard).
         let year_seed = rng_seed.wrapping_add((year as u64).wrapping_mul(0x100000003));
         let mut year_rng = StdRng::seed_from_u64(year_seed);
+        ctx.timeline.enter_year(year);
 
         for module in modules {
             module.run(&mut ctx, &mut year_rng, year);
@@ -322,6 +323,7 @@ pub fn generate_timeline_with_modules_inner(
     for year in ctx.timeline.era_start..ctx.timeline.era_end {
         let year_seed = rng_seed.wrapping_add((year as u64).wrapping_mul(0x100000003));
         let mut year_rng = StdRng::seed_from_u64(year_seed);
+        ctx.timeline.enter_year(year);
 
         for module in modules {
             module.run(&mut ctx, &mut year_rng, year);
@@ -414,6 +416,7 @@ mod tests {
         for year in ctx.timeline.era_start..ctx.timeline.era_end {
             let year_seed = rng_seed.wrapping_add((year as u64).wrapping_mul(0x100000003));
             let mut year_rng = StdRng::seed_from_u64(year_seed);
+            ctx.timeline.enter_year(year);
             for module in modules {
                 module.run(&mut ctx, &mut year_rng, year);
             }
@@ -1154,6 +1157,84 @@ mod tests {
         );
     }
 
+    /// A cold, wet era (read by the plague module through `ctx.climate()`)
+    /// brings more plagues than the year-0 climate.
+    #[test]
+    fn plagues_follow_the_era_climate() {
+        let pack = make_pack(5, 3, 2, 5);
+        let (cells, cs, cc, cr, cb, ch, cp) = make_cells_with_mesh(100, 42, 5);
+        let plagues = |climate: Vec<crate::timeline::ClimateKeyframe>| {
+            let params = TimelineParams {
+                era_start: 0,
+                era_end: 400,
+                climate,
+                ..Default::default()
+            };
+            let timeline =
+                generate_timeline(&pack, &cs, &cc, &cr, &cb, &ch, &cp, &cells, 42, &params);
+            timeline
+                .iter()
+                .filter(|e| e.kind == EventKind::Plague)
+                .count()
+        };
+        let long_winter = vec![crate::timeline::ClimateKeyframe {
+            year: 0,
+            temp_offset: -10.0,
+            prec_scale: 1.2,
+        }];
+        let (mild, cold) = (plagues(Vec::new()), plagues(long_winter));
+        assert!(
+            cold as f64 > 1.5 * mild as f64,
+            "{cold} plagues in the cold era vs {mild} in the mild one"
+        );
+    }
+
+    /// A cold, dry era (read by the migration module through `ctx.climate()`)
+    /// drives more migrations than the year-0 climate.
+    #[test]
+    fn migrations_follow_the_era_climate() {
+        // Three culture bands on a real mesh, as in
+        // `migration_transfers_are_distinct_and_consistent`, so every culture
+        // is large enough to migrate and has a border to migrate across.
+        let mesh = crate::mesh::build(300, 99);
+        let n = mesh.points.len();
+        let cc: Vec<i32> = (0..n).map(|i| (i * 3 / n) as i32 + 1).collect();
+        let mut pack = make_pack(3, 3, 0, 3);
+        for (i, c) in pack.cultures.iter_mut().enumerate() {
+            c.cell_count = cc.iter().filter(|&&v| v == i as i32 + 1).count() as u32;
+        }
+        let cs: Vec<i32> = (0..n).map(|i| (i % 3) as i32 + 1).collect();
+        let (cr, cb) = (vec![0i32; n], vec![0i16; n]);
+        let ch = vec![50u8; n];
+        let cp: Vec<i32> = (0..n as i32).collect();
+        let cells = &mesh.cells;
+        let migrations = |climate: Vec<crate::timeline::ClimateKeyframe>| {
+            let params = TimelineParams {
+                era_start: 0,
+                era_end: 400,
+                migration_prob: 0.02,
+                climate,
+                ..Default::default()
+            };
+            let timeline =
+                generate_timeline(&pack, &cs, &cc, &cr, &cb, &ch, &cp, cells, 99, &params);
+            timeline
+                .iter()
+                .filter(|e| e.kind == EventKind::Migrate)
+                .count()
+        };
+        let drought = vec![crate::timeline::ClimateKeyframe {
+            year: 0,
+            temp_offset: -10.0,
+            prec_scale: 0.5,
+        }];
+        let (mild, harsh) = (migrations(Vec::new()), migrations(drought));
+        assert!(
+            mild > 0 && harsh as f64 > 1.5 * mild as f64,
+            "{harsh} migrations in the harsh era vs {mild} in the mild one"
+        );
+    }
+
     #[test]
     fn produces_golden_age_events() {
         let pack = make_pack(5, 3, 2, 5);
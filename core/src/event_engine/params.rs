@@ -10,6 +10,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::timeline::ClimateDrift;
+
 /// Tunable parameters for the event generator. All fields have deterministic
 /// defaults so that omitting `opts` in a `generate_timeline` call produces a
 /// reproducible world.
@@ -55,6 +57,11 @@ pub struct TimelineParams {
     /// Random number generator seed override. If 0, the engine derives a
     /// sub-stream from the timeline seed. Default: 0 (derive).
     pub rng_override: u64,
+    /// Era climate keyframes (temperature offset, precipitation scale over
+    /// the years). Modules read the current year's climate through
+    /// `GenContext::climate`. Default: none (the year-0 climate throughout).
+    #[serde(default)]
+    pub climate: ClimateDrift,
 }
 
 impl Default for TimelineParams {
@@ -76,6 +83,7 @@ impl Default for TimelineParams {
             min_state_pop: 1.0,
             min_burg_spacing: 0,
             rng_override: 0,
+            climate: Vec::new(),
         }
     }
 }
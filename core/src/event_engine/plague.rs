@@ -1,7 +1,8 @@
 //! Phase 4 Step 4.2 — `Plague` event module.
 //!
 //! A plague reduces population in a state. Probability is
-//! `ctx.timeline.params.plague_prob` per state per year.
+//! `ctx.timeline.params.plague_prob` per state per year, raised while the era
+//! climate runs colder or wetter than year 0 (`ctx.climate()`).
 //!
 //! Extracted from the monolithic `event_engine.rs` (refactor §P4.2-modular).
 
@@ -11,6 +12,12 @@ use crate::timeline::{EntityType, EventKind, EventPayload};
 use rand::rngs::StdRng;
 use rand::Rng;
 
+/// Extra plague odds per °C the era runs colder than year 0.
+const COLD_PLAGUE: f64 = 0.1;
+
+/// Extra plague odds per unit of precipitation scale above year 0.
+const WET_PLAGUE: f64 = 0.5;
+
 /// The plague event module.
 pub struct PlagueModule;
 
@@ -30,8 +37,14 @@ impl EventModule for PlagueModule {
             .map(|s| s.id)
             .collect();
 
+        let climate = ctx.climate();
+        let odds = 1.0
+            + COLD_PLAGUE * (-climate.temp_offset).max(0.0)
+            + WET_PLAGUE * (climate.prec_scale - 1.0).max(0.0);
+        let prob = (ctx.timeline.params.plague_prob * odds).min(1.0);
+
         for state_id in eligible {
-            if rng.gen_bool(ctx.timeline.params.plague_prob) {
+            if rng.gen_bool(prob) {
                 let factor =
                     1.0 - (ctx.timeline.params.plague_mortality * rng.gen_range(0.5..=1.0));
                 scale_state_pops(ctx, state_id, factor);
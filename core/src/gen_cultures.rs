@@ -311,13 +311,15 @@ fn generate_cultures(
         let mut too_close = false;
         for dy in -1i32..=1 {
             for dx in -1i32..=1 {
-                let nx = bx as i32 + dx;
+                let Some(nx) = grid.mesh.bucket_col(bx as i32 + dx, cols) else {
+                    continue;
+                };
                 let ny = by as i32 + dy;
-                if nx < 0 || ny < 0 || nx >= cols as i32 || ny >= rows as i32 {
+                if ny < 0 || ny >= rows as i32 {
                     continue;
                 }
-                for &[px, py] in &occupied[ny as usize * cols + nx as usize] {
-                    let dist2 = (px - x).powi(2) + (py - y).powi(2);
+                for &[px, py] in &occupied[ny as usize * cols + nx] {
+                    let dist2 = grid.mesh.distance_sq([px, py], [x, y]);
                     if dist2 < spacing * spacing {
                         too_close = true;
                         break;
@@ -832,13 +834,15 @@ fn generate_religions(
         let mut too_close = false;
         for dy in -1i32..=1 {
             for dx in -1i32..=1 {
-                let nx = bx as i32 + dx;
+                let Some(nx) = grid.mesh.bucket_col(bx as i32 + dx, cols) else {
+                    continue;
+                };
                 let ny = by as i32 + dy;
-                if nx < 0 || ny < 0 || nx >= cols as i32 || ny >= rows as i32 {
+                if ny < 0 || ny >= rows as i32 {
                     continue;
                 }
-                for &[px, py] in &occupied[ny as usize * cols + nx as usize] {
-                    let dist2 = (px - x).powi(2) + (py - y).powi(2);
+                for &[px, py] in &occupied[ny as usize * cols + nx] {
+                    let dist2 = grid.mesh.distance_sq([px, py], [x, y]);
                     if dist2 < spacing * spacing {
                         too_close = true;
                         break;
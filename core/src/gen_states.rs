@@ -318,14 +318,16 @@ fn seed_capitals(
         let mut too_close = false;
         for dy in -1i32..=1 {
             for dx in -1i32..=1 {
-                let nx = bx as i32 + dx;
+                let Some(nx) = grid.mesh.bucket_col(bx as i32 + dx, cols) else {
+                    continue;
+                };
                 let ny = by as i32 + dy;
-                if nx < 0 || ny < 0 || nx >= cols as i32 || ny >= rows as i32 {
+                if ny < 0 || ny >= rows as i32 {
                     continue;
                 }
-                let bucket = occupied[ny as usize * cols + nx as usize].as_slice();
+                let bucket = occupied[ny as usize * cols + nx].as_slice();
                 for &[px, py] in bucket {
-                    let dist2 = (px - x).powi(2) + (py - y).powi(2);
+                    let dist2 = grid.mesh.distance_sq([px, py], [x, y]);
                     if dist2 < spacing * spacing {
                         too_close = true;
                         break;
@@ -532,14 +534,16 @@ fn generate_towns(
         let mut too_close = false;
         for dy in -1i32..=1 {
             for dx in -1i32..=1 {
-                let nx = bx as i32 + dx;
+                let Some(nx) = grid.mesh.bucket_col(bx as i32 + dx, cols) else {
+                    continue;
+                };
                 let ny = by as i32 + dy;
-                if nx < 0 || ny < 0 || nx >= cols as i32 || ny >= rows as i32 {
+                if ny < 0 || ny >= rows as i32 {
                     continue;
                 }
-                let bucket = &occupied[ny as usize * cols + nx as usize];
+                let bucket = &occupied[ny as usize * cols + nx];
                 for &[px, py] in bucket {
-                    let dist2 = (px - x).powi(2) + (py - y).powi(2);
+                    let dist2 = grid.mesh.distance_sq([px, py], [x, y]);
                     if dist2 < min_spacing2 {
                         too_close = true;
                         break;
@@ -681,6 +685,10 @@ pub fn hsl_to_rgb_u32(h: f64, s: f64, l: f64) -> u32 {
 /// Expand each state's frontier from its capital cell using a Dijkstra-like
 /// priority queue. Cost per neighbor cell is composed of culture, population,
 /// biome, height, river, and type costs, scaled by `1/expansionism`.
+///
+/// The frontier walks the mesh adjacency only, so on a wrapping mesh
+/// (`Topology::Cylinder` / `Globe`) states spread across the east–west seam
+/// like across any other cell edge.
 fn expand_states(
     grid: &Grid,
     suitability: &[f64],
@@ -909,7 +917,7 @@ fn subdivide_provinces(
             let [cx, cy] = grid.mesh.points[c];
             let too_close = seeds.iter().any(|&s| {
                 let [sx, sy] = grid.mesh.points[s];
-                let d2 = (sx - cx).powi(2) + (sy - cy).powi(2);
+                let d2 = grid.mesh.distance_sq([sx, sy], [cx, cy]);
                 d2 < min_spacing * min_spacing
             });
             if !too_close {
@@ -1246,6 +1254,38 @@ mod tests {
         generate_world_inner(seed, n, &opts)
     }
 
+    /// On a cylinder the frontier crosses the seam: on an all-land band
+    /// some state owns cells on both sides of `x = 0` that are neighbours
+    /// across it.
+    #[test]
+    fn states_expand_across_seam_on_cylinder() {
+        let opts = climate::ClimateOpts::default();
+        let mut grid = crate::generate_world_with_topology_inner(
+            5,
+            2000,
+            crate::mesh::Topology::Cylinder,
+            &opts,
+        );
+        // Raise the ocean (templates mask the map edges to water) so the seam
+        // is land.
+        for c in 0..grid.cell_count() {
+            grid.cells.h[c] = grid.cells.h[c].max(30);
+            if grid.cells.biome[c] == 0 {
+                grid.cells.biome[c] = 6;
+            }
+        }
+        let r = generate_states(&grid, 5, 4);
+        let spans_seam = (0..grid.cell_count()).any(|a| {
+            let sa = r.cells_state[a];
+            sa > 0
+                && grid.mesh.cells.neighbors_of_cell(a).iter().any(|&b| {
+                    let dx = grid.mesh.points[b as usize][0] - grid.mesh.points[a][0];
+                    dx.abs() > grid.mesh.world_w / 2.0 && r.cells_state[b as usize] == sa
+                })
+        });
+        assert!(spans_seam, "no state crossed the east–west seam");
+    }
+
     #[test]
     fn determinism_same_seed_same_output() {
         let grid = test_grid(42, 1000);
@@ -44,6 +44,12 @@ pub struct CellData {
     pub fl: Vec<u16>,
     pub r: Vec<u16>,
     pub conf: Vec<u16>,
+    /// Edit lock, set by `EditMode::Lock` / `Unlock`. Locked cells keep their
+    /// height through edits and heightmap regeneration, and their entity
+    /// indices through the repair cascade. Empty in grids saved before locks
+    /// existed, which reads as nothing locked (see `is_locked`).
+    #[serde(default)]
+    pub locked: Vec<bool>,
 }
 
 impl CellData {
@@ -65,8 +71,14 @@ impl CellData {
             fl: vec![0u16; n],
             r: vec![0u16; n],
             conf: vec![0u16; n],
+            locked: vec![false; n],
         }
     }
+
+    /// Whether cell `c` is locked against edits.
+    pub fn is_locked(&self, c: usize) -> bool {
+        self.locked.get(c).copied().unwrap_or(false)
+    }
 }
 
 /// The world: geometry (mesh) + per-cell data. This is the unit that
@@ -141,6 +153,7 @@ mod tests {
             vertices: Vertices { p: vec![] },
             world_w: 10000.0,
             world_h: 8000.0,
+            ..Default::default()
         }
     }
 
@@ -306,6 +319,36 @@ pub struct LakeGeo {
     pub closed: bool,
 }
 
+/// Which water body a [`Shoreline`] bounds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShoreKind {
+    /// Land against ocean (any water cell that is not part of a lake).
+    Sea,
+    /// Land around a [`LakeGeo`].
+    Lake,
+}
+
+/// An ordered shoreline traced along the Voronoi edges between land and
+/// water cells (`coast::shorelines`). Every shoreline is oriented the same
+/// way relative to land: walking it, land lies on the side the cell rings
+/// wind towards (left in screen space, `y` down).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Shoreline {
+    pub kind: ShoreKind,
+    /// `Sea`: landmass index (0-based, landmasses ordered by lowest cell id).
+    /// `Lake`: the [`LakeGeo::id`] it surrounds.
+    pub feature: u32,
+    /// Vertex ids into `Vertices.p` along the shore.
+    pub vertices: Vec<u32>,
+    /// Polyline coordinates: the vertex positions, Chaikin-smoothed if
+    /// requested. `x` is unwrapped across a cylinder seam so the line is
+    /// continuous (it may leave `[0, world_w]`).
+    pub points: Vec<[f64; 2]>,
+    /// Closed ring (first point not repeated) or open polyline (a shore
+    /// running into the map edge).
+    pub closed: bool,
+}
+
 /// Output of `recompute_dependents` (Step 2.5.3). Carries the freshly
 /// recomputed per-cell arrays (climate + biomes + entity indices post-repair)
 /// and the new river/lake geometry. The renderer swaps data textures from
@@ -26,13 +26,17 @@
 
 use js_sys::Uint8Array;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::mesh::{Cells, Mesh};
+use crate::noise::{self, NoiseKind};
 
 /// Sea level in the height scale. `< 20` is water (technical-requirements §3.1).
 pub const SEA_LEVEL: u8 = 20;
 
+/// Octaves of a noise step that doesn't name them.
+const NOISE_OCTAVES: u32 = 5;
+
 /// Owned mesh needed by the heightmap (we only read `cells`/`points`/`world_w`).
 /// `mesh::Mesh` is the serde target; here we wrap the bits we need so this
 /// module doesn't depend on the WASM `Serialize` derive surface.
@@ -44,6 +48,9 @@ pub struct MeshView {
     pub world_w: f64,
     #[serde(rename = "world_h")]
     pub world_h: f64,
+    /// Whether `x = 0` and `x = world_w` meet (see `Topology::wraps_x`).
+    #[serde(default)]
+    pub wraps_x: bool,
 }
 
 impl MeshView {
@@ -56,6 +63,7 @@ impl MeshView {
             cells: mesh.cells.clone(),
             world_w: mesh.world_w,
             world_h: mesh.world_h,
+            wraps_x: mesh.topology.wraps_x(),
         }
     }
 }
@@ -167,42 +175,15 @@ fn find_grid_cell(view: &MeshView, x: f64, y: f64) -> usize {
     view.cells.spacing.get(slot).copied().unwrap_or(0) as usize
 }
 
-/// Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Uses the
-/// `cells.spacing` spatial grid (`find_grid_cell`) to get a bucket cell,
-/// then refines by checking the bucket cell + its neighbors for the truly
-/// nearest one (Euclidean distance to cell center). O(1)-ish, deterministic.
+/// Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Exact — a
+/// ring search over a spatial index built for this one query
+/// (`SpatialIndex::nearest`), ties to the lower cell id. Out-of-world points
+/// pick the nearest edge cell. Callers picking repeatedly on one mesh keep
+/// their own index instead.
 ///
 /// Returns `None` only if the mesh has no cells (edge case).
 pub fn pick_cell(mesh: &crate::mesh::Mesh, x: f64, y: f64) -> Option<u32> {
-    let n = mesh.points.len();
-    if n == 0 {
-        return None;
-    }
-    let view = MeshView::from_mesh(mesh);
-    let bucket = find_grid_cell(&view, x, y);
-
-    // Check the bucket cell + its neighbors for the nearest one.
-    let mut best = bucket;
-    let [bx, by] = mesh.points[bucket];
-    let mut best_d2 = (bx - x).powi(2) + (by - y).powi(2);
-
-    let csr_i = &mesh.cells.i;
-    let csr_c = &mesh.cells.c;
-    if bucket < csr_i.len() - 1 {
-        let lo = csr_i[bucket] as usize;
-        let hi = csr_i[bucket + 1] as usize;
-        for &nb in &csr_c[lo..hi] {
-            let nb = nb as usize;
-            let [px, py] = mesh.points[nb];
-            let d2 = (px - x).powi(2) + (py - y).powi(2);
-            if d2 < best_d2 {
-                best_d2 = d2;
-                best = nb;
-            }
-        }
-    }
-
-    Some(best as u32)
+    crate::spatial::SpatialIndex::build(mesh).nearest(mesh, x, y)
 }
 
 /// Pick a point at fractional range `[minFrac, maxFrac]` of the world axis.
@@ -294,12 +275,13 @@ fn add_hill(
     h: &mut [u8],
     rng: &mut StdRng,
     start: usize,
+    height: &str,
     blob_power: f64,
     max_cells: usize,
 ) {
     let n = h.len();
     let mut change = vec![0f64; n];
-    let height = lim(get_number_in_range(rng, "85-100"));
+    let height = lim(get_number_in_range(rng, height));
     // Avoid starting on an already-too-high cell: walk to a neighbor that is
     // low enough. Pick the lowest-height neighbor (not just the first CSR slot,
     // which is an arbitrary, fixed order) so the escape actually descends toward
@@ -377,6 +359,7 @@ fn add_pit(
     h: &mut [u8],
     rng: &mut StdRng,
     start: usize,
+    height: &str,
     blob_power: f64,
     max_cells: usize,
 ) {
@@ -394,7 +377,7 @@ fn add_pit(
         }
         limit += 1;
     }
-    let height = lim(get_number_in_range(rng, "10-20")) as f64;
+    let height = lim(get_number_in_range(rng, height)) as f64;
     // Per-cell change map (negative). f64 throughout, quantize to u8 ONCE at the
     // end — same contract as add_hill's `change: Vec<f64>`.
     let mut change = vec![0f64; n];
@@ -495,6 +478,7 @@ fn add_ridge(
     rng: &mut StdRng,
     start: usize,
     end: usize,
+    height: &str,
     randomness: f64,
     line_power: f64,
     raise: bool,
@@ -502,10 +486,7 @@ fn add_ridge(
 ) {
     let n = h.len();
     let mut used = vec![false; n];
-    let mut height = lim(get_number_in_range(
-        rng,
-        if raise { "30-60" } else { "20-30" },
-    ));
+    let mut height = lim(get_number_in_range(rng, height));
     let range = build_range(view, rng, start, end, randomness);
     let mut queue: Vec<usize> = range.clone();
     for q in &queue {
@@ -634,14 +615,154 @@ fn mask(view: &MeshView, h: &mut [u8], power: f64) {
     }
 }
 
-/// FMG `modify(range="land", mult)`: scale land cells around sea level.
-/// `mult` is the multiplier (a2); cells below `SEA_LEVEL` are unaffected.
-fn multiply_land(h: &mut [u8], mult: f64) {
+/// A height filter: `"all"`, `"land"` or `"lo-hi"` as inclusive bounds.
+fn height_range(range: &str) -> Option<(f64, f64)> {
+    match range {
+        "land" => Some((SEA_LEVEL as f64, 100.0)),
+        "all" => Some((0.0, 100.0)),
+        r => r.split_once('-').map(|(a, b)| {
+            (
+                a.trim().parse().unwrap_or(0.0),
+                b.trim().parse().unwrap_or(100.0),
+            )
+        }),
+    }
+}
+
+/// FMG `modify(range, add, mult)`: shift and/or scale the cells whose height
+/// lies in `range` (`"all"`, `"land"` or `"lo-hi"`). A range starting at sea
+/// level is treated as land, exactly as in FMG: scaling pivots on
+/// `SEA_LEVEL` and adding never pushes a cell into the sea.
+fn modify(h: &mut [u8], range: &str, add: f64, mult: f64) {
+    let sea = SEA_LEVEL as f64;
+    let Some((min, max)) = height_range(range) else {
+        return;
+    };
+    let is_land = min == sea;
     for v in h.iter_mut() {
-        if *v >= SEA_LEVEL {
-            let scaled = ((*v as f64 - SEA_LEVEL as f64) * mult + SEA_LEVEL as f64).round();
-            *v = lim(scaled);
+        let mut x = *v as f64;
+        if x < min || x > max {
+            continue;
+        }
+        if add != 0.0 {
+            x = if is_land { (x + add).max(sea) } else { x + add };
+        }
+        if mult != 1.0 {
+            x = if is_land {
+                (x - sea) * mult + sea
+            } else {
+                x * mult
+            };
         }
+        *v = lim(x);
+    }
+}
+
+/// FMG `addStrait`: cut a channel of `width` rings across the map, top to
+/// bottom (`vertical`) or left to right, by lowering the cells along a
+/// wandering path with `h ** exp` (`exp < 1` flattens toward 1).
+fn add_strait(view: &MeshView, h: &mut [u8], rng: &mut StdRng, width: &str, vertical: bool) {
+    let n = h.len();
+    let max_width = (view.cells.cells_x as f64 / 3.0).max(1.0);
+    let mut width = get_number_in_range(rng, width).min(max_width);
+    if width < 1.0 {
+        return;
+    }
+    let (w, hh) = (view.world_w, view.world_h);
+    let (start_x, start_y) = if vertical {
+        ((rng.gen::<f64>() * w * 0.4 + w * 0.3).floor(), 5.0)
+    } else {
+        (5.0, (rng.gen::<f64>() * hh * 0.4 + hh * 0.3).floor())
+    };
+    let (end_x, end_y) = if vertical {
+        (
+            (w - start_x - w * 0.1 + rng.gen::<f64>() * w * 0.2).floor(),
+            hh - 5.0,
+        )
+    } else {
+        (
+            w - 5.0,
+            (hh - start_y - hh * 0.1 + rng.gen::<f64>() * hh * 0.2).floor(),
+        )
+    };
+    let start = find_grid_cell(view, start_x, start_y);
+    let end = find_grid_cell(view, end_x, end_y);
+    let mut range = build_range(view, rng, start, end, 0.2);
+    let mut used = vec![false; n];
+    // FMG never clears `query`, so every ring re-walks the earlier ones too.
+    let mut query = Vec::new();
+    let step = 0.1 / width;
+    while width > 0.0 {
+        let exp = 0.9 - step * width;
+        for &r in &range {
+            let (lo, hi) = (view.cells.i[r] as usize, view.cells.i[r + 1] as usize);
+            for &e in &view.cells.c[lo..hi] {
+                let e = e as usize;
+                if used[e] {
+                    continue;
+                }
+                used[e] = true;
+                query.push(e);
+                h[e] = lim((h[e] as f64).powf(exp));
+            }
+        }
+        range = query.clone();
+        width -= 1.0;
+    }
+}
+
+/// FMG `invert`: with probability `probability`, mirror the map along `x`,
+/// `y` or both (each cell takes the height of the cell nearest its mirror
+/// point).
+fn invert(view: &MeshView, h: &mut [u8], rng: &mut StdRng, probability: f64, axes: &str) {
+    if !p(rng, probability) {
+        return;
+    }
+    let (flip_x, flip_y) = (axes != "y", axes != "x");
+    let snapshot = h.to_vec();
+    for (i, &[x, y]) in view.points.iter().enumerate() {
+        let mx = if flip_x { view.world_w - x } else { x };
+        let my = if flip_y { view.world_h - y } else { y };
+        h[i] = snapshot[find_grid_cell(view, mx, my)];
+    }
+}
+
+/// Add `amplitude ×` a `kind` noise field (features about `scale` world
+/// units across) to the cells whose height lies in `range`. Like `modify`, a
+/// range starting at sea level is land and is never pushed into the sea;
+/// with `all` the noise can move the coastline.
+#[allow(clippy::too_many_arguments)]
+fn add_noise(
+    view: &MeshView,
+    h: &mut [u8],
+    rng: &mut StdRng,
+    kind: NoiseKind,
+    amplitude: f64,
+    scale: f64,
+    octaves: u32,
+    range: &str,
+) {
+    let Some((min, max)) = height_range(range) else {
+        return;
+    };
+    let sea = SEA_LEVEL as f64;
+    let is_land = min == sea;
+    let field = noise::field(
+        &view.points,
+        view.world_w,
+        view.wraps_x,
+        rng,
+        kind,
+        scale,
+        octaves,
+    );
+    for (v, n) in h.iter_mut().zip(field) {
+        let x = *v as f64;
+        if x < min || x > max {
+            continue;
+        }
+        let y = x + amplitude * n;
+        *v = lim(if is_land { y.max(sea) } else { y });
     }
 }
 
@@ -649,9 +770,9 @@ fn multiply_land(h: &mut [u8], mult: f64) {
 // Template runner
 // ---------------------------------------------------------------------------
 
-/// A single parsed template step. (We only implement the tools the default
-/// sequence uses — Hill/Pit/Range/Trough/Smooth/Mask — kept minimal and
-/// deterministic.)
+/// A single parsed template step. The tool set is FMG's: the flood tools
+/// (Hill/Pit/Range/Trough), the whole-map passes (Smooth/Mask/Add/Multiply/
+/// Invert) and Strait, plus the noise passes (Fbm/Ridged/Warp, see `noise`).
 #[derive(Debug)]
 enum Tool {
     Hill,
@@ -661,13 +782,288 @@ enum Tool {
     Smooth,
     Mask,
     Multiply,
+    Add,
+    Strait,
+    Invert,
+    Fbm,
+    Ridged,
+    Warp,
 }
 
 struct Step {
     tool: Tool,
-    a2: String, // count (or power for Mask/Smooth, multiplier for Multiply)
-    a4: String, // rangeX
-    a5: String, // rangeY
+    a2: String, // count (or power for Mask/Smooth, value for Add/Multiply, probability for Invert, amplitude for noise)
+    a3: String, // height (or range for Add/Multiply, direction for Strait, axes for Invert, scale % for noise)
+    a4: String, // rangeX (octaves for noise)
+    a5: String, // rangeY (height range for noise)
+}
+
+/// Why a heightmap template was rejected. `line` is 1-based; `index` counts
+/// the arguments after the tool name from 1 (FMG's `a2` is argument 1).
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum TemplateError {
+    /// The template has no steps (only blank or `#` comment lines).
+    Empty,
+    /// The first word of a line is not a tool name.
+    UnknownTool { line: usize, tool: String },
+    /// A line has the wrong number of arguments for its tool.
+    ArgumentCount {
+        line: usize,
+        tool: String,
+        min: usize,
+        found: usize,
+    },
+    /// An argument doesn't have the form its tool expects.
+    BadArgument {
+        line: usize,
+        index: usize,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Empty => write!(f, "template has no steps"),
+            TemplateError::UnknownTool { line, tool } => {
+                write!(f, "line {line}: unknown tool `{tool}`")
+            }
+            TemplateError::ArgumentCount {
+                line,
+                tool,
+                min,
+                found,
+            } => write!(
+                f,
+                "line {line}: {tool} takes {min} to 4 arguments, got {found}"
+            ),
+            TemplateError::BadArgument {
+                line,
+                index,
+                value,
+                expected,
+            } => write!(
+                f,
+                "line {line}: argument {index} `{value}` should be {expected}"
+            ),
+        }
+    }
+}
+
+/// A template from the named library.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct NamedTemplate {
+    /// FMG's template id (`"highIsland"`).
+    pub id: &'static str,
+    /// Display name (`"High Island"`).
+    pub name: &'static str,
+    /// Template text, one step per line.
+    pub template: &'static str,
+}
+
+/// FMG's named heightmap templates (`heightmap-templates.js`), verbatim.
+/// Counts are still size-scaled by `run_step`, so the shapes hold across
+/// cell counts.
+pub const TEMPLATES: &[NamedTemplate] = &[
+    NamedTemplate {
+        id: "volcano",
+        name: "Volcano",
+        template: "Hill 1 90-100 44-56 40-60\n\
+                   Multiply 0.8 50-100 0 0\n\
+                   Range 1.5 30-55 45-55 40-60\n\
+                   Smooth 3 0 0 0\n\
+                   Hill 1.5 35-45 25-30 20-75\n\
+                   Hill 1 35-55 75-80 25-75\n\
+                   Hill 0.5 20-25 10-15 20-25\n\
+                   Mask 3 0 0 0",
+    },
+    NamedTemplate {
+        id: "highIsland",
+        name: "High Island",
+        template: "Hill 1 90-100 65-75 47-53\n\
+                   Add 7 all 0 0\n\
+                   Hill 5-6 20-30 25-75 30-70\n\
+                   Range 1 40-50 45-55 45-55\n\
+                   Multiply 0.8 land 0 0\n\
+                   Mask 3 0 0 0\n\
+                   Smooth 2 0 0 0\n\
+                   Trough 2-3 20-30 20-30 20-30\n\
+                   Trough 2-3 20-30 60-80 70-80\n\
+                   Hill 1 10-15 60-60 50-50\n\
+                   Hill 1.5 13-16 15-20 20-75\n\
+                   Range 1.5 30-40 15-85 30-40\n\
+                   Range 1.5 30-40 15-85 60-70\n\
+                   Pit 3-5 10-30 15-85 20-80",
+    },
+    NamedTemplate {
+        id: "lowIsland",
+        name: "Low Island",
+        template: "Hill 1 90-99 60-80 45-55\n\
+                   Hill 1-2 20-30 10-30 10-90\n\
+                   Smooth 2 0 0 0\n\
+                   Hill 6-7 25-35 20-70 30-70\n\
+                   Range 1 40-50 45-55 45-55\n\
+                   Trough 2-3 20-30 15-85 20-30\n\
+                   Trough 2-3 20-30 15-85 70-80\n\
+                   Hill 1.5 10-15 5-15 20-80\n\
+                   Hill 1 10-15 85-95 70-80\n\
+                   Pit 5-7 15-25 15-85 20-80\n\
+                   Multiply 0.4 20-100 0 0\n\
+                   Mask 4 0 0 0",
+    },
+    NamedTemplate {
+        id: "continents",
+        name: "Continents",
+        template: "Hill 1 80-85 60-80 40-60\n\
+                   Hill 1 80-85 20-30 40-60\n\
+                   Hill 6-7 15-30 25-75 15-85\n\
+                   Multiply 0.6 land 0 0\n\
+                   Hill 8-10 5-10 15-85 20-80\n\
+                   Range 1-2 30-60 5-15 25-75\n\
+                   Range 1-2 30-60 80-95 25-75\n\
+                   Range 0-3 30-60 80-90 20-80\n\
+                   Strait 2 vertical 0 0\n\
+                   Strait 1 vertical 0 0\n\
+                   Smooth 3 0 0 0\n\
+                   Trough 3-4 15-20 15-85 20-80\n\
+                   Trough 3-4 5-10 45-55 45-55\n\
+                   Pit 3-4 10-20 15-85 20-80\n\
+                   Mask 4 0 0 0",
+    },
+    NamedTemplate {
+        id: "archipelago",
+        name: "Archipelago",
+        template: "Add 11 all 0 0\n\
+                   Range 2-3 40-60 20-80 20-80\n\
+                   Hill 5 15-20 10-90 30-70\n\
+                   Hill 2 10-15 10-30 20-80\n\
+                   Hill 2 10-15 60-90 20-80\n\
+                   Smooth 3 0 0 0\n\
+                   Trough 10 20-30 5-95 5-95\n\
+                   Strait 2 vertical 0 0\n\
+                   Strait 2 horizontal 0 0",
+    },
+    NamedTemplate {
+        id: "atoll",
+        name: "Atoll",
+        template: "Hill 1 75-80 50-60 45-55\n\
+                   Hill 1.5 30-50 25-75 30-70\n\
+                   Hill .5 30-50 25-35 30-70\n\
+                   Smooth 1 0 0 0\n\
+                   Multiply 0.2 25-100 0 0\n\
+                   Hill 0.5 10-20 50-55 48-52",
+    },
+    NamedTemplate {
+        id: "mediterranean",
+        name: "Mediterranean",
+        template: "Range 4-6 30-80 0-100 0-10\n\
+                   Range 4-6 30-80 0-100 90-100\n\
+                   Hill 6-8 30-50 10-90 0-5\n\
+                   Hill 6-8 30-50 10-90 95-100\n\
+                   Multiply 0.9 land 0 0\n\
+                   Mask -2 0 0 0\n\
+                   Smooth 1 0 0 0\n\
+                   Hill 2-3 30-70 0-5 20-80\n\
+                   Hill 2-3 30-70 95-100 20-80\n\
+                   Trough 3-6 40-50 0-100 0-10\n\
+                   Trough 3-6 40-50 0-100 90-100",
+    },
+    NamedTemplate {
+        id: "peninsula",
+        name: "Peninsula",
+        template: "Range 2-3 20-35 40-50 0-15\n\
+                   Add 5 all 0 0\n\
+                   Hill 1 90-100 10-90 0-5\n\
+                   Add 13 all 0 0\n\
+                   Hill 3-4 3-5 5-95 80-100\n\
+                   Hill 1-2 3-5 5-95 40-60\n\
+                   Trough 5-6 10-25 5-95 5-95\n\
+                   Smooth 3 0 0 0\n\
+                   Invert 0.4 both 0 0",
+    },
+    NamedTemplate {
+        id: "pangea",
+        name: "Pangea",
+        template: "Hill 1-2 25-40 15-50 0-10\n\
+                   Hill 1-2 5-40 50-85 0-10\n\
+                   Hill 1-2 25-40 50-85 90-100\n\
+                   Hill 1-2 5-40 15-50 90-100\n\
+                   Hill 8-12 20-40 20-80 48-52\n\
+                   Smooth 2 0 0 0\n\
+                   Multiply 0.7 land 0 0\n\
+                   Trough 3-4 25-35 5-95 10-20\n\
+                   Trough 3-4 25-35 5-95 80-90\n\
+                   Range 5-6 30-40 10-90 35-65",
+    },
+    NamedTemplate {
+        id: "isthmus",
+        name: "Isthmus",
+        template: "Hill 5-10 15-30 0-30 0-20\n\
+                   Hill 5-10 15-30 10-50 20-40\n\
+                   Hill 5-10 15-30 30-70 40-60\n\
+                   Hill 5-10 15-30 50-90 60-80\n\
+                   Hill 5-10 15-30 70-100 80-100\n\
+                   Smooth 2 0 0 0\n\
+                   Trough 4-8 15-30 0-30 0-20\n\
+                   Trough 4-8 15-30 10-50 20-40\n\
+                   Trough 4-8 15-30 30-70 40-60\n\
+                   Trough 4-8 15-30 50-90 60-80\n\
+                   Trough 4-8 15-30 70-100 80-100\n\
+                   Invert 0.25 x 0 0",
+    },
+    NamedTemplate {
+        id: "shattered",
+        name: "Shattered",
+        template: "Hill 8 35-40 15-85 30-70\n\
+                   Trough 10-20 40-50 5-95 5-95\n\
+                   Range 5-7 30-40 10-90 20-80\n\
+                   Pit 12-20 30-40 15-85 20-80",
+    },
+    NamedTemplate {
+        id: "taklamakan",
+        name: "Taklamakan",
+        template: "Hill 1-3 20-30 30-70 30-70\n\
+                   Hill 2-4 60-85 0-5 0-100\n\
+                   Hill 2-4 60-85 95-100 0-100\n\
+                   Hill 3-4 60-85 20-80 0-5\n\
+                   Hill 3-4 60-85 20-80 95-100\n\
+                   Smooth 3 0 0 0",
+    },
+    NamedTemplate {
+        id: "oldWorld",
+        name: "Old World",
+        template: "Range 3 70 15-85 20-80\n\
+                   Hill 2-3 50-70 15-45 20-80\n\
+                   Hill 2-3 50-70 65-85 20-80\n\
+                   Hill 4-6 20-25 15-85 20-80\n\
+                   Multiply 0.5 land 0 0\n\
+                   Smooth 2 0 0 0\n\
+                   Range 3-4 20-50 15-35 20-45\n\
+                   Range 2-4 20-50 65-85 45-80\n\
+                   Strait 3-7 vertical 0 0\n\
+                   Trough 6-8 20-50 15-85 45-65\n\
+                   Pit 5-6 20-30 10-90 10-90",
+    },
+    NamedTemplate {
+        id: "fractious",
+        name: "Fractious",
+        template: "Hill 12-15 50-80 5-95 5-95\n\
+                   Mask -1.5 0 0 0\n\
+                   Mask 3 0 0 0\n\
+                   Add -20 30-100 0 0\n\
+                   Range 6-8 40-50 5-95 10-90",
+    },
+];
+
+/// Look up a library template by id or display name (ASCII case-insensitive,
+/// so `"archipelago"`, `"Archipelago"` and `"High Island"` all resolve).
+pub fn named_template(name: &str) -> Option<&'static NamedTemplate> {
+    let name = name.trim();
+    TEMPLATES
+        .iter()
+        .find(|t| t.id.eq_ignore_ascii_case(name) || t.name.eq_ignore_ascii_case(name))
 }
 
 /// Parse the default template as a set of steps. The default procedural
@@ -678,42 +1074,175 @@ fn default_template() -> &'static str {
     // continents via hills/ranges, then carve water back with pits/troughs,
     // smooth, and apply a gentle radial mask. Feature counts are size-scaled
     // in `run_step` so the land fraction stays in a sane band across 1k–60k
-    // cells. Each line: `Tool count height rangeX rangeY`.
-    "Hill 6-10 60-85 10-90 10-90\n\
+    // cells. Each line: `Tool count height rangeX rangeY`. The heights are
+    // the ones the flood helpers used when they were still hard-coded, so
+    // the default world is unchanged.
+    "Hill 6-10 85-100 10-90 10-90\n\
      Range 2-4 30-60 5-15 25-75\n\
      Range 2-4 30-60 80-95 25-75\n\
-     Range 1-3 30-55 80-90 20-80\n\
-     Trough 2-4 15-25 15-85 20-80\n\
+     Range 1-3 30-60 80-90 20-80\n\
+     Trough 2-4 20-30 15-85 20-80\n\
      Pit 2-4 10-20 15-85 20-80\n\
      Smooth 3 0 0 0\n\
      Mask 2 0 0 0"
 }
 
-fn parse_template(text: &str) -> Vec<Step> {
+/// `"5"`, `"1.5"`, `"-2"` or `"2-4"` — what `get_number_in_range` accepts.
+fn is_number_or_range(s: &str) -> bool {
+    if s.parse::<f64>().is_ok() {
+        return true;
+    }
+    let body = s.strip_prefix('-').unwrap_or(s);
+    matches!(body.split_once('-'), Some((a, b)) if a.parse::<f64>().is_ok() && b.parse::<f64>().is_ok())
+}
+
+/// `"lo-hi"` — what `point_in_range` and the Add/Multiply height filter accept.
+fn is_range(s: &str) -> bool {
+    matches!(s.split_once('-'), Some((a, b)) if a.parse::<f64>().is_ok() && b.parse::<f64>().is_ok())
+}
+
+/// Parse template text into steps. One step per line —
+/// `Tool a2 a3 a4 a5`, FMG's layout; trailing arguments a tool ignores may
+/// be left off. Blank lines and lines starting with `#` are skipped. The
+/// first malformed line is reported with its 1-based line number.
+fn parse_template(text: &str) -> Result<Vec<Step>, TemplateError> {
     let mut steps = Vec::new();
-    for line in text.lines() {
+    for (k, line) in text.lines().enumerate() {
+        let line_no = k + 1;
         let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 {
+        let Some(&name) = parts.first() else {
             continue;
+        };
+        if name.starts_with('#') {
+            continue;
+        }
+        // (tool, arguments it reads)
+        let (tool, min) = match name {
+            "Hill" => (Tool::Hill, 4),
+            "Pit" => (Tool::Pit, 4),
+            "Range" => (Tool::Range, 4),
+            "Trough" => (Tool::Trough, 4),
+            "Smooth" => (Tool::Smooth, 1),
+            "Mask" => (Tool::Mask, 1),
+            "Multiply" => (Tool::Multiply, 2),
+            "Add" => (Tool::Add, 2),
+            "Strait" => (Tool::Strait, 2),
+            "Invert" => (Tool::Invert, 2),
+            "Fbm" => (Tool::Fbm, 2),
+            "Ridged" => (Tool::Ridged, 2),
+            "Warp" => (Tool::Warp, 2),
+            _ => {
+                return Err(TemplateError::UnknownTool {
+                    line: line_no,
+                    tool: name.to_string(),
+                })
+            }
+        };
+        let args = &parts[1..];
+        if args.len() < min || args.len() > 4 {
+            return Err(TemplateError::ArgumentCount {
+                line: line_no,
+                tool: name.to_string(),
+                min,
+                found: args.len(),
+            });
         }
-        let tool = match parts[0] {
-            "Hill" => Tool::Hill,
-            "Pit" => Tool::Pit,
-            "Range" => Tool::Range,
-            "Trough" => Tool::Trough,
-            "Smooth" => Tool::Smooth,
-            "Mask" => Tool::Mask,
-            "Multiply" => Tool::Multiply,
-            _ => continue,
+        let bad = |index: usize, expected: &'static str| TemplateError::BadArgument {
+            line: line_no,
+            index,
+            value: args[index - 1].to_string(),
+            expected,
+        };
+        let check = |index: usize, ok: bool, expected: &'static str| {
+            if ok {
+                Ok(())
+            } else {
+                Err(bad(index, expected))
+            }
         };
+        match tool {
+            Tool::Hill | Tool::Pit | Tool::Range | Tool::Trough => {
+                check(1, is_number_or_range(args[0]), "a count like `3` or `2-4`")?;
+                check(
+                    2,
+                    is_number_or_range(args[1]),
+                    "a height like `40` or `30-60`",
+                )?;
+                check(3, is_range(args[2]), "an x range in percent like `10-90`")?;
+                check(4, is_range(args[3]), "a y range in percent like `10-90`")?;
+            }
+            Tool::Smooth | Tool::Mask => {
+                check(1, is_number_or_range(args[0]), "a number")?;
+            }
+            Tool::Multiply | Tool::Add => {
+                check(1, args[0].parse::<f64>().is_ok(), "a number")?;
+                let range = args[1];
+                check(
+                    2,
+                    range == "all" || range == "land" || is_range(range),
+                    "`all`, `land` or a height range like `20-100`",
+                )?;
+            }
+            Tool::Strait => {
+                check(1, is_number_or_range(args[0]), "a width like `2` or `1-3`")?;
+                check(
+                    2,
+                    matches!(args[1], "vertical" | "horizontal"),
+                    "`vertical` or `horizontal`",
+                )?;
+            }
+            Tool::Invert => {
+                check(
+                    1,
+                    matches!(args[0].parse::<f64>(), Ok(p) if (0.0..=1.0).contains(&p)),
+                    "a probability in 0..1",
+                )?;
+                check(
+                    2,
+                    matches!(args[1], "x" | "y" | "both"),
+                    "`x`, `y` or `both`",
+                )?;
+            }
+            Tool::Fbm | Tool::Ridged | Tool::Warp => {
+                check(
+                    1,
+                    is_number_or_range(args[0]),
+                    "an amplitude like `8` or `5-10`",
+                )?;
+                check(
+                    2,
+                    matches!(args[1].parse::<f64>(), Ok(s) if s > 0.0),
+                    "a feature size in percent of the map width like `15`",
+                )?;
+                if let Some(octaves) = args.get(2) {
+                    check(
+                        3,
+                        matches!(octaves.parse::<u32>(), Ok(o) if (1..=noise::MAX_OCTAVES).contains(&o)),
+                        "an octave count in 1..8",
+                    )?;
+                }
+                if let Some(&range) = args.get(3) {
+                    check(
+                        4,
+                        range == "all" || range == "land" || is_range(range),
+                        "`all`, `land` or a height range like `20-100`",
+                    )?;
+                }
+            }
+        }
+        let arg = |k: usize| args.get(k).copied().unwrap_or("").to_string();
         steps.push(Step {
             tool,
-            a2: parts.get(1).unwrap_or(&"").to_string(),
-            a4: parts.get(3).unwrap_or(&"").to_string(),
-            a5: parts.get(4).unwrap_or(&"").to_string(),
+            a2: arg(0),
+            a3: arg(1),
+            a4: arg(2),
+            a5: arg(3),
         });
     }
-    steps
+    if steps.is_empty() {
+        return Err(TemplateError::Empty);
+    }
+    Ok(steps)
 }
 
 /// Run one parsed step against `h`, drawing randomness from `rng`.
@@ -761,9 +1290,18 @@ fn run_step(
     // ~4% carved — a local basin, not a regional one.
     let pit_budget = ((n as f64 * 0.01).round() as usize).max(min_feature);
     let ridge_budget = ((n as f64 * 0.07).round() as usize).max(min_feature);
+    // A count that rolls below 1 (`Hill 0.5`, `Range 0-3`) places nothing.
+    let count = |rng: &mut StdRng| {
+        let base = get_number_in_range(rng, &step.a2);
+        if base < 1.0 {
+            0
+        } else {
+            scaled_count(base)
+        }
+    };
     match step.tool {
         Tool::Hill => {
-            let count = scaled_count(get_number_in_range(rng, &step.a2).max(1.0));
+            let count = count(rng);
             for _ in 0..count {
                 let Some(x) = point_in_range(rng, &step.a4, view.world_w) else {
                     continue;
@@ -772,11 +1310,11 @@ fn run_step(
                     continue;
                 };
                 let start = find_grid_cell(view, x, y);
-                add_hill(view, h, rng, start, blob_power, hill_budget);
+                add_hill(view, h, rng, start, &step.a3, blob_power, hill_budget);
             }
         }
         Tool::Pit => {
-            let count = scaled_count(get_number_in_range(rng, &step.a2).max(1.0));
+            let count = count(rng);
             for _ in 0..count {
                 let Some(x) = point_in_range(rng, &step.a4, view.world_w) else {
                     continue;
@@ -785,11 +1323,11 @@ fn run_step(
                     continue;
                 };
                 let start = find_grid_cell(view, x, y);
-                add_pit(view, h, rng, start, blob_power, pit_budget);
+                add_pit(view, h, rng, start, &step.a3, blob_power, pit_budget);
             }
         }
         Tool::Range => {
-            let count = scaled_count(get_number_in_range(rng, &step.a2).max(1.0));
+            let count = count(rng);
             for _ in 0..count {
                 let (start, end) = pick_range_endpoints(view, rng, &step.a4, &step.a5);
                 if start == end {
@@ -801,6 +1339,7 @@ fn run_step(
                     rng,
                     start,
                     end,
+                    &step.a3,
                     0.15,
                     line_power,
                     true,
@@ -809,7 +1348,7 @@ fn run_step(
             }
         }
         Tool::Trough => {
-            let count = scaled_count(get_number_in_range(rng, &step.a2).max(1.0));
+            let count = count(rng);
             for _ in 0..count {
                 let (start, end) = pick_range_endpoints(view, rng, &step.a4, &step.a5);
                 if start == end {
@@ -821,6 +1360,7 @@ fn run_step(
                     rng,
                     start,
                     end,
+                    &step.a3,
                     0.2,
                     line_power,
                     false,
@@ -836,9 +1376,32 @@ fn run_step(
             let power = get_number_in_range(rng, &step.a2);
             mask(view, h, power);
         }
+        // FMG reads these as plain numbers — `Multiply 0.8` must not be
+        // rounded to 0 or 1 the way `get_number_in_range` rounds a fraction.
         Tool::Multiply => {
-            let mult = get_number_in_range(rng, &step.a2);
-            multiply_land(h, mult);
+            let mult = step.a2.parse().unwrap_or(1.0);
+            modify(h, &step.a3, 0.0, mult);
+        }
+        Tool::Add => {
+            let add = step.a2.parse().unwrap_or(0.0);
+            modify(h, &step.a3, add, 1.0);
+        }
+        Tool::Strait => add_strait(view, h, rng, &step.a2, step.a3 == "vertical"),
+        Tool::Invert => {
+            let probability = step.a2.parse().unwrap_or(0.0);
+            invert(view, h, rng, probability, &step.a3);
+        }
+        Tool::Fbm | Tool::Ridged | Tool::Warp => {
+            let kind = match step.tool {
+                Tool::Fbm => NoiseKind::Fbm,
+                Tool::Ridged => NoiseKind::Ridged,
+                _ => NoiseKind::Warp,
+            };
+            let amplitude = get_number_in_range(rng, &step.a2);
+            let scale = step.a3.parse::<f64>().unwrap_or(15.0) / 100.0 * view.world_w;
+            let octaves = step.a4.parse().unwrap_or(NOISE_OCTAVES);
+            let range = if step.a5.is_empty() { "all" } else { &step.a5 };
+            add_noise(view, h, rng, kind, amplitude, scale, octaves, range);
         }
     }
 }
@@ -874,6 +1437,32 @@ fn pick_range_endpoints(
 /// Generate the heightmap for a deserialized `Mesh`. Returns a `Vec<u8>` of
 /// length N, values `0..=100`, `< 20` == water.
 pub fn generate(mesh: &Mesh, seed: u64) -> Vec<u8> {
+    let steps = parse_template(default_template()).expect("default template parses");
+    run_template(mesh, seed, &steps)
+}
+
+/// `generate` driven by a caller-chosen template: either the id or name of a
+/// library template (`named_template`) or template text. Malformed text is
+/// rejected with the offending line before anything runs.
+pub fn generate_from_template(
+    mesh: &Mesh,
+    seed: u64,
+    template: &str,
+) -> Result<Vec<u8>, TemplateError> {
+    let text = named_template(template).map_or(template, |t| t.template);
+    let steps = parse_template(text)?;
+    Ok(run_template(mesh, seed, &steps))
+}
+
+/// Check a template (library id/name or text) without running it. Returns
+/// the number of steps.
+pub fn validate_template(template: &str) -> Result<usize, TemplateError> {
+    let text = named_template(template).map_or(template, |t| t.template);
+    parse_template(text).map(|steps| steps.len())
+}
+
+/// Run parsed steps over an all-water map.
+fn run_template(mesh: &Mesh, seed: u64, steps: &[Step]) -> Vec<u8> {
     let view = MeshView::from_mesh(mesh);
     let n = view.points.len();
     let mut rng = StdRng::seed_from_u64(seed);
@@ -883,8 +1472,7 @@ pub fn generate(mesh: &Mesh, seed: u64) -> Vec<u8> {
     let line_power = get_line_power(n);
 
     // Start everything below sea level, then raise land.
-    let steps = parse_template(default_template());
-    for step in &steps {
+    for step in steps {
         run_step(&view, &mut h, &mut rng, step, blob_power, line_power);
     }
 
@@ -1010,7 +1598,7 @@ mod tests {
         // Seed a few spikes.
         for _ in 0..5 {
             let start = (rng.gen_range(0..h.len())) as usize;
-            add_hill(&view, &mut h, &mut rng, start, blob_power, 200);
+            add_hill(&view, &mut h, &mut rng, start, "85-100", blob_power, 200);
         }
         let before_max = *h.iter().max().unwrap();
         let before_var = variance(&h);
@@ -1081,7 +1669,7 @@ mod tests {
         let mut h = vec![80u8; view.points.len()];
         let start = 500;
         let budget = (view.points.len() / 10).max(20);
-        add_pit(&view, &mut h, &mut rng, start, blob_power, budget);
+        add_pit(&view, &mut h, &mut rng, start, "10-20", blob_power, budget);
         // The start cell should drop noticeably from the 80 baseline.
         assert!(
             h[start] < 80,
@@ -1366,49 +1954,35 @@ mod tests {
         // rely on the `n == 0` guard inside pick_cell.)
     }
 
-    /// Step 2.5.4 review (F9): `pick_cell` only checks the bucket cell + its
-    /// 1-hop CSR neighbors. This test compares pick_cell's answer against a
-    /// brute-force O(n) nearest-cell scan over ALL cells for random query
-    /// points. If 1-hop misses, this test documents the gap.
+    /// Step 2.5.4 review (F9): the old bucket + 1-hop refinement missed the
+    /// true nearest cell near Voronoi corners. The spatial-index ring search
+    /// is exact, so it must agree with a brute-force O(n) scan everywhere.
     #[test]
     fn pick_cell_vs_brute_force_nearest() {
         let mesh = mesh::build(3000, 42);
-        // Brute-force nearest cell for a query point.
         let brute = |x: f64, y: f64| -> u32 {
-            mesh.points
-                .iter()
-                .enumerate()
-                .min_by_key(|(_, &[px, py])| ((px - x).powi(2) + (py - y).powi(2)) as i64)
-                .map(|(i, _)| i as u32)
-                .unwrap()
+            (0..mesh.points.len())
+                .min_by(|&a, &b| {
+                    let [ax, ay] = mesh.points[a];
+                    let [bx, by] = mesh.points[b];
+                    let da = (ax - x).powi(2) + (ay - y).powi(2);
+                    let db = (bx - x).powi(2) + (by - y).powi(2);
+                    da.total_cmp(&db)
+                })
+                .unwrap() as u32
         };
-        // Test N random query points across the map.
         let mut rng = StdRng::seed_from_u64(999);
-        let mut mismatches = 0;
-        let n_tests = 200u32;
-        for _ in 0..n_tests {
+        for _ in 0..200 {
+            let x = rng.gen_range(0.0..mesh.world_w);
+            let y = rng.gen_range(0.0..mesh.world_h);
+            assert_eq!(pick_cell(&mesh, x, y), Some(brute(x, y)), "at ({x}, {y})");
+        }
+        // The original corner probes (queries in [0, 1)²).
+        for _ in 0..200 {
             let x = rng.gen_range(0.0..1.0);
             let y = rng.gen_range(0.0..1.0);
-            let picked = pick_cell(&mesh, x, y).unwrap();
-            let true_nearest = brute(x, y);
-            if picked != true_nearest {
-                mismatches += 1;
-            }
+            assert_eq!(pick_cell(&mesh, x, y), Some(brute(x, y)));
         }
-        // 1-hop should be correct for the vast majority of points. A small
-        // number of mismatches near Voronoi boundaries (where the true nearest
-        // is 2+ hops from the bucket cell) is the known limitation.
-        let mismatch_rate = mismatches as f64 / n_tests as f64;
-        eprintln!(
-            "pick_cell 1-hop vs brute-force: {mismatches}/{n_tests} mismatches ({:.1}%)",
-            mismatch_rate * 100.0
-        );
-        // Threshold: 1-hop should get >= 90% correct. If this fails, the
-        // bucket grid is too coarse or 2-hop expansion is needed.
-        assert!(
-            mismatch_rate < 0.10,
-            "1-hop pick_cell mismatch rate {mismatch_rate:.1}% exceeds 10% — 2-hop expansion may be needed"
-        );
     }
 
     /// `build_range` constructs a ridge path from `start` to `end` by greedy
@@ -1475,7 +2049,15 @@ mod tests {
         // add_hill on flat zero map
         let mut h = vec![0u8; n];
         let start = 200;
-        add_hill(&view, &mut h, &mut rng, start, blob_power, hill_budget);
+        add_hill(
+            &view,
+            &mut h,
+            &mut rng,
+            start,
+            "85-100",
+            blob_power,
+            hill_budget,
+        );
         assert!(
             h[start] > 0,
             "hill start cell should be > 0, got {}",
@@ -1501,6 +2083,7 @@ mod tests {
             &mut rng2,
             start2,
             end2,
+            "30-60",
             0.0,
             line_power,
             true,
@@ -1519,6 +2102,7 @@ mod tests {
             &mut rng3,
             start2,
             end2,
+            "20-30",
             0.0,
             line_power,
             false,
@@ -1536,7 +2120,7 @@ mod tests {
         );
     }
 
-    /// `multiply_land` scales only cells ≥ SEA_LEVEL (land). Water cells
+    /// `modify("land", mult)` (`Multiply … land`) scales only cells ≥ SEA_LEVEL (land). Water cells
     /// (<20) are untouched. The math: `new = (old - 20) * mult + 20`, clamped
     /// to [0,100]. A multiplier of 1.0 is identity; 2.0 doubles the height
     /// above sea level; 0.5 halves it.
@@ -1551,7 +2135,7 @@ mod tests {
             100, // peak
         ];
         let original = h.clone();
-        multiply_land(&mut h, 2.0);
+        modify(&mut h, "land", 0.0, 2.0);
         // Water unchanged
         assert_eq!(h[0], 0);
         assert_eq!(h[1], 10);
@@ -1566,12 +2150,12 @@ mod tests {
 
         // Identity multiplier
         let mut h2 = original.clone();
-        multiply_land(&mut h2, 1.0);
+        modify(&mut h2, "land", 0.0, 1.0);
         assert_eq!(h2, original);
 
         // Halve
         let mut h3 = original.clone();
-        multiply_land(&mut h3, 0.5);
+        modify(&mut h3, "land", 0.0, 0.5);
         assert_eq!(h3[2], 20); // sea level unchanged
         assert_eq!(h3[3], 25); // (30-20)*0.5 + 20 = 25
         assert_eq!(h3[4], 50); // (80-20)*0.5 + 20 = 50
@@ -1583,7 +2167,7 @@ mod tests {
     /// accidental template corruption.
     #[test]
     fn default_template_parses_correctly() {
-        let steps = parse_template(default_template());
+        let steps = parse_template(default_template()).unwrap();
         assert_eq!(steps.len(), 8);
         let tools: Vec<_> = steps
             .iter()
@@ -1595,6 +2179,12 @@ mod tests {
                 Tool::Smooth => "Smooth",
                 Tool::Mask => "Mask",
                 Tool::Multiply => "Multiply",
+                Tool::Add => "Add",
+                Tool::Strait => "Strait",
+                Tool::Invert => "Invert",
+                Tool::Fbm => "Fbm",
+                Tool::Ridged => "Ridged",
+                Tool::Warp => "Warp",
             })
             .collect();
         assert_eq!(
@@ -1611,6 +2201,120 @@ mod tests {
         }
     }
 
+    /// Every library template parses, runs deterministically and raises some
+    /// land; lookups work by id and by display name in any case.
+    #[test]
+    fn named_templates_parse_and_generate() {
+        let mesh = test_mesh(2000, 3);
+        for t in TEMPLATES {
+            assert!(validate_template(t.template).is_ok(), "{} rejected", t.id);
+            let a = generate_from_template(&mesh, 11, t.id).unwrap();
+            let b = generate_from_template(&mesh, 11, t.template).unwrap();
+            assert_eq!(a, b, "{} not deterministic", t.id);
+            assert!(a.iter().any(|&v| v >= SEA_LEVEL), "{} has no land", t.id);
+        }
+        assert_eq!(named_template("ARCHIPELAGO").unwrap().id, "archipelago");
+        assert_eq!(named_template("high island").unwrap().id, "highIsland");
+        assert!(named_template("Atlantis").is_none());
+        // Different templates give different worlds from the same seed.
+        assert_ne!(
+            generate_from_template(&mesh, 11, "pangea").unwrap(),
+            generate_from_template(&mesh, 11, "archipelago").unwrap()
+        );
+    }
+
+    /// Malformed templates are rejected with the 1-based line of the first
+    /// problem; blank and `#` lines are skipped but still counted.
+    #[test]
+    fn template_errors_carry_line_numbers() {
+        let text = "# my world\nHill 2 40 10-90 10-90\n\nVolcano 1 2 3 4";
+        assert_eq!(
+            validate_template(text),
+            Err(TemplateError::UnknownTool {
+                line: 4,
+                tool: "Volcano".into()
+            })
+        );
+        assert_eq!(
+            validate_template("Smooth 2\nHill 2 40"),
+            Err(TemplateError::ArgumentCount {
+                line: 2,
+                tool: "Hill".into(),
+                min: 4,
+                found: 2
+            })
+        );
+        let Err(TemplateError::BadArgument { line, index, .. }) =
+            validate_template("Hill 2 40 10-90 10-90\nStrait 2 diagonal")
+        else {
+            panic!("bad Strait direction accepted");
+        };
+        assert_eq!((line, index), (2, 2));
+        let Err(TemplateError::BadArgument { line, index, .. }) =
+            validate_template("Hill 2 40 left 10-90")
+        else {
+            panic!("bad x range accepted");
+        };
+        assert_eq!((line, index), (1, 3));
+        assert_eq!(validate_template(" \n# nothing"), Err(TemplateError::Empty));
+        assert_eq!(validate_template("Smooth 2\nMask 3 0 0 0"), Ok(2));
+        let mesh = test_mesh(1000, 1);
+        assert!(generate_from_template(&mesh, 1, "Hill x 40 10-90 10-90").is_err());
+    }
+
+    /// `Add`/`Multiply` honour their height filter, and a fractional
+    /// multiplier scales instead of rounding to 0 or 1.
+    #[test]
+    fn modify_filters_by_range() {
+        let mut h = vec![10u8, 20, 40, 60, 90];
+        modify(&mut h, "30-70", 5.0, 1.0);
+        assert_eq!(h, [10, 20, 45, 65, 90]);
+        let mut h = vec![10u8, 20, 40, 60, 90];
+        modify(&mut h, "land", 0.0, 0.5);
+        assert_eq!(h, [10, 20, 30, 40, 55]);
+        // Land adds never sink a cell below sea level.
+        let mut h = vec![10u8, 25, 60];
+        modify(&mut h, "land", -20.0, 1.0);
+        assert_eq!(h, [10, 20, 40]);
+        let mesh = test_mesh(1000, 2);
+        let flat = generate_from_template(&mesh, 2, "Add 30 all").unwrap();
+        assert!(flat.iter().all(|&v| v == 30));
+        let scaled = generate_from_template(&mesh, 2, "Add 60 all\nMultiply 0.5 land").unwrap();
+        assert!(scaled.iter().all(|&v| v == 40));
+    }
+
+    /// Noise steps parse, are seeded, respect their height filter and
+    /// reject bad arguments.
+    #[test]
+    fn noise_steps_roughen_within_range() {
+        let mesh = test_mesh(2000, 3);
+        let base = "Add 40 all\n";
+        for tool in ["Fbm", "Ridged", "Warp"] {
+            let text = format!("{base}{tool} 10 20 4 land");
+            let a = generate_from_template(&mesh, 3, &text).unwrap();
+            assert_eq!(a, generate_from_template(&mesh, 3, &text).unwrap());
+            assert_ne!(a, generate_from_template(&mesh, 4, &text).unwrap());
+            assert!(a.iter().any(|&v| v != 40), "{tool} changed nothing");
+            assert!(a.iter().all(|&v| (SEA_LEVEL..=50).contains(&v)), "{tool}");
+            // Outside the filter nothing moves.
+            let skipped = generate_from_template(&mesh, 3, &format!("{base}{tool} 10 20 4 60-100"));
+            assert!(skipped.unwrap().iter().all(|&v| v == 40));
+        }
+        // Octaves and range are optional.
+        assert_eq!(validate_template("Fbm 5-8 10"), Ok(1));
+        for (text, index) in [
+            ("Fbm 5 0", 2),
+            ("Warp 5 10 12", 3),
+            ("Ridged 5 10 3 coast", 4),
+        ] {
+            let Err(TemplateError::BadArgument { index: found, .. }) = validate_template(text)
+            else {
+                panic!("`{text}` accepted");
+            };
+            assert_eq!(found, index, "{text}");
+        }
+    }
+
     /// `generate(&mesh, seed)` returns a `Vec<u8>` of length exactly N (the
     /// mesh's cell count). This is an explicit contract — downstream `Grid`
     /// construction expects `h.len() == N`.
@@ -10,12 +10,37 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
-use crate::grid::Grid;
+use crate::erosion::{self, ErosionOpts};
+use crate::grid::{CellData, Grid};
 use crate::heightmap::SEA_LEVEL;
 use crate::heightmap::{build_range, get_line_power, MeshView};
 use crate::mesh::Mesh;
+use crate::noise::{self, NoiseKind};
+use crate::spatial::SpatialIndex;
 use rand::{rngs::StdRng, SeedableRng};
 
+/// Height change of a full-strength noise brush at a field extreme.
+const NOISE_BRUSH_AMPLITUDE: f64 = 20.0;
+
+/// Octaves of the noise brushes.
+const NOISE_BRUSH_OCTAVES: u32 = 4;
+
+/// Erosion passes per `Erode` brush stroke.
+const ERODE_BRUSH_ITERATIONS: u32 = 3;
+
+/// Height change of a full-strength `Roughen` brush at a field extreme.
+const ROUGHEN_AMPLITUDE: f64 = 8.0;
+
+/// `Roughen` feature size, in cell spacings.
+const ROUGHEN_SCALE: f64 = 3.0;
+
+/// Octaves of the `Roughen` field.
+const ROUGHEN_OCTAVES: u32 = 3;
+
+/// Mixed into the world seed so `Roughen` doesn't share the noise brushes'
+/// permutation table.
+const ROUGHEN_SALT: u64 = 0x0B0B_5A1D_F00D;
+
 /// Clamp a float into `[0, 100]` then round to nearest `u8` (same as `heightmap::lim`).
 fn lim(v: f64) -> u8 {
     v.clamp(0.0, 100.0).round() as u8
@@ -35,12 +60,60 @@ pub enum EditMode {
     Invert,
     Add,
     Multiply,
+    /// Hydraulic + thermal erosion (`erosion`), blended in by
+    /// `strength × falloff`.
+    Erode,
+    /// Noise brushes (`noise`): add `strength × falloff ×` a fractal field
+    /// with features about half the radius across.
+    Fbm,
+    Ridged,
+    Warp,
+    /// Quantize heights into steps of `params.step` above sea level.
+    Terrace,
+    /// Fine seeded noise (a few cells across), for texture rather than shape.
+    Roughen,
+    /// Clone brush: copy the heights around `target_cell` (the source) onto
+    /// the brush at `center_cell`, rotated by `params.rotation` and scaled by
+    /// `params.scale`.
+    Stamp,
+    /// Protect the brushed (or region) cells from later edits; see
+    /// `CellData::locked`.
+    Lock,
+    /// Clear the lock on the brushed (or region) cells.
+    Unlock,
+}
+
+/// Parameters only some modes read. All fields are optional on the wire via
+/// `#[serde(default)]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditParams {
+    /// `Terrace`: height of one step, in height units.
+    pub step: f32,
+    /// `Stamp`: counter-clockwise rotation of the copied patch, in degrees.
+    pub rotation: f32,
+    /// `Stamp`: magnification of the copied patch (2 = twice as large).
+    pub scale: f32,
+}
+
+impl Default for EditParams {
+    fn default() -> Self {
+        EditParams {
+            step: 8.0,
+            rotation: 0.0,
+            scale: 1.0,
+        }
+    }
 }
 
 /// One edit operation. `cells` is the set of affected cell ids (brush = radius
 /// query; macro = path). For brush modes, `radius` and `strength` are used;
 /// for macros, `strength` may be a multiplier/offset. `target_cell` is used by
-/// Range/Trough as the ridge walk endpoint (FMG `addRange`/`addTrough`).
+/// Range/Trough as the ridge walk endpoint (FMG `addRange`/`addTrough`) and by
+/// Stamp as the source centre. `params` holds the Terrace / Stamp extras.
+///
+/// With a `region`, the op applies to the cells inside that world-space shape
+/// instead: `center_cell`, `radius` and `cells` are ignored (see `Region`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EditOp {
     pub mode: EditMode,
@@ -49,46 +122,75 @@ pub struct EditOp {
     pub radius: f32,
     pub strength: f32,
     pub cells: Vec<u32>,
+    #[serde(default)]
+    pub params: EditParams,
+    #[serde(default)]
+    pub region: Option<Region>,
+}
+
+impl Default for EditOp {
+    /// A zero-radius, zero-strength `Raise` on cell 0, for filling in the
+    /// fields an op doesn't use.
+    fn default() -> Self {
+        EditOp {
+            mode: EditMode::Raise,
+            center_cell: 0,
+            target_cell: 0,
+            radius: 0.0,
+            strength: 0.0,
+            cells: Vec::new(),
+            params: EditParams::default(),
+            region: None,
+        }
+    }
+}
+
+/// A world-space outline for an `EditOp`, in mesh coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum RegionShape {
+    /// A closed polygon (even-odd fill).
+    Polygon { points: Vec<[f64; 2]> },
+    /// A freehand lasso path, closed back to its first point. Filled like
+    /// `Polygon`.
+    Lasso { path: Vec<[f64; 2]> },
+    /// An axis-aligned rectangle between two corners.
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+}
+
+/// Where a region op applies. The op runs at full strength on the cells
+/// whose centre lies inside `shape`, as a brush centred on the cell nearest
+/// their centroid and just reaching the farthest of them (that centre and
+/// radius feed `Flatten`, `Mask`, the noise scale and so on). The result is
+/// then blended in with a weight that ramps from 0 at the outline to 1 at
+/// `feather` world units inside it. Cells outside never change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub shape: RegionShape,
+    #[serde(default)]
+    pub feather: f32,
 }
 
 // ---------------------------------------------------------------------------
 // Brush helpers
 // ---------------------------------------------------------------------------
 
-/// Gather all cells within `radius` (in world units) of `center_cell` using
-/// BFS over `cells.c` adjacency, stopping when the Euclidean distance from the
-/// center cell's position exceeds `radius`. Returns the set including the
-/// center cell. Deterministic (no RNG).
-fn gather_radius_cells(mesh: &Mesh, center_cell: u32, radius: f32) -> Vec<u32> {
-    let center = center_cell as usize;
-    let r2 = (radius as f64) * (radius as f64);
-    let [cx, cy] = mesh.points[center];
-    let i = &mesh.cells.i;
-    let c = &mesh.cells.c;
-    let n = mesh.points.len();
-    let mut visited = vec![false; n];
-    let mut queue: Vec<usize> = Vec::with_capacity(64);
-    let mut out: Vec<u32> = Vec::with_capacity(64);
-    visited[center] = true;
-    queue.push(center);
-    out.push(center as u32);
-    while let Some(q) = queue.pop() {
-        let lo = i[q] as usize;
-        let hi = i[q + 1] as usize;
-        for &nbor in &c[lo..hi] {
-            let nb = nbor as usize;
-            if visited[nb] {
-                continue;
-            }
-            let [px, py] = mesh.points[nb];
-            let dx = px - cx;
-            let dy = py - cy;
-            if dx * dx + dy * dy <= r2 {
-                visited[nb] = true;
-                queue.push(nb);
-                out.push(nb as u32);
-            }
-        }
+/// Gather all cells whose centre is within `radius` (in world units) of
+/// `center_cell`'s centre, via the mesh's spatial index. Unlike an adjacency
+/// BFS this also finds in-range cells that are only connected through
+/// out-of-range ones (concave coastlines, narrow bays). Returns ascending cell
+/// ids, always including the center cell. Deterministic (no RNG).
+fn gather_radius_cells(
+    mesh: &Mesh,
+    index: &SpatialIndex,
+    center_cell: u32,
+    radius: f32,
+) -> Vec<u32> {
+    let [cx, cy] = mesh.points[center_cell as usize];
+    let mut out = index.within_radius(mesh, cx, cy, radius as f64);
+    if let Err(pos) = out.binary_search(&center_cell) {
+        // Negative / NaN radius: the brush still touches its own cell.
+        out.insert(pos, center_cell);
     }
     out
 }
@@ -103,16 +205,154 @@ fn falloff(distance_sq: f64, radius: f32) -> f64 {
     1.0 - distance_sq / r2
 }
 
+/// How a brush's strength varies over the cells it touches.
+#[derive(Clone, Copy)]
+enum Weighting {
+    /// `falloff` around `center_cell` out to `radius` (radius brushes).
+    Radial,
+    /// Full strength everywhere; region ops feather afterwards.
+    Flat,
+}
+
+impl Weighting {
+    fn at(self, mesh: &Mesh, op: &EditOp, cell: usize) -> f64 {
+        match self {
+            Weighting::Radial => falloff(
+                mesh.distance_sq(mesh.points[cell], mesh.points[op.center_cell as usize]),
+                op.radius,
+            ),
+            Weighting::Flat => 1.0,
+        }
+    }
+}
+
+/// Distance from `p` to the closest point of segment `a`–`b`. On a mesh that
+/// wraps in `x`, `p` is taken at its copy nearest the segment's midpoint.
+fn segment_distance(
+    mesh: &Mesh,
+    [px, py]: [f64; 2],
+    [ax, ay]: [f64; 2],
+    [bx, by]: [f64; 2],
+) -> f64 {
+    let mid = (ax + bx) / 2.0;
+    let px = mid + mesh.delta_x(mid, px);
+    let (dx, dy) = (bx - ax, by - ay);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    ((px - ax - t * dx).powi(2) + (py - ay - t * dy).powi(2)).sqrt()
+}
+
+impl Region {
+    /// The outline as a closed ring.
+    fn ring(&self) -> Vec<[f64; 2]> {
+        match &self.shape {
+            RegionShape::Polygon { points } => points.clone(),
+            RegionShape::Lasso { path } => path.clone(),
+            RegionShape::Rect { x0, y0, x1, y1 } => {
+                vec![[*x0, *y0], [*x1, *y0], [*x1, *y1], [*x0, *y1]]
+            }
+        }
+    }
+
+    /// Cells inside the outline (ascending ids) and the feather weight of
+    /// each.
+    fn resolve(&self, mesh: &Mesh, index: &SpatialIndex) -> (Vec<u32>, Vec<f64>) {
+        let ring = self.ring();
+        let cells = match self.shape {
+            RegionShape::Rect { x0, y0, x1, y1 } => index.in_rect(mesh, x0, y0, x1, y1),
+            _ if ring.len() < 3 => Vec::new(),
+            _ => index.in_polygon(mesh, &ring),
+        };
+        let feather = self.feather as f64;
+        let weight = cells
+            .iter()
+            .map(|&c| {
+                if feather <= 0.0 {
+                    return 1.0;
+                }
+                let p = mesh.points[c as usize];
+                let edge = (0..ring.len())
+                    .map(|k| segment_distance(mesh, p, ring[k], ring[(k + 1) % ring.len()]))
+                    .fold(f64::INFINITY, f64::min);
+                (edge / feather).min(1.0)
+            })
+            .collect();
+        (cells, weight)
+    }
+}
+
+/// Apply `op` over its `region`: run it at full strength as a brush covering
+/// the region's cells, then blend the result in by the feather weight.
+fn apply_region(
+    mesh: &Mesh,
+    index: &SpatialIndex,
+    h: &mut [u8],
+    op: &EditOp,
+    region: &Region,
+    locked: &[bool],
+    grid_seed: u64,
+) {
+    let (cells, weight) = region.resolve(mesh, index);
+    if cells.is_empty() {
+        return;
+    }
+    // Mean position, `x` unwrapped around the first cell so a region across
+    // the seam is centred on the seam rather than on the far side.
+    let k = cells.len() as f64;
+    let x0 = mesh.points[cells[0] as usize][0];
+    let (sx, sy) = cells.iter().fold((0.0, 0.0), |(sx, sy), &c| {
+        let [x, y] = mesh.points[c as usize];
+        (sx + x0 + mesh.delta_x(x0, x), sy + y)
+    });
+    let cx = if mesh.topology.wraps_x() {
+        (sx / k).rem_euclid(mesh.world_w)
+    } else {
+        sx / k
+    };
+    let center_cell = index.nearest(mesh, cx, sy / k).unwrap_or(cells[0]);
+    let center = mesh.points[center_cell as usize];
+    let radius = cells
+        .iter()
+        .map(|&c| mesh.distance_sq(mesh.points[c as usize], center))
+        .fold(0.0, f64::max)
+        .sqrt();
+    let brush = EditOp {
+        center_cell,
+        radius: radius as f32,
+        cells: cells.clone(),
+        region: None,
+        ..op.clone()
+    };
+    let mut edited = h.to_vec();
+    apply_op(
+        mesh,
+        index,
+        &mut edited,
+        &brush,
+        Weighting::Flat,
+        locked,
+        grid_seed,
+    );
+    for (&c, &w) in cells.iter().zip(&weight) {
+        let c = c as usize;
+        h[c] = lim(h[c] as f64 + (edited[c] as f64 - h[c] as f64) * w);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Brush ops (raise / lower / flatten / smooth)
 // ---------------------------------------------------------------------------
 
-/// Apply a brush `op` to `h`. For Raise/Lower/Flatten/Smooth, `op.cells` should
+/// Apply a brush `op` to `h`. For Raise/Lower/Flatten/Smooth/Erode/Terrace/Stamp, `op.cells` should
 /// be the radius-bounded cell set (caller may pre-compute or we gather from
-/// `center_cell`+`radius` if `cells` is empty).
-fn apply_brush(mesh: &Mesh, h: &mut [u8], op: &EditOp) {
+/// `center_cell`+`radius` if `cells` is empty), weighted by `weighting`.
+fn apply_brush(mesh: &Mesh, index: &SpatialIndex, h: &mut [u8], op: &EditOp, weighting: Weighting) {
     let cells: Vec<u32> = if op.cells.is_empty() {
-        gather_radius_cells(mesh, op.center_cell, op.radius)
+        gather_radius_cells(mesh, index, op.center_cell, op.radius)
     } else {
         op.cells.clone()
     };
@@ -121,19 +361,13 @@ fn apply_brush(mesh: &Mesh, h: &mut [u8], op: &EditOp) {
     match op.mode {
         EditMode::Raise => {
             for &cid in &cells {
-                let [px, py] = mesh.points[cid as usize];
-                let dx = px - cx;
-                let dy = py - cy;
-                let f = falloff(dx * dx + dy * dy, op.radius);
+                let f = weighting.at(mesh, op, cid as usize);
                 h[cid as usize] = lim(h[cid as usize] as f64 + strength * f * 100.0);
             }
         }
         EditMode::Lower => {
             for &cid in &cells {
-                let [px, py] = mesh.points[cid as usize];
-                let dx = px - cx;
-                let dy = py - cy;
-                let f = falloff(dx * dx + dy * dy, op.radius);
+                let f = weighting.at(mesh, op, cid as usize);
                 h[cid as usize] = lim(h[cid as usize] as f64 - strength * f * 100.0);
             }
         }
@@ -141,10 +375,7 @@ fn apply_brush(mesh: &Mesh, h: &mut [u8], op: &EditOp) {
             // Flatten: blend each cell toward the center cell's height.
             let target = h[op.center_cell as usize] as f64;
             for &cid in &cells {
-                let [px, py] = mesh.points[cid as usize];
-                let dx = px - cx;
-                let dy = py - cy;
-                let f = falloff(dx * dx + dy * dy, op.radius);
+                let f = weighting.at(mesh, op, cid as usize);
                 let blend = strength * f;
                 h[cid as usize] = lim(h[cid as usize] as f64 * (1.0 - blend) + target * blend);
             }
@@ -164,22 +395,77 @@ fn apply_brush(mesh: &Mesh, h: &mut [u8], op: &EditOp) {
                     count += 1;
                 }
                 let mean = sum / count as f64;
-                let [px, py] = mesh.points[ci];
-                let dx = px - cx;
-                let dy = py - cy;
-                let f = falloff(dx * dx + dy * dy, op.radius);
+                let f = weighting.at(mesh, op, ci);
                 let blend = strength * f;
                 h[ci] = lim(h[ci] as f64 * (1.0 - blend) + mean * blend);
             }
             let _ = n;
         }
+        EditMode::Erode => {
+            // Flux routes over the whole map so valleys see their real
+            // catchment; only the brushed cells may change.
+            let mut weight = vec![0.0; h.len()];
+            for &cid in &cells {
+                weight[cid as usize] =
+                    (strength * weighting.at(mesh, op, cid as usize)).clamp(0.0, 1.0);
+            }
+            let opts = ErosionOpts {
+                iterations: ERODE_BRUSH_ITERATIONS,
+                ..ErosionOpts::default()
+            };
+            erosion::erode_weighted(mesh, h, &opts, &weight);
+        }
+        EditMode::Terrace => {
+            // Steps are counted from sea level, so a cell never rounds
+            // across the coast.
+            let step = (op.params.step as f64).max(1.0);
+            let sea = SEA_LEVEL as f64;
+            for &cid in &cells {
+                let ci = cid as usize;
+                let v = h[ci] as f64;
+                let terrace = sea + ((v - sea) / step).floor() * step;
+                let blend = strength * weighting.at(mesh, op, ci);
+                h[ci] = lim(v * (1.0 - blend) + terrace * blend);
+            }
+        }
+        EditMode::Stamp => {
+            // Map each brushed cell back into the source patch (undo the
+            // scale, then the rotation) and take the nearest source cell's
+            // height from a snapshot, so overlapping patches copy the
+            // original.
+            let snapshot = h.to_vec();
+            let [sx, sy] = mesh.points[op.target_cell as usize];
+            let scale = (op.params.scale as f64).max(0.01);
+            let (sin, cos) = (op.params.rotation as f64).to_radians().sin_cos();
+            for &cid in &cells {
+                let ci = cid as usize;
+                let [px, py] = mesh.points[ci];
+                let (dx, dy) = (mesh.delta_x(cx, px) / scale, (py - cy) / scale);
+                let mut x = sx + dx * cos + dy * sin;
+                let y = sy - dx * sin + dy * cos;
+                if mesh.topology.wraps_x() {
+                    x = x.rem_euclid(mesh.world_w);
+                } else if !(0.0..=mesh.world_w).contains(&x) {
+                    continue;
+                }
+                if !(0.0..=mesh.world_h).contains(&y) {
+                    continue;
+                }
+                let Some(src) = index.nearest(mesh, x, y) else {
+                    continue;
+                };
+                let blend = strength * weighting.at(mesh, op, ci);
+                h[ci] = lim(h[ci] as f64 * (1.0 - blend) + snapshot[src as usize] as f64 * blend);
+            }
+        }
         // Macro modes are handled by `apply_macro`.
         _ => {}
     }
 }
 
 // ---------------------------------------------------------------------------
-// Macro ops (Range / Trough / Strait / Mask / Invert / Add / Multiply)
+// Macro ops (Range / Trough / Strait / Mask / Invert / Add / Multiply, and
+// the seeded noise brushes)
 // ---------------------------------------------------------------------------
 
 /// Apply a macro `op` to `h`. These are pure functions over `h` + `cells.c`
@@ -187,7 +473,14 @@ fn apply_brush(mesh: &Mesh, h: &mut [u8], op: &EditOp) {
 /// `center_cell` → `target_cell` (FMG `getRange`) using `grid.seed`, then spread
 /// outward with `linePower` decay (FMG `addRange`/`addTrough`). For other
 /// macros, `op.cells` is the explicit cell set.
-fn apply_macro(mesh: &Mesh, h: &mut [u8], op: &EditOp, grid_seed: u64) {
+fn apply_macro(
+    mesh: &Mesh,
+    index: &SpatialIndex,
+    h: &mut [u8],
+    op: &EditOp,
+    weighting: Weighting,
+    grid_seed: u64,
+) {
     // Area macros (Strait/Mask/Invert/Add/Multiply) operate over `op.cells`.
     // When the caller leaves it empty (the editor hot path), gather the
     // radius-bounded neighborhood around `center_cell` — identical to how the
@@ -195,7 +488,7 @@ fn apply_macro(mesh: &Mesh, h: &mut [u8], op: &EditOp, grid_seed: u64) {
     // useful macro edit instead of a no-op. Range/Trough ignore this set and
     // build their own ridge path from center → target.
     let area_cells: Vec<u32> = if op.cells.is_empty() {
-        gather_radius_cells(mesh, op.center_cell, op.radius)
+        gather_radius_cells(mesh, index, op.center_cell, op.radius)
     } else {
         op.cells.clone()
     };
@@ -309,11 +602,15 @@ fn apply_macro(mesh: &Mesh, h: &mut [u8], op: &EditOp, grid_seed: u64) {
             let [cx, cy] = mesh.points[op.center_cell as usize];
             // Guard against a zero radius (division by zero below); a single
             // cell collapses to a distance 0 mask, which is a no-op.
-            let radius = if op.radius <= 0.0 { 1.0 } else { op.radius as f64 };
+            let radius = if op.radius <= 0.0 {
+                1.0
+            } else {
+                op.radius as f64
+            };
             for &cid in &area_cells {
                 let ci = cid as usize;
                 let [px, py] = mesh.points[ci];
-                let nx = (px - cx) / radius;
+                let nx = mesh.delta_x(cx, px) / radius;
                 let ny = (py - cy) / radius;
                 let dist = (1.0 - nx * nx) * (1.0 - ny * ny);
                 let masked = snapshot[ci] as f64 * dist.max(0.0);
@@ -348,6 +645,59 @@ fn apply_macro(mesh: &Mesh, h: &mut [u8], op: &EditOp, grid_seed: u64) {
                 }
             }
         }
+        EditMode::Fbm | EditMode::Ridged | EditMode::Warp => {
+            // The field is drawn from the world seed, so every stroke reveals
+            // the same underlying noise rather than a fresh pattern.
+            let kind = match op.mode {
+                EditMode::Fbm => NoiseKind::Fbm,
+                EditMode::Ridged => NoiseKind::Ridged,
+                _ => NoiseKind::Warp,
+            };
+            let mut rng = StdRng::seed_from_u64(grid_seed);
+            let points: Vec<[f64; 2]> = area_cells
+                .iter()
+                .map(|&cid| mesh.points[cid as usize])
+                .collect();
+            let scale = (op.radius as f64 / 2.0).max(1.0);
+            let field = noise::field(
+                &points,
+                mesh.world_w,
+                mesh.topology.wraps_x(),
+                &mut rng,
+                kind,
+                scale,
+                NOISE_BRUSH_OCTAVES,
+            );
+            let strength = op.strength as f64;
+            for (&cid, v) in area_cells.iter().zip(field) {
+                let ci = cid as usize;
+                let f = weighting.at(mesh, op, ci);
+                h[ci] = lim(h[ci] as f64 + strength * f * NOISE_BRUSH_AMPLITUDE * v);
+            }
+        }
+        EditMode::Roughen => {
+            let mut rng = StdRng::seed_from_u64(grid_seed ^ ROUGHEN_SALT);
+            let points: Vec<[f64; 2]> = area_cells
+                .iter()
+                .map(|&cid| mesh.points[cid as usize])
+                .collect();
+            let spacing = (mesh.world_w * mesh.world_h / h.len().max(1) as f64).sqrt();
+            let field = noise::field(
+                &points,
+                mesh.world_w,
+                mesh.topology.wraps_x(),
+                &mut rng,
+                NoiseKind::Fbm,
+                ROUGHEN_SCALE * spacing,
+                ROUGHEN_OCTAVES,
+            );
+            let strength = op.strength as f64;
+            for (&cid, v) in area_cells.iter().zip(field) {
+                let ci = cid as usize;
+                let f = weighting.at(mesh, op, ci);
+                h[ci] = lim(h[ci] as f64 + strength * f * ROUGHEN_AMPLITUDE * v);
+            }
+        }
         // Brush modes handled elsewhere.
         _ => {}
     }
@@ -361,15 +711,90 @@ fn apply_macro(mesh: &Mesh, h: &mut [u8], op: &EditOp, grid_seed: u64) {
 /// `grid` + same `ops` → byte-identical `h`. Exposed as
 /// `edit_heightmap(grid, ops)` to JS via `lib.rs`.
 pub fn edit_heightmap(grid: &mut Grid, ops: &[EditOp]) {
+    let (mesh, cells) = (&grid.mesh, &mut grid.cells);
+    let index = SpatialIndex::build(mesh);
     for op in ops {
-        match op.mode {
-            EditMode::Raise | EditMode::Lower | EditMode::Flatten | EditMode::Smooth => {
-                apply_brush(&grid.mesh, &mut grid.cells.h, op);
-            }
-            _ => {
-                apply_macro(&grid.mesh, &mut grid.cells.h, op, grid.seed);
-            }
+        match (&op.region, op.mode) {
+            (_, EditMode::Lock | EditMode::Unlock) => set_locked(mesh, &index, cells, op),
+            (Some(region), _) => apply_region(
+                mesh,
+                &index,
+                &mut cells.h,
+                op,
+                region,
+                &cells.locked,
+                grid.seed,
+            ),
+            (None, _) => apply_op(
+                mesh,
+                &index,
+                &mut cells.h,
+                op,
+                Weighting::Radial,
+                &cells.locked,
+                grid.seed,
+            ),
+        }
+    }
+}
+
+/// The cells `op` can change (height or lock flag), or `None` when that
+/// isn't known before running it: `Range` / `Trough` spread from their ridge
+/// path over any number of cells. `index` is the spatial index over `mesh`.
+pub fn footprint(mesh: &Mesh, index: &SpatialIndex, op: &EditOp) -> Option<Vec<u32>> {
+    match (&op.region, op.mode) {
+        (Some(region), _) => Some(region.resolve(mesh, index).0),
+        (None, EditMode::Range | EditMode::Trough) => None,
+        (None, _) if op.cells.is_empty() => {
+            Some(gather_radius_cells(mesh, index, op.center_cell, op.radius))
         }
+        (None, _) => Some(op.cells.clone()),
+    }
+}
+
+/// Dispatch `op` to `apply_brush` or `apply_macro`, then put back the height
+/// of every `locked` cell, so no mode can move one (or smooth or mask it).
+fn apply_op(
+    mesh: &Mesh,
+    index: &SpatialIndex,
+    h: &mut [u8],
+    op: &EditOp,
+    weighting: Weighting,
+    locked: &[bool],
+    grid_seed: u64,
+) {
+    let before = locked.contains(&true).then(|| h.to_vec());
+    match op.mode {
+        EditMode::Raise
+        | EditMode::Lower
+        | EditMode::Flatten
+        | EditMode::Smooth
+        | EditMode::Erode
+        | EditMode::Terrace
+        | EditMode::Stamp => apply_brush(mesh, index, h, op, weighting),
+        _ => apply_macro(mesh, index, h, op, weighting, grid_seed),
+    }
+    if let Some(before) = before {
+        for (c, _) in locked.iter().enumerate().filter(|(_, &l)| l) {
+            h[c] = before[c];
+        }
+    }
+}
+
+/// `Lock` / `Unlock`: set the lock flag on the op's region, or on `cells` /
+/// the radius gather like a brush.
+fn set_locked(mesh: &Mesh, index: &SpatialIndex, cells: &mut CellData, op: &EditOp) {
+    let n = cells.h.len();
+    if cells.locked.len() != n {
+        cells.locked = vec![false; n];
+    }
+    let target = match &op.region {
+        Some(region) => region.resolve(mesh, index).0,
+        None if op.cells.is_empty() => gather_radius_cells(mesh, index, op.center_cell, op.radius),
+        None => op.cells.clone(),
+    };
+    for c in target {
+        cells.locked[c as usize] = op.mode == EditMode::Lock;
     }
 }
 
@@ -392,6 +817,7 @@ pub fn edit_heightmap_js(grid_js: JsValue, ops_js: JsValue) -> JsValue {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::heightmap::pick_cell;
     use crate::mesh;
 
     fn test_grid(n: u32, seed: u32) -> Grid {
@@ -409,7 +835,8 @@ mod tests {
         let mut grid = test_grid(2000, 42);
         let center = 500u32;
         let radius = 500.0f32; // world units — generous for 2000 cells
-        let cells = gather_radius_cells(&grid.mesh, center, radius);
+        let cells =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
         let op = EditOp {
             mode: EditMode::Raise,
             center_cell: center,
@@ -417,6 +844,7 @@ mod tests {
             radius,
             strength: 0.5,
             cells: cells.clone(),
+            ..EditOp::default()
         };
         let before = grid.cells.h[center as usize];
         edit_heightmap(&mut grid, &[op]);
@@ -456,7 +884,8 @@ mod tests {
         let mut grid = test_grid(2000, 42);
         let center = 100u32;
         let radius = 500.0;
-        let cells = gather_radius_cells(&grid.mesh, center, radius);
+        let cells =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
         let before = grid.cells.h[center as usize];
         let op = EditOp {
             mode: EditMode::Lower,
@@ -465,6 +894,7 @@ mod tests {
             radius,
             strength: 0.5,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         let after = grid.cells.h[center as usize];
@@ -481,6 +911,7 @@ mod tests {
             radius,
             strength: 1.0,
             cells: vec![center],
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid2, &[op2]);
         assert_eq!(grid2.cells.h[center as usize], 0, "lower clamps to 0");
@@ -494,7 +925,8 @@ mod tests {
         let center = 300u32;
         grid.cells.h[center as usize] = 100;
         let radius = 800.0;
-        let cells = gather_radius_cells(&grid.mesh, center, radius);
+        let cells =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
         let before_var = variance(&grid.cells.h);
         let op = EditOp {
             mode: EditMode::Smooth,
@@ -503,6 +935,7 @@ mod tests {
             radius,
             strength: 1.0,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         let after_var = variance(&grid.cells.h);
@@ -519,7 +952,8 @@ mod tests {
         let center = 200u32;
         grid.cells.h[center as usize] = 80; // center is high
         let radius = 500.0;
-        let cells = gather_radius_cells(&grid.mesh, center, radius);
+        let cells =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
         let op = EditOp {
             mode: EditMode::Flatten,
             center_cell: center,
@@ -527,6 +961,7 @@ mod tests {
             radius,
             strength: 1.0,
             cells: cells.clone(),
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         // After full-strength flatten, center stays 80; nearby cells move toward 80.
@@ -555,6 +990,7 @@ mod tests {
                 radius: 500.0,
                 strength: 0.3,
                 cells: vec![],
+                ..EditOp::default()
             },
             EditOp {
                 mode: EditMode::Smooth,
@@ -563,6 +999,7 @@ mod tests {
                 radius: 600.0,
                 strength: 0.7,
                 cells: vec![],
+                ..EditOp::default()
             },
             EditOp {
                 mode: EditMode::Lower,
@@ -571,6 +1008,7 @@ mod tests {
                 radius: 400.0,
                 strength: 0.5,
                 cells: vec![],
+                ..EditOp::default()
             },
         ];
         let mut a = make();
@@ -592,6 +1030,7 @@ mod tests {
             radius: 0.0,
             strength: 1.0, // offset = 1.0 * 100 = 100 → 50 + 100 = clamp 100
             cells: cells.clone(),
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         for &cid in &cells {
@@ -615,6 +1054,7 @@ mod tests {
             radius: 0.0,
             strength: 2.0, // (40 - 20) * 2 + 20 = 60
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         for &h in &grid.cells.h {
@@ -637,6 +1077,7 @@ mod tests {
             radius: 0.0,
             strength: 0.0,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         for &h in &grid.cells.h {
@@ -648,13 +1089,35 @@ mod tests {
     #[test]
     fn gather_radius_includes_center_and_is_deterministic() {
         let mesh = mesh::build(2000, 42);
-        let a = gather_radius_cells(&mesh, 500, 500.0);
-        let b = gather_radius_cells(&mesh, 500, 500.0);
+        let a = gather_radius_cells(&mesh, &SpatialIndex::build(&mesh), 500, 500.0);
+        let b = gather_radius_cells(&mesh, &SpatialIndex::build(&mesh), 500, 500.0);
         assert_eq!(a, b, "gather must be deterministic");
         assert!(a.contains(&500), "must include center");
         assert!(!a.is_empty(), "must return at least the center");
     }
 
+    /// `gather_radius_cells` selects exactly the cells whose centre is in
+    /// range — including ones an adjacency BFS from the center could only
+    /// reach through out-of-range cells.
+    #[test]
+    fn gather_radius_matches_brute_force() {
+        let mesh = mesh::build(2000, 42);
+        for (center, radius) in [(500u32, 500.0f32), (17, 1200.0), (1999, 80.0)] {
+            let [cx, cy] = mesh.points[center as usize];
+            let r2 = (radius as f64).powi(2);
+            let expected: Vec<u32> = (0..mesh.points.len() as u32)
+                .filter(|&c| {
+                    let [x, y] = mesh.points[c as usize];
+                    (x - cx).powi(2) + (y - cy).powi(2) <= r2
+                })
+                .collect();
+            assert_eq!(
+                gather_radius_cells(&mesh, &SpatialIndex::build(&mesh), center, radius),
+                expected
+            );
+        }
+    }
+
     /// `Range` raises cells along the ridge path from center to target,
     /// and the start cell is raised. Deterministic across re-runs.
     #[test]
@@ -670,6 +1133,7 @@ mod tests {
             radius: 0.0,
             strength: 1.0,
             cells: vec![],
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, std::slice::from_ref(&op));
         let after = grid.cells.h[center as usize];
@@ -702,6 +1166,7 @@ mod tests {
             radius: 0.0,
             strength: 1.0,
             cells: vec![],
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, std::slice::from_ref(&op));
         let after = grid.cells.h[center as usize];
@@ -730,6 +1195,7 @@ mod tests {
             radius: 0.0,
             strength: 1.0, // full blend → all cells become sea level
             cells: cells.clone(),
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         for &cid in &cells {
@@ -747,7 +1213,8 @@ mod tests {
         let mut grid = test_grid(2000, 42);
         let center = 500u32;
         let radius = 500.0f32;
-        let cells = gather_radius_cells(&grid.mesh, center, radius);
+        let cells =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
         // Set all cells to 100 so mask scales down (dist < 1).
         for &cid in &cells {
             grid.cells.h[cid as usize] = 100;
@@ -759,6 +1226,7 @@ mod tests {
             radius,
             strength: 1.0,
             cells: cells.clone(),
+            ..EditOp::default()
         };
         edit_heightmap(&mut grid, &[op]);
         let center_h = grid.cells.h[center as usize];
@@ -787,6 +1255,7 @@ mod tests {
             radius: 0.0,
             strength: 0.0,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut a, std::slice::from_ref(&op));
         edit_heightmap(&mut b, &[op]);
@@ -806,6 +1275,7 @@ mod tests {
             radius: 0.0,
             strength: 0.3,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut a, std::slice::from_ref(&op));
         edit_heightmap(&mut b, &[op]);
@@ -825,6 +1295,7 @@ mod tests {
             radius: 0.0,
             strength: 1.5,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut a, std::slice::from_ref(&op));
         edit_heightmap(&mut b, &[op]);
@@ -844,6 +1315,7 @@ mod tests {
             radius: 0.0,
             strength: 0.5,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut a, std::slice::from_ref(&op));
         edit_heightmap(&mut b, &[op]);
@@ -857,7 +1329,7 @@ mod tests {
         let mut b = test_grid(2000, 42);
         let center = 500u32;
         let radius = 500.0f32;
-        let cells = gather_radius_cells(&a.mesh, center, radius);
+        let cells = gather_radius_cells(&a.mesh, &SpatialIndex::build(&a.mesh), center, radius);
         let op = EditOp {
             mode: EditMode::Mask,
             center_cell: center,
@@ -865,12 +1337,456 @@ mod tests {
             radius,
             strength: 0.7,
             cells,
+            ..EditOp::default()
         };
         edit_heightmap(&mut a, std::slice::from_ref(&op));
         edit_heightmap(&mut b, &[op]);
         assert_eq!(a.cells.h, b.cells.h, "mask must be deterministic");
     }
 
+    /// `Erode` only touches cells inside the brush and is deterministic.
+    #[test]
+    fn erode_brush_stays_inside_radius() {
+        let mut a = test_grid(2000, 42);
+        // A cone, so there is something to erode.
+        a.cells.h = a
+            .mesh
+            .points
+            .iter()
+            .map(|&[x, y]| {
+                let d = ((x - 5000.0).powi(2) + (y - 4000.0).powi(2)).sqrt();
+                (95.0 - d / 50.0).clamp(25.0, 95.0) as u8
+            })
+            .collect();
+        let mut b = a.clone();
+        let before = a.cells.h.clone();
+        let center = pick_cell(&a.mesh, 5600.0, 4000.0).unwrap();
+        let radius = 1200.0f32;
+        let op = EditOp {
+            mode: EditMode::Erode,
+            center_cell: center,
+            target_cell: 0,
+            radius,
+            strength: 1.0,
+            cells: vec![],
+            ..EditOp::default()
+        };
+        edit_heightmap(&mut a, std::slice::from_ref(&op));
+        edit_heightmap(&mut b, &[op]);
+        assert_eq!(a.cells.h, b.cells.h, "erode must be deterministic");
+        assert_ne!(a.cells.h, before);
+        let inside = gather_radius_cells(&a.mesh, &SpatialIndex::build(&a.mesh), center, radius);
+        for (c, &was) in before.iter().enumerate() {
+            if inside.binary_search(&(c as u32)).is_err() {
+                assert_eq!(a.cells.h[c], was, "cell {c} outside the brush");
+            }
+        }
+    }
+
+    /// Noise brushes (and `Roughen`) stay inside the radius and draw the same
+    /// field from the world seed on every stroke.
+    #[test]
+    fn noise_brushes_are_seeded_and_confined() {
+        let center = 500u32;
+        let radius = 800.0f32;
+        for mode in [
+            EditMode::Fbm,
+            EditMode::Ridged,
+            EditMode::Warp,
+            EditMode::Roughen,
+        ] {
+            let mut a = test_grid(2000, 42);
+            let mut b = test_grid(2000, 42);
+            let op = EditOp {
+                mode,
+                center_cell: center,
+                target_cell: 0,
+                radius,
+                strength: 1.0,
+                cells: vec![],
+                ..EditOp::default()
+            };
+            edit_heightmap(&mut a, std::slice::from_ref(&op));
+            edit_heightmap(&mut b, std::slice::from_ref(&op));
+            assert_eq!(a.cells.h, b.cells.h, "{mode:?} must be deterministic");
+            assert!(
+                a.cells.h.iter().any(|&v| v != 50),
+                "{mode:?} changed nothing"
+            );
+            let inside =
+                gather_radius_cells(&a.mesh, &SpatialIndex::build(&a.mesh), center, radius);
+            for (c, &v) in a.cells.h.iter().enumerate() {
+                if inside.binary_search(&(c as u32)).is_err() {
+                    assert_eq!(v, 50, "{mode:?}: cell {c} outside the brush");
+                }
+            }
+            let mut other = test_grid(2000, 42);
+            other.seed = 7;
+            edit_heightmap(&mut other, &[op]);
+            assert_ne!(other.cells.h, a.cells.h, "{mode:?} ignores the world seed");
+        }
+    }
+
+    /// West-to-east ramp from 10 to 90, so both sides of the coast exist.
+    fn ramp_grid() -> Grid {
+        let mut grid = test_grid(3000, 11);
+        grid.cells.h = grid
+            .mesh
+            .points
+            .iter()
+            .map(|&[x, _]| (10.0 + x / 125.0).round() as u8)
+            .collect();
+        grid
+    }
+
+    /// At full strength the brush centre snaps onto a step; the coast and
+    /// cells outside the radius don't move.
+    #[test]
+    fn terrace_quantizes_from_sea_level() {
+        let mut grid = ramp_grid();
+        let before = grid.cells.h.clone();
+        let center = pick_cell(&grid.mesh, 2500.0, 4000.0).unwrap();
+        let radius = 3000.0f32;
+        let op = EditOp {
+            mode: EditMode::Terrace,
+            center_cell: center,
+            target_cell: 0,
+            radius,
+            strength: 1.0,
+            cells: vec![],
+            params: EditParams {
+                step: 10.0,
+                ..EditParams::default()
+            },
+            ..EditOp::default()
+        };
+        edit_heightmap(&mut grid, &[op]);
+        let c = center as usize;
+        assert_eq!(
+            (grid.cells.h[c] as i32 - SEA_LEVEL as i32).rem_euclid(10),
+            0
+        );
+        assert!(grid.cells.h[c] <= before[c]);
+        let inside =
+            gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
+        for (c, (&was, &now)) in before.iter().zip(&grid.cells.h).enumerate() {
+            assert_eq!(
+                was >= SEA_LEVEL,
+                now >= SEA_LEVEL,
+                "cell {c} crossed the coast"
+            );
+            if inside.binary_search(&(c as u32)).is_err() {
+                assert_eq!(was, now, "cell {c} outside the brush");
+            }
+        }
+        assert_ne!(grid.cells.h, before);
+    }
+
+    /// The centre receives the source centre's height; a half-turn reverses
+    /// the copied ramp; cells outside the radius are untouched.
+    #[test]
+    fn stamp_copies_rotated_patch() {
+        let radius = 1500.0f32;
+        let stamp = |rotation: f32| {
+            let mut grid = ramp_grid();
+            let before = grid.cells.h.clone();
+            let center = pick_cell(&grid.mesh, 7000.0, 4000.0).unwrap();
+            let source = pick_cell(&grid.mesh, 2500.0, 4000.0).unwrap();
+            let op = EditOp {
+                mode: EditMode::Stamp,
+                center_cell: center,
+                target_cell: source,
+                radius,
+                strength: 1.0,
+                cells: vec![],
+                params: EditParams {
+                    rotation,
+                    ..EditParams::default()
+                },
+                ..EditOp::default()
+            };
+            edit_heightmap(&mut grid, &[op]);
+            assert_eq!(grid.cells.h[center as usize], before[source as usize]);
+            let inside =
+                gather_radius_cells(&grid.mesh, &SpatialIndex::build(&grid.mesh), center, radius);
+            for (c, &was) in before.iter().enumerate() {
+                if inside.binary_search(&(c as u32)).is_err() {
+                    assert_eq!(grid.cells.h[c], was, "cell {c} outside the brush");
+                }
+            }
+            // Mean height east minus west of the centre, near the centre.
+            let near = gather_radius_cells(
+                &grid.mesh,
+                &SpatialIndex::build(&grid.mesh),
+                center,
+                radius / 2.0,
+            );
+            let [cx, _] = grid.mesh.points[center as usize];
+            let side = |east: bool| {
+                let hs: Vec<f64> = near
+                    .iter()
+                    .filter(|&&c| (grid.mesh.points[c as usize][0] > cx) == east)
+                    .map(|&c| grid.cells.h[c as usize] as f64)
+                    .collect();
+                hs.iter().sum::<f64>() / hs.len() as f64
+            };
+            side(true) - side(false)
+        };
+        assert!(stamp(0.0) > 0.0);
+        assert!(stamp(180.0) < 0.0);
+    }
+
+    fn region_op(mode: EditMode, strength: f32, region: Region) -> EditOp {
+        EditOp {
+            mode,
+            center_cell: 0,
+            target_cell: 0,
+            radius: 0.0,
+            strength,
+            cells: vec![],
+            region: Some(region),
+            ..EditOp::default()
+        }
+    }
+
+    /// A polygon op changes exactly the cells inside it, at full strength;
+    /// a lasso along the same outline does the same.
+    #[test]
+    fn polygon_region_lowers_inside_only() {
+        let bay = vec![
+            [2000.0, 2000.0],
+            [6000.0, 1500.0],
+            [7000.0, 5000.0],
+            [3000.0, 6000.0],
+        ];
+        let mut grid = test_grid(3000, 5);
+        let region = Region {
+            shape: RegionShape::Polygon {
+                points: bay.clone(),
+            },
+            feather: 0.0,
+        };
+        edit_heightmap(&mut grid, &[region_op(EditMode::Lower, 0.1, region)]);
+        let inside = SpatialIndex::build(&grid.mesh).in_polygon(&grid.mesh, &bay);
+        assert!(!inside.is_empty());
+        for (c, &v) in grid.cells.h.iter().enumerate() {
+            let expected = if inside.binary_search(&(c as u32)).is_ok() {
+                40
+            } else {
+                50
+            };
+            assert_eq!(v, expected, "cell {c}");
+        }
+
+        let mut lasso = test_grid(3000, 5);
+        let region = Region {
+            shape: RegionShape::Lasso { path: bay },
+            feather: 0.0,
+        };
+        edit_heightmap(&mut lasso, &[region_op(EditMode::Lower, 0.1, region)]);
+        assert_eq!(lasso.cells.h, grid.cells.h);
+    }
+
+    /// Feathering ramps the effect up from the outline; macros work too.
+    #[test]
+    fn rect_region_feathers_edges() {
+        let mut grid = test_grid(3000, 5);
+        let region = Region {
+            shape: RegionShape::Rect {
+                x0: 7000.0,
+                y0: 6000.0,
+                x1: 2000.0,
+                y1: 2000.0,
+            },
+            feather: 1000.0,
+        };
+        edit_heightmap(&mut grid, &[region_op(EditMode::Add, 0.2, region)]);
+        let index = SpatialIndex::build(&grid.mesh);
+        let h_at = |x: f64, y: f64| grid.cells.h[index.nearest(&grid.mesh, x, y).unwrap() as usize];
+        assert_eq!(h_at(4500.0, 4000.0), 70);
+        let edge = h_at(2350.0, 4000.0);
+        assert!(edge > 50 && edge < 60, "edge {edge}");
+        assert_eq!(h_at(1500.0, 4000.0), 50);
+        assert_eq!(h_at(4500.0, 6600.0), 50);
+    }
+
+    /// On a cylinder the feather ramps in from both sides of a region that
+    /// straddles the seam.
+    #[test]
+    fn region_feather_wraps_the_seam() {
+        let mesh = mesh::build_with_topology(3000, 5, mesh::Topology::Cylinder);
+        let mut grid = Grid::from_mesh(&mesh, 5);
+        grid.cells.h = vec![50u8; grid.mesh.points.len()];
+        let w = grid.mesh.world_w;
+        let region = Region {
+            shape: RegionShape::Rect {
+                x0: w - 2000.0,
+                y0: 2000.0,
+                x1: w + 2000.0,
+                y1: 6000.0,
+            },
+            feather: 1000.0,
+        };
+        edit_heightmap(&mut grid, &[region_op(EditMode::Add, 0.2, region)]);
+        let index = SpatialIndex::build(&grid.mesh);
+        let h_at = |x: f64, y: f64| grid.cells.h[index.nearest(&grid.mesh, x, y).unwrap() as usize];
+        assert_eq!(h_at(0.0, 4000.0), 70);
+        for x in [1500.0, w - 1500.0] {
+            let edge = h_at(x, 4000.0);
+            assert!(edge > 50 && edge < 70, "edge {edge} at x = {x}");
+        }
+    }
+
+    /// A region across the seam is centred on the seam: Flatten pulls it to
+    /// a height from inside the region, not from the far side of the world.
+    #[test]
+    fn region_center_wraps_the_seam() {
+        let mesh = mesh::build_with_topology(3000, 5, mesh::Topology::Cylinder);
+        let mut grid = Grid::from_mesh(&mesh, 5);
+        let w = grid.mesh.world_w;
+        grid.cells.h = grid
+            .mesh
+            .points
+            .iter()
+            .map(|&[x, y]| {
+                if grid.mesh.delta_x(x, 0.0).abs() < 2500.0 {
+                    30 + (y / 1000.0) as u8
+                } else {
+                    90
+                }
+            })
+            .collect();
+        let region = Region {
+            shape: RegionShape::Rect {
+                x0: w - 2000.0,
+                y0: 2000.0,
+                x1: w + 2000.0,
+                y1: 6000.0,
+            },
+            feather: 0.0,
+        };
+        let (cells, _) = region.resolve(&grid.mesh, &SpatialIndex::build(&grid.mesh));
+        edit_heightmap(&mut grid, &[region_op(EditMode::Flatten, 1.0, region)]);
+        let flat = grid.cells.h[cells[0] as usize];
+        assert!((33..=35).contains(&flat), "flattened to {flat}");
+        assert!(cells.iter().all(|&c| grid.cells.h[c as usize] == flat));
+    }
+
+    /// Every op with a `footprint` changes only the cells in it; the ridge
+    /// macros have none.
+    #[test]
+    fn ops_stay_inside_their_footprint() {
+        let base = ramp_grid();
+        let [cx, cy] = base.mesh.points[0];
+        let region = Region {
+            shape: RegionShape::Rect {
+                x0: cx - 1500.0,
+                y0: cy - 1500.0,
+                x1: cx + 1500.0,
+                y1: cy + 1500.0,
+            },
+            feather: 300.0,
+        };
+        let index = SpatialIndex::build(&base.mesh);
+        let center = index.nearest(&base.mesh, 5000.0, 4000.0).unwrap();
+        for mode in [
+            EditMode::Raise,
+            EditMode::Lower,
+            EditMode::Flatten,
+            EditMode::Smooth,
+            EditMode::Strait,
+            EditMode::Mask,
+            EditMode::Invert,
+            EditMode::Add,
+            EditMode::Multiply,
+            EditMode::Erode,
+            EditMode::Fbm,
+            EditMode::Terrace,
+            EditMode::Roughen,
+            EditMode::Stamp,
+            EditMode::Lock,
+        ] {
+            for region in [None, Some(region.clone())] {
+                let op = EditOp {
+                    mode,
+                    center_cell: center,
+                    target_cell: 0,
+                    radius: 1200.0,
+                    strength: 0.6,
+                    cells: vec![],
+                    region,
+                    ..EditOp::default()
+                };
+                let inside = footprint(&base.mesh, &index, &op).unwrap();
+                let mut grid = base.clone();
+                edit_heightmap(&mut grid, std::slice::from_ref(&op));
+                for c in 0..grid.cells.h.len() {
+                    if grid.cells.h[c] != base.cells.h[c] || grid.cells.is_locked(c) {
+                        assert!(inside.contains(&(c as u32)), "{mode:?} changed cell {c}");
+                    }
+                }
+            }
+        }
+        for mode in [EditMode::Range, EditMode::Trough] {
+            let op = EditOp {
+                mode,
+                center_cell: center,
+                target_cell: 0,
+                radius: 1200.0,
+                strength: 0.6,
+                cells: vec![],
+                ..EditOp::default()
+            };
+            assert_eq!(footprint(&base.mesh, &index, &op), None);
+        }
+    }
+
+    /// Brushes, smoothing and masks leave locked cells alone while their
+    /// neighbours change; unlocking makes them editable again.
+    #[test]
+    fn locked_cells_resist_edits() {
+        let mut grid = ramp_grid();
+        let region = Region {
+            shape: RegionShape::Rect {
+                x0: 4000.0,
+                y0: 3000.0,
+                x1: 6000.0,
+                y1: 5000.0,
+            },
+            feather: 0.0,
+        };
+        edit_heightmap(&mut grid, &[region_op(EditMode::Lock, 1.0, region.clone())]);
+        let locked: Vec<usize> = (0..grid.cells.h.len())
+            .filter(|&c| grid.cells.is_locked(c))
+            .collect();
+        assert!(!locked.is_empty());
+        let before = grid.cells.h.clone();
+
+        let center = pick_cell(&grid.mesh, 5000.0, 4000.0).unwrap();
+        let brush = |mode| EditOp {
+            mode,
+            center_cell: center,
+            target_cell: 0,
+            radius: 3000.0,
+            strength: 0.5,
+            cells: vec![],
+            ..EditOp::default()
+        };
+        for mode in [EditMode::Raise, EditMode::Smooth, EditMode::Mask] {
+            let prev = grid.cells.h.clone();
+            edit_heightmap(&mut grid, &[brush(mode)]);
+            assert_ne!(grid.cells.h, prev, "{mode:?} changed nothing");
+            for &c in &locked {
+                assert_eq!(grid.cells.h[c], before[c], "{mode:?} moved cell {c}");
+            }
+        }
+
+        let unlock = region_op(EditMode::Unlock, 1.0, region);
+        edit_heightmap(&mut grid, &[unlock, brush(EditMode::Raise)]);
+        assert!(!grid.cells.locked.contains(&true));
+        assert!(locked.iter().any(|&c| grid.cells.h[c] != before[c]));
+    }
+
     fn variance(h: &[u8]) -> f64 {
         if h.is_empty() {
             return 0.0;
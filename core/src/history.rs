@@ -0,0 +1,452 @@
+//! Undo / redo history for edits to the held grid.
+//!
+//! Each recorded step is a compact diff: the ids of the cells the edit
+//! changed, with their height, entity indices (`state` / `province` /
+//! `culture` / `religion` / `burg`) and lock flag before and after. Undo
+//! writes the "before" values back, redo the "after" values; jumping walks
+//! the steps in between. Nothing else is stored — climate, biomes and
+//! drainage are derived from `h`, so after moving through the history they
+//! are recomputed (`lib.rs` marks them stale and refreshes them on the next
+//! read).
+//!
+//! The "before" values come from a `Snapshot`: of every cell, or, for an op
+//! with a known `heightmap_edit::footprint`, of just the cells it can change.
+//!
+//! Entity indices are part of the diff because whole-map operations (reset,
+//! templates, imports) clear them, and undoing those must bring them back.
+//! The entity repair that `recompute_dependents_inner` runs after an edit
+//! only clears cells that the edit turned into water, which are already in
+//! that edit's diff, so their "before" values restore them too.
+//!
+//! Recording a new step drops any redo tail. At most `MAX_STEPS` steps are
+//! kept; the oldest fall off first.
+//!
+//! Steps made by an `EditOp` keep the op, so the applied steps can be turned
+//! back into the op list of an edit `journal`.
+
+use serde::Serialize;
+
+use crate::grid::CellData;
+use crate::heightmap_edit::EditOp;
+
+/// Most steps kept before the oldest are dropped.
+pub const MAX_STEPS: usize = 256;
+
+/// The per-cell values a step restores.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CellValues {
+    h: u8,
+    state: i32,
+    province: i32,
+    culture: i32,
+    religion: i32,
+    burg: i16,
+    locked: bool,
+}
+
+impl CellValues {
+    fn read(cells: &CellData, c: usize) -> Self {
+        CellValues {
+            h: cells.h[c],
+            state: cells.state[c],
+            province: cells.province[c],
+            culture: cells.culture[c],
+            religion: cells.religion[c],
+            burg: cells.burg[c],
+            locked: cells.is_locked(c),
+        }
+    }
+
+    fn write(self, cells: &mut CellData, c: usize) {
+        cells.h[c] = self.h;
+        cells.state[c] = self.state;
+        cells.province[c] = self.province;
+        cells.culture[c] = self.culture;
+        cells.religion[c] = self.religion;
+        cells.burg[c] = self.burg;
+        // A grid without a lock mask never had a lock to restore.
+        if let Some(locked) = cells.locked.get_mut(c) {
+            *locked = self.locked;
+        }
+    }
+}
+
+/// The diffed layers of a `CellData`, taken before an edit.
+pub struct Snapshot {
+    /// The cells taken, ascending; `None` for all of them.
+    ids: Option<Vec<u32>>,
+    values: Vec<CellValues>,
+}
+
+impl Snapshot {
+    pub fn of(cells: &CellData) -> Self {
+        Snapshot {
+            ids: None,
+            values: (0..cells.h.len())
+                .map(|c| CellValues::read(cells, c))
+                .collect(),
+        }
+    }
+
+    /// Only the cells `ids`, for an edit known to change no others.
+    pub fn of_cells(cells: &CellData, mut ids: Vec<u32>) -> Self {
+        ids.sort_unstable();
+        ids.dedup();
+        Snapshot {
+            values: ids
+                .iter()
+                .map(|&c| CellValues::read(cells, c as usize))
+                .collect(),
+            ids: Some(ids),
+        }
+    }
+}
+
+struct Step {
+    label: String,
+    /// The op that made the step, if it was an `EditOp`.
+    op: Option<EditOp>,
+    cells: Vec<u32>,
+    before: Vec<CellValues>,
+    after: Vec<CellValues>,
+}
+
+/// One step as reported to the UI.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StepInfo {
+    /// What made the step (the `EditMode`, `"Reset"`, `"Template"`, ...).
+    pub label: String,
+    /// Number of cells it changed.
+    pub cells: u32,
+}
+
+/// The whole history as reported to the UI. `cursor` is the number of
+/// steps currently applied: `0` is the state before the first kept step,
+/// `steps.len()` the latest edit.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HistoryInfo {
+    pub cursor: u32,
+    pub steps: Vec<StepInfo>,
+}
+
+#[derive(Default)]
+pub struct History {
+    steps: Vec<Step>,
+    cursor: usize,
+    /// Ops applied before the first kept step (replayed, or fallen off).
+    base_ops: Vec<EditOp>,
+    /// Something other than an `EditOp` fell off the front.
+    base_unjournaled: bool,
+}
+
+impl History {
+    /// An empty history over a grid that already has `ops` applied.
+    pub fn from_ops(ops: Vec<EditOp>) -> Self {
+        History {
+            base_ops: ops,
+            ..History::default()
+        }
+    }
+
+    /// Record the difference between `before` and `after` as a step labelled
+    /// `label`. An edit that changed nothing records nothing; returns whether
+    /// a step was added.
+    pub fn record(&mut self, label: &str, before: &Snapshot, after: &CellData) -> bool {
+        self.push(label.to_string(), None, before, after)
+    }
+
+    /// `record` for a step made by `op`, labelled with its mode.
+    pub fn record_op(&mut self, op: &EditOp, before: &Snapshot, after: &CellData) -> bool {
+        self.push(format!("{:?}", op.mode), Some(op.clone()), before, after)
+    }
+
+    fn push(
+        &mut self,
+        label: String,
+        op: Option<EditOp>,
+        before: &Snapshot,
+        after: &CellData,
+    ) -> bool {
+        let mut step = Step {
+            label,
+            op,
+            cells: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+        for (k, &was) in before.values.iter().enumerate() {
+            let c = before.ids.as_ref().map_or(k, |ids| ids[k] as usize);
+            let now = CellValues::read(after, c);
+            if now != was {
+                step.cells.push(c as u32);
+                step.before.push(was);
+                step.after.push(now);
+            }
+        }
+        if step.cells.is_empty() {
+            return false;
+        }
+        self.steps.truncate(self.cursor);
+        self.steps.push(step);
+        if self.steps.len() > MAX_STEPS {
+            let oldest = self.steps.remove(0);
+            self.retire(oldest);
+        }
+        self.cursor = self.steps.len();
+        true
+    }
+
+    /// Revert the latest applied step. `false` if there is none.
+    pub fn undo(&mut self, cells: &mut CellData) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        let step = &self.steps[self.cursor];
+        for (&c, &v) in step.cells.iter().zip(&step.before) {
+            v.write(cells, c as usize);
+        }
+        true
+    }
+
+    /// Re-apply the next undone step. `false` if there is none.
+    pub fn redo(&mut self, cells: &mut CellData) -> bool {
+        let Some(step) = self.steps.get(self.cursor) else {
+            return false;
+        };
+        for (&c, &v) in step.cells.iter().zip(&step.after) {
+            v.write(cells, c as usize);
+        }
+        self.cursor += 1;
+        true
+    }
+
+    /// Undo or redo until `cursor` steps are applied. `false` (and no
+    /// change) if `cursor` is past the last step.
+    pub fn jump(&mut self, cursor: usize, cells: &mut CellData) -> bool {
+        if cursor > self.steps.len() {
+            return false;
+        }
+        while self.cursor > cursor {
+            self.undo(cells);
+        }
+        while self.cursor < cursor {
+            self.redo(cells);
+        }
+        true
+    }
+
+    /// Forget every step: the applied ones become part of the base state,
+    /// the undone ones are dropped.
+    pub fn clear(&mut self) {
+        let applied: Vec<Step> = self.steps.drain(..self.cursor).collect();
+        for step in applied {
+            self.retire(step);
+        }
+        self.steps.clear();
+        self.cursor = 0;
+    }
+
+    /// Fold an applied step that can no longer be undone into the base.
+    fn retire(&mut self, step: Step) {
+        match step.op {
+            Some(op) => self.base_ops.push(op),
+            None => self.base_unjournaled = true,
+        }
+    }
+
+    /// Every `EditOp` behind the current state, in order, or `None` if some
+    /// applied step wasn't an `EditOp` (a reset, template, import, ...).
+    pub fn applied_ops(&self) -> Option<Vec<EditOp>> {
+        if self.base_unjournaled {
+            return None;
+        }
+        let mut ops = self.base_ops.clone();
+        for step in &self.steps[..self.cursor] {
+            ops.push(step.op.clone()?);
+        }
+        Some(ops)
+    }
+
+    pub fn info(&self) -> HistoryInfo {
+        HistoryInfo {
+            cursor: self.cursor as u32,
+            steps: self
+                .steps
+                .iter()
+                .map(|s| StepInfo {
+                    label: s.label.clone(),
+                    cells: s.cells.len() as u32,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::heightmap_edit::{edit_heightmap, EditMode, EditOp};
+    use crate::mesh;
+    use crate::spatial::SpatialIndex;
+
+    fn raise(center: u32) -> EditOp {
+        EditOp {
+            mode: EditMode::Raise,
+            center_cell: center,
+            target_cell: 0,
+            radius: 600.0,
+            strength: 0.3,
+            cells: vec![],
+            ..EditOp::default()
+        }
+    }
+
+    fn record(history: &mut History, grid: &mut Grid, label: &str, edit: impl FnOnce(&mut Grid)) {
+        let before = Snapshot::of(&grid.cells);
+        edit(grid);
+        history.record(label, &before, &grid.cells);
+    }
+
+    /// Undo, redo and jump land on exactly the heights each step produced,
+    /// and each step only stores the cells it touched.
+    #[test]
+    fn undo_redo_and_jump_restore_each_step() {
+        let mut grid = Grid::from_mesh(&mesh::build(2000, 3), 3);
+        grid.cells.h = vec![30; grid.mesh.points.len()];
+        let mut history = History::default();
+        let mut states = vec![grid.cells.h.clone()];
+        for center in [100, 900, 1500] {
+            record(&mut history, &mut grid, "Raise", |g| {
+                edit_heightmap(g, &[raise(center)])
+            });
+            states.push(grid.cells.h.clone());
+        }
+        let info = history.info();
+        assert_eq!(info.cursor, 3);
+        assert!(info
+            .steps
+            .iter()
+            .all(|s| s.cells > 0 && (s.cells as usize) < grid.mesh.points.len() / 2));
+
+        assert!(history.undo(&mut grid.cells));
+        assert_eq!(grid.cells.h, states[2]);
+        assert!(history.undo(&mut grid.cells));
+        assert!(history.redo(&mut grid.cells));
+        assert_eq!(grid.cells.h, states[2]);
+        assert!(history.jump(0, &mut grid.cells));
+        assert_eq!(grid.cells.h, states[0]);
+        assert!(!history.undo(&mut grid.cells));
+        assert!(history.jump(3, &mut grid.cells));
+        assert_eq!(grid.cells.h, states[3]);
+        assert!(!history.redo(&mut grid.cells));
+        assert!(!history.jump(4, &mut grid.cells));
+
+        // A new edit after undoing drops the redo tail.
+        history.undo(&mut grid.cells);
+        record(&mut history, &mut grid, "Raise", |g| {
+            edit_heightmap(g, &[raise(40)])
+        });
+        assert_eq!(history.info().steps.len(), 3);
+        assert!(!history.redo(&mut grid.cells));
+    }
+
+    /// A snapshot of just the touched cells records the same step as a full
+    /// one.
+    #[test]
+    fn partial_snapshot_records_the_same_step() {
+        let mut grid = Grid::from_mesh(&mesh::build(2000, 4), 4);
+        grid.cells.h = vec![30; grid.mesh.points.len()];
+        let op = raise(700);
+        let touched =
+            crate::heightmap_edit::footprint(&grid.mesh, &SpatialIndex::build(&grid.mesh), &op)
+                .unwrap();
+        let (mut full, mut partial) = (History::default(), History::default());
+        let before = Snapshot::of(&grid.cells);
+        let before_touched = Snapshot::of_cells(&grid.cells, touched);
+        edit_heightmap(&mut grid, std::slice::from_ref(&op));
+        assert!(full.record_op(&op, &before, &grid.cells));
+        assert!(partial.record_op(&op, &before_touched, &grid.cells));
+        assert_eq!(partial.info(), full.info());
+        let edited = grid.cells.h.clone();
+        partial.undo(&mut grid.cells);
+        assert_eq!(grid.cells.h, vec![30; grid.mesh.points.len()]);
+        partial.redo(&mut grid.cells);
+        assert_eq!(grid.cells.h, edited);
+    }
+
+    /// Undoing a whole-map reset brings back the entity indices it cleared;
+    /// a no-op edit records nothing.
+    #[test]
+    fn undo_restores_entities_and_skips_no_ops() {
+        let mut grid = Grid::from_mesh(&mesh::build(1000, 5), 5);
+        grid.cells.h = vec![40; grid.mesh.points.len()];
+        grid.cells.state[7] = 2;
+        grid.cells.burg[7] = 4;
+        let mut history = History::default();
+        record(&mut history, &mut grid, "Nothing", |_| {});
+        assert!(history.info().steps.is_empty());
+        record(&mut history, &mut grid, "Reset", |g| {
+            g.cells.h.fill(10);
+            g.cells.state.fill(-1);
+            g.cells.burg.fill(0);
+        });
+        history.undo(&mut grid.cells);
+        assert_eq!(
+            (grid.cells.h[7], grid.cells.state[7], grid.cells.burg[7]),
+            (40, 2, 4)
+        );
+    }
+
+    /// The op list follows undo / redo and survives steps falling off, but
+    /// not a non-op step.
+    #[test]
+    fn applied_ops_follow_the_cursor() {
+        let mut grid = Grid::from_mesh(&mesh::build(1000, 2), 2);
+        grid.cells.h = vec![30; grid.mesh.points.len()];
+        let mut history = History::from_ops(vec![raise(5)]);
+        for center in 0..MAX_STEPS as u32 + 2 {
+            let mut op = raise(center % 900);
+            if center % 2 == 1 {
+                op.mode = EditMode::Lower;
+            }
+            let before = Snapshot::of(&grid.cells);
+            edit_heightmap(&mut grid, std::slice::from_ref(&op));
+            assert!(history.record_op(&op, &before, &grid.cells));
+        }
+        let centers =
+            |ops: Vec<EditOp>| -> Vec<u32> { ops.iter().map(|o| o.center_cell).collect() };
+        let all = centers(history.applied_ops().unwrap());
+        assert_eq!(all.len(), MAX_STEPS + 3);
+        assert_eq!(&all[..3], &[5, 0, 1]);
+        history.undo(&mut grid.cells);
+        assert_eq!(
+            centers(history.applied_ops().unwrap()),
+            all[..all.len() - 1]
+        );
+        record(&mut history, &mut grid, "Reset", |g| g.cells.h.fill(10));
+        assert!(history.applied_ops().is_none());
+        history.undo(&mut grid.cells);
+        assert!(history.applied_ops().is_some());
+        history.clear();
+        assert!(history.info().steps.is_empty());
+        assert_eq!(history.applied_ops().unwrap().len(), all.len() - 1);
+    }
+
+    #[test]
+    fn oldest_steps_fall_off() {
+        let mut grid = Grid::from_mesh(&mesh::build(500, 1), 1);
+        let mut history = History::default();
+        for k in 0..MAX_STEPS + 5 {
+            record(&mut history, &mut grid, "Set", |g| {
+                g.cells.h[0] = (k % 100) as u8 + 1
+            });
+        }
+        let info = history.info();
+        assert_eq!(info.steps.len(), MAX_STEPS);
+        assert_eq!(info.cursor as usize, MAX_STEPS);
+        assert!(history.jump(0, &mut grid.cells));
+        assert_eq!(grid.cells.h[0], 5);
+    }
+}
@@ -0,0 +1,241 @@
+//! Replayable edit journals.
+//!
+//! Generation and `heightmap_edit` are deterministic, so an edited world is
+//! fully described by how its base world was generated (`WorldOrigin`) plus
+//! the ordered `EditOp`s applied to it. An `EditJournal` stores exactly that,
+//! with the generator version and an xxh64 checksum of the edited `cells.h`
+//! — a few KB instead of the full grid.
+//!
+//! `replay` regenerates the base world, applies the ops, re-runs
+//! `recompute_dependents_inner` (as the editor does after a stroke) and
+//! checks the checksum, so a journal that no longer reproduces its world
+//! (a different generator, a hand-edited file) is rejected instead of
+//! silently producing something else.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::climate::ClimateOpts;
+use crate::grid::Grid;
+use crate::heightmap::TemplateError;
+use crate::heightmap_edit::{self, EditOp};
+use crate::mesh::Topology;
+use crate::tectonics::TectonicOpts;
+
+/// Version of the generators a journal was recorded with. Replaying under a
+/// different version is refused: the base world may no longer match.
+pub const GENERATOR_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Where the base world's heightmap came from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(tag = "kind")]
+pub enum HeightmapSource {
+    /// The default template (`generate_world`).
+    #[default]
+    Default,
+    /// A library template id/name or template text.
+    Template { template: String },
+    /// Plate tectonics.
+    Tectonic { opts: TectonicOpts },
+}
+
+/// The inputs a base world is generated from.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WorldOrigin {
+    pub seed: u32,
+    pub cell_count: u32,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default)]
+    pub heightmap: HeightmapSource,
+    #[serde(default)]
+    pub climate: ClimateOpts,
+}
+
+impl WorldOrigin {
+    /// Generate the base world.
+    pub fn generate(&self) -> Result<Grid, TemplateError> {
+        let (seed, n, topology) = (self.seed, self.cell_count, self.topology);
+        Ok(match &self.heightmap {
+            HeightmapSource::Default => {
+                crate::generate_world_with_topology_inner(seed, n, topology, &self.climate)
+            }
+            HeightmapSource::Template { template } => crate::generate_world_from_template_inner(
+                seed,
+                n,
+                template,
+                topology,
+                &self.climate,
+            )?,
+            HeightmapSource::Tectonic { opts } => {
+                crate::generate_world_tectonic_inner(seed, n, topology, opts, &self.climate)
+            }
+        })
+    }
+}
+
+/// A world as its origin plus the edits applied to it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EditJournal {
+    /// `GENERATOR_VERSION` at recording time.
+    pub version: String,
+    pub origin: WorldOrigin,
+    pub ops: Vec<EditOp>,
+    /// `checksum` of the edited `cells.h`.
+    pub checksum: String,
+}
+
+/// Why a journal could not be replayed.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum JournalError {
+    /// Recorded with a different generator version.
+    Version { expected: String, found: String },
+    /// The origin's template is malformed.
+    Template { error: TemplateError },
+    /// The replayed heightmap doesn't match the recorded checksum.
+    Checksum { expected: String, found: String },
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JournalError::Version { expected, found } => write!(
+                f,
+                "journal was recorded with generator {found}, this is {expected}"
+            ),
+            JournalError::Template { error } => write!(f, "journal template: {error}"),
+            JournalError::Checksum { expected, found } => write!(
+                f,
+                "replayed heightmap checksum {found} does not match the journal's {expected}"
+            ),
+        }
+    }
+}
+
+/// xxh64 of a heightmap, as 16 hex digits (a `u64` doesn't survive JSON).
+pub fn checksum(h: &[u8]) -> String {
+    format!("{:016x}", xxhash_rust::xxh64::xxh64(h, 0))
+}
+
+/// Journal `grid`, the result of applying `ops` to the world of `origin`.
+pub fn record(origin: &WorldOrigin, ops: Vec<EditOp>, grid: &Grid) -> EditJournal {
+    EditJournal {
+        version: GENERATOR_VERSION.to_string(),
+        origin: origin.clone(),
+        ops,
+        checksum: checksum(&grid.cells.h),
+    }
+}
+
+/// Rebuild the edited world of `journal` and verify it against the checksum.
+pub fn replay(journal: &EditJournal) -> Result<Grid, JournalError> {
+    if journal.version != GENERATOR_VERSION {
+        return Err(JournalError::Version {
+            expected: GENERATOR_VERSION.to_string(),
+            found: journal.version.clone(),
+        });
+    }
+    let mut grid = journal
+        .origin
+        .generate()
+        .map_err(|error| JournalError::Template { error })?;
+    if !journal.ops.is_empty() {
+        heightmap_edit::edit_heightmap(&mut grid, &journal.ops);
+        crate::recompute_dependents_inner(&mut grid, &journal.origin.climate);
+    }
+    let found = checksum(&grid.cells.h);
+    if found != journal.checksum {
+        return Err(JournalError::Checksum {
+            expected: journal.checksum.clone(),
+            found,
+        });
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heightmap_edit::EditMode;
+
+    fn origin() -> WorldOrigin {
+        WorldOrigin {
+            seed: 11,
+            cell_count: 2000,
+            topology: Topology::Flat,
+            heightmap: HeightmapSource::Template {
+                template: "archipelago".to_string(),
+            },
+            climate: ClimateOpts::default(),
+        }
+    }
+
+    fn ops() -> Vec<EditOp> {
+        [
+            (EditMode::Raise, 40),
+            (EditMode::Smooth, 900),
+            (EditMode::Ridged, 1500),
+        ]
+        .into_iter()
+        .map(|(mode, center_cell)| EditOp {
+            mode,
+            center_cell,
+            target_cell: 0,
+            radius: 900.0,
+            strength: 0.5,
+            cells: vec![],
+            ..EditOp::default()
+        })
+        .collect()
+    }
+
+    /// A journal survives a JSON round trip and replays to the exact world
+    /// it was recorded from.
+    #[test]
+    fn replay_rebuilds_the_edited_world() {
+        let origin = origin();
+        let mut grid = origin.generate().unwrap();
+        let base = grid.cells.h.clone();
+        heightmap_edit::edit_heightmap(&mut grid, &ops());
+        crate::recompute_dependents_inner(&mut grid, &origin.climate);
+        assert_ne!(grid.cells.h, base);
+
+        let journal = record(&origin, ops(), &grid);
+        let json = serde_json::to_string(&journal).unwrap();
+        let journal: EditJournal = serde_json::from_str(&json).unwrap();
+        let replayed = replay(&journal).unwrap();
+        assert_eq!(
+            serde_json::to_vec(&replayed).unwrap(),
+            serde_json::to_vec(&grid).unwrap()
+        );
+    }
+
+    /// Dropped ops, a foreign version and a bad template are all rejected.
+    #[test]
+    fn replay_rejects_mismatches() {
+        let origin = origin();
+        let mut grid = origin.generate().unwrap();
+        heightmap_edit::edit_heightmap(&mut grid, &ops());
+        let mut journal = record(&origin, ops(), &grid);
+
+        journal.ops.pop();
+        assert!(matches!(
+            replay(&journal),
+            Err(JournalError::Checksum { .. })
+        ));
+        journal.version = "0.0.0-other".to_string();
+        assert!(matches!(
+            replay(&journal),
+            Err(JournalError::Version { .. })
+        ));
+        journal.version = GENERATOR_VERSION.to_string();
+        journal.origin.heightmap = HeightmapSource::Template {
+            template: "Bogus 1 2".to_string(),
+        };
+        assert!(matches!(
+            replay(&journal),
+            Err(JournalError::Template { .. })
+        ));
+    }
+}
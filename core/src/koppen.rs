@@ -0,0 +1,485 @@
+//! Köppen–Geiger climate classification.
+//!
+//! Classifies every land cell into one of the 30 Köppen–Geiger classes (`Af`,
+//! `BWh`, `Cfb`, `Dfc`, `ET`, ...) from twelve months of temperature and
+//! precipitation, following the criteria of Peel, Finlayson & McMahon (2007).
+//! Water cells get `OCEAN`. The table (`KOPPEN`) carries each class's code,
+//! name and the customary colour (Beck et al. 2018), indexed by id.
+//!
+//! ## Inputs
+//!
+//! - **Seasonal** (`classify_seasons`): the monthly fields of
+//!   `climate::generate_seasons`. This is the real classification.
+//! - **Annual** (`classify_annual`): only `cells.temp` / `cells.prec`. A year
+//!   is synthesized around the annual mean — temperature swings sinusoidally
+//!   by `ANNUAL_SWING` per °C below `SWING_PIVOT`, rain falls evenly — so the
+//!   thermal classes (A/C/D/E and their third letters) and the arid B classes
+//!   come out about right, but the dry-season letters (`s`, `w`, `m`) never
+//!   appear.
+//!
+//! `prec` is FMG's unitless scale; `MM_PER_PREC` converts it to millimetres a
+//! year the way FMG reports it.
+
+use serde::Serialize;
+
+use crate::climate::{Seasons, MONTHS, SEA_LEVEL};
+
+/// FMG `getFriendlyPrecipitation`: one `prec` unit is 100 mm a year.
+const MM_PER_PREC: f64 = 100.0;
+
+/// Annual fallback: half the yearly temperature range per °C the annual mean
+/// is below `SWING_PIVOT` (an equatorial year barely changes, a subarctic one
+/// swings by tens of degrees).
+const ANNUAL_SWING: f64 = 0.5;
+
+/// Annual mean (°C) without a seasonal swing in the annual fallback.
+const SWING_PIVOT: f64 = 27.0;
+
+/// Largest half-range (°C) the annual fallback gives a year.
+const MAX_SWING: f64 = 22.0;
+
+/// Id of water cells.
+pub const OCEAN: u8 = 0;
+
+/// One Köppen–Geiger class.
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct KoppenDef {
+    pub id: u8,
+    /// Köppen code, e.g. `"Cfb"`.
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Packed `0xRRGGBB`, like `BiomeDef::color`.
+    pub color: u32,
+}
+
+/// Ocean plus the 30 Köppen–Geiger classes, in id order.
+pub const KOPPEN: [KoppenDef; 31] = [
+    KoppenDef {
+        id: 0,
+        code: "",
+        name: "Ocean",
+        color: 0x466eab,
+    },
+    KoppenDef {
+        id: 1,
+        code: "Af",
+        name: "Tropical rainforest",
+        color: 0x0000ff,
+    },
+    KoppenDef {
+        id: 2,
+        code: "Am",
+        name: "Tropical monsoon",
+        color: 0x0078ff,
+    },
+    KoppenDef {
+        id: 3,
+        code: "Aw",
+        name: "Tropical savanna",
+        color: 0x46aafa,
+    },
+    KoppenDef {
+        id: 4,
+        code: "BWh",
+        name: "Hot desert",
+        color: 0xff0000,
+    },
+    KoppenDef {
+        id: 5,
+        code: "BWk",
+        name: "Cold desert",
+        color: 0xff9696,
+    },
+    KoppenDef {
+        id: 6,
+        code: "BSh",
+        name: "Hot semi-arid",
+        color: 0xf5a500,
+    },
+    KoppenDef {
+        id: 7,
+        code: "BSk",
+        name: "Cold semi-arid",
+        color: 0xffdc64,
+    },
+    KoppenDef {
+        id: 8,
+        code: "Csa",
+        name: "Hot-summer Mediterranean",
+        color: 0xffff00,
+    },
+    KoppenDef {
+        id: 9,
+        code: "Csb",
+        name: "Warm-summer Mediterranean",
+        color: 0xc8c800,
+    },
+    KoppenDef {
+        id: 10,
+        code: "Csc",
+        name: "Cold-summer Mediterranean",
+        color: 0x969600,
+    },
+    KoppenDef {
+        id: 11,
+        code: "Cwa",
+        name: "Monsoon-influenced humid subtropical",
+        color: 0x96ff96,
+    },
+    KoppenDef {
+        id: 12,
+        code: "Cwb",
+        name: "Subtropical highland",
+        color: 0x64c864,
+    },
+    KoppenDef {
+        id: 13,
+        code: "Cwc",
+        name: "Cold subtropical highland",
+        color: 0x329632,
+    },
+    KoppenDef {
+        id: 14,
+        code: "Cfa",
+        name: "Humid subtropical",
+        color: 0xc8ff50,
+    },
+    KoppenDef {
+        id: 15,
+        code: "Cfb",
+        name: "Temperate oceanic",
+        color: 0x64ff50,
+    },
+    KoppenDef {
+        id: 16,
+        code: "Cfc",
+        name: "Subpolar oceanic",
+        color: 0x32c800,
+    },
+    KoppenDef {
+        id: 17,
+        code: "Dsa",
+        name: "Hot-summer Mediterranean continental",
+        color: 0xff00ff,
+    },
+    KoppenDef {
+        id: 18,
+        code: "Dsb",
+        name: "Warm-summer Mediterranean continental",
+        color: 0xc800c8,
+    },
+    KoppenDef {
+        id: 19,
+        code: "Dsc",
+        name: "Mediterranean subarctic",
+        color: 0x963296,
+    },
+    KoppenDef {
+        id: 20,
+        code: "Dsd",
+        name: "Mediterranean extremely cold subarctic",
+        color: 0x966496,
+    },
+    KoppenDef {
+        id: 21,
+        code: "Dwa",
+        name: "Monsoon-influenced hot-summer continental",
+        color: 0xabb1ff,
+    },
+    KoppenDef {
+        id: 22,
+        code: "Dwb",
+        name: "Monsoon-influenced warm-summer continental",
+        color: 0x5a77db,
+    },
+    KoppenDef {
+        id: 23,
+        code: "Dwc",
+        name: "Monsoon-influenced subarctic",
+        color: 0x4c51b5,
+    },
+    KoppenDef {
+        id: 24,
+        code: "Dwd",
+        name: "Monsoon-influenced extremely cold subarctic",
+        color: 0x320087,
+    },
+    KoppenDef {
+        id: 25,
+        code: "Dfa",
+        name: "Hot-summer humid continental",
+        color: 0x00ffff,
+    },
+    KoppenDef {
+        id: 26,
+        code: "Dfb",
+        name: "Warm-summer humid continental",
+        color: 0x37c8ff,
+    },
+    KoppenDef {
+        id: 27,
+        code: "Dfc",
+        name: "Subarctic",
+        color: 0x007d7d,
+    },
+    KoppenDef {
+        id: 28,
+        code: "Dfd",
+        name: "Extremely cold subarctic",
+        color: 0x00465f,
+    },
+    KoppenDef {
+        id: 29,
+        code: "ET",
+        name: "Tundra",
+        color: 0xb2b2b2,
+    },
+    KoppenDef {
+        id: 30,
+        code: "EF",
+        name: "Ice cap",
+        color: 0x666666,
+    },
+];
+
+/// Id of the class with Köppen code `code`.
+pub fn id_of(code: &str) -> Option<u8> {
+    KOPPEN.iter().find(|k| k.code == code).map(|k| k.id)
+}
+
+/// Classify one year: `temp` in °C and `prec` in mm per month, January first.
+/// Summer is whichever half of the year (April–September or October–March)
+/// is warmer, so either hemisphere works.
+pub fn classify(temp: &[f64; MONTHS], prec: &[f64; MONTHS]) -> u8 {
+    let code = |c: &str| id_of(c).expect("classify: unknown Köppen code");
+    let mat = temp.iter().sum::<f64>() / MONTHS as f64;
+    let map: f64 = prec.iter().sum();
+    let t_hot = temp.iter().copied().fold(f64::MIN, f64::max);
+    let t_cold = temp.iter().copied().fold(f64::MAX, f64::min);
+    let p_dry = prec.iter().copied().fold(f64::MAX, f64::min);
+    let warm_months = temp.iter().filter(|&&t| t > 10.0).count();
+
+    let april_to_september = |m: usize| (3..9).contains(&m);
+    let north_summer = (0..MONTHS)
+        .filter(|&m| april_to_september(m))
+        .map(|m| temp[m])
+        .sum::<f64>()
+        >= (0..MONTHS)
+            .filter(|&m| !april_to_september(m))
+            .map(|m| temp[m])
+            .sum::<f64>();
+    let summer = |m: usize| april_to_september(m) == north_summer;
+    let half = |in_summer: bool| {
+        let months = (0..MONTHS).filter(move |&m| summer(m) == in_summer);
+        let total: f64 = months.clone().map(|m| prec[m]).sum();
+        let dry = months.clone().map(|m| prec[m]).fold(f64::MAX, f64::min);
+        let wet = months.map(|m| prec[m]).fold(0.0, f64::max);
+        (total, dry, wet)
+    };
+    let (summer_total, summer_dry, summer_wet) = half(true);
+    let (winter_total, winter_dry, winter_wet) = half(false);
+
+    if t_hot < 10.0 {
+        return code(if t_hot > 0.0 { "ET" } else { "EF" });
+    }
+
+    // Dryness threshold (Peel et al.): more rain is needed where it falls in
+    // the summer, when more of it evaporates.
+    let threshold = 2.0 * mat
+        + if winter_total >= 0.7 * map {
+            0.0
+        } else if summer_total >= 0.7 * map {
+            28.0
+        } else {
+            14.0
+        };
+    if map < 10.0 * threshold {
+        let kind = if map < 5.0 * threshold { "BW" } else { "BS" };
+        let heat = if mat >= 18.0 { "h" } else { "k" };
+        return code(&format!("{kind}{heat}"));
+    }
+
+    if t_cold >= 18.0 {
+        return code(if p_dry >= 60.0 {
+            "Af"
+        } else if p_dry >= 100.0 - map / 25.0 {
+            "Am"
+        } else {
+            "Aw"
+        });
+    }
+
+    let group = if t_cold > 0.0 { "C" } else { "D" };
+    let dry_season = if summer_dry < 40.0 && summer_dry < winter_wet / 3.0 {
+        "s"
+    } else if winter_dry < summer_wet / 10.0 {
+        "w"
+    } else {
+        "f"
+    };
+    let summer_heat = if t_hot >= 22.0 {
+        "a"
+    } else if warm_months >= 4 {
+        "b"
+    } else if group == "D" && t_cold <= -38.0 {
+        "d"
+    } else {
+        "c"
+    };
+    code(&format!("{group}{dry_season}{summer_heat}"))
+}
+
+/// Köppen id per cell from the monthly climate.
+pub fn classify_seasons(h: &[u8], seasons: &Seasons) -> Vec<u8> {
+    (0..h.len())
+        .map(|c| {
+            if h[c] < SEA_LEVEL {
+                return OCEAN;
+            }
+            let temp = std::array::from_fn(|m| seasons.temp[m][c] as f64);
+            let prec =
+                std::array::from_fn(|m| seasons.prec[m][c] as f64 * MM_PER_PREC / MONTHS as f64);
+            classify(&temp, &prec)
+        })
+        .collect()
+}
+
+/// Köppen id per cell from the annual climate alone (see the module docs
+/// for what this can't tell apart).
+pub fn classify_annual(h: &[u8], temp: &[i8], prec: &[u8]) -> Vec<u8> {
+    (0..h.len())
+        .map(|c| {
+            if h[c] < SEA_LEVEL {
+                return OCEAN;
+            }
+            let mean = temp[c] as f64;
+            let swing = ((SWING_PIVOT - mean) * ANNUAL_SWING).clamp(0.0, MAX_SWING);
+            // Coldest in January, warmest in July.
+            let temp = std::array::from_fn(|m| {
+                let phase = std::f64::consts::TAU * m as f64 / MONTHS as f64;
+                mean - swing * phase.cos()
+            });
+            let prec = [prec[c] as f64 * MM_PER_PREC / MONTHS as f64; MONTHS];
+            classify(&temp, &prec)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::climate::{self, ClimateOpts};
+    use crate::mesh::{self, Mesh};
+
+    fn code_of(id: u8) -> &'static str {
+        KOPPEN[id as usize].code
+    }
+
+    /// Ids match table positions and codes are unique.
+    #[test]
+    fn table_is_indexed_by_id() {
+        for (i, k) in KOPPEN.iter().enumerate() {
+            assert_eq!(k.id as usize, i);
+            assert_eq!(id_of(k.code), Some(k.id));
+        }
+    }
+
+    /// Textbook stations (monthly normals, rounded).
+    #[test]
+    fn classifies_reference_climates() {
+        let stations: [(&str, [f64; 12], [f64; 12]); 7] = [
+            (
+                "Af", // Singapore
+                [
+                    26.5, 27.1, 27.5, 28.0, 28.3, 28.3, 27.9, 27.9, 27.6, 27.6, 27.0, 26.5,
+                ],
+                [
+                    234., 113., 170., 154., 171., 131., 158., 176., 169., 193., 256., 288.,
+                ],
+            ),
+            (
+                "BWh", // Cairo
+                [
+                    14., 15., 17.5, 21., 24.5, 27., 28., 28., 26., 23.5, 19., 15.5,
+                ],
+                [5., 4., 4., 1., 0., 0., 0., 0., 0., 1., 3., 6.],
+            ),
+            (
+                "Csa", // Rome
+                [7.5, 8.5, 11., 13.5, 18., 22., 25., 25., 21.5, 17., 12., 8.5],
+                [67., 73., 58., 81., 53., 34., 19., 37., 73., 113., 115., 82.],
+            ),
+            (
+                "Cfb", // London
+                [
+                    5.2, 5.3, 7.6, 9.9, 13.3, 16.5, 18.7, 18.5, 15.7, 12.0, 8.0, 5.5,
+                ],
+                [55., 41., 42., 44., 49., 45., 45., 50., 49., 69., 59., 55.],
+            ),
+            (
+                "Dfb", // Moscow
+                [
+                    -6.5, -6.7, -1.0, 6.7, 13.2, 17.0, 19.2, 17.0, 11.3, 5.6, -1.2, -5.2,
+                ],
+                [53., 44., 39., 37., 61., 78., 84., 78., 66., 70., 52., 51.],
+            ),
+            (
+                "Cfa", // Buenos Aires: southern hemisphere summer
+                [
+                    24.9, 23.8, 22.1, 18.4, 15.3, 12.0, 11.0, 12.8, 14.6, 17.9, 20.7, 23.1,
+                ],
+                [
+                    138., 127., 140., 119., 92., 58., 66., 67., 71., 122., 119., 117.,
+                ],
+            ),
+            (
+                "ET", // Svalbard
+                [
+                    -14., -15., -14., -10., -3., 2.5, 6., 5., 1., -5., -10., -13.,
+                ],
+                [15., 16., 18., 11., 10., 12., 24., 28., 24., 18., 16., 18.],
+            ),
+        ];
+        for (expected, temp, prec) in stations {
+            assert_eq!(code_of(classify(&temp, &prec)), expected);
+        }
+    }
+
+    /// Lowland with a sea along its western edge.
+    fn fixture() -> (Mesh, Vec<u8>) {
+        let mesh = mesh::build(4000, 5);
+        let h = mesh
+            .points
+            .iter()
+            .map(|&[x, _]| if x < 1500.0 { 10 } else { 25 })
+            .collect();
+        (mesh, h)
+    }
+
+    /// Water is ocean, every land cell is classified, and the classes run
+    /// from tropical at the equator to polar at the edges, for both the
+    /// seasonal classification and the annual fallback.
+    #[test]
+    fn world_classes_follow_latitude() {
+        let (mesh, h) = fixture();
+        let opts = ClimateOpts::default();
+        let seasons = climate::generate_seasons(&mesh, &h, &opts);
+        let (temp, prec) = climate::generate_climate(&mesh, &h, &opts);
+        let lat = |c: usize| 90.0 - mesh.points[c][1] / mesh.world_h * 180.0;
+        for koppen in [
+            classify_seasons(&h, &seasons),
+            classify_annual(&h, &temp, &prec),
+        ] {
+            for c in 0..h.len() {
+                assert_eq!(koppen[c] == OCEAN, h[c] < SEA_LEVEL, "cell {c}");
+            }
+            let group = |c: usize| code_of(koppen[c]).chars().next().unwrap();
+            let land = (0..h.len()).filter(|&c| h[c] >= SEA_LEVEL);
+            let polar = land.clone().filter(|&c| lat(c).abs() > 75.0);
+            let tropical = land.filter(|&c| lat(c).abs() < 5.0);
+            assert!(polar.clone().count() > 0 && tropical.clone().count() > 0);
+            assert!(polar.clone().all(|c| group(c) == 'E' || group(c) == 'D'));
+            assert!(tropical.clone().all(|c| group(c) == 'A' || group(c) == 'B'));
+        }
+    }
+}
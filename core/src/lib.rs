@@ -5,17 +5,21 @@
 //! later phases.
 
 use serde::Serialize;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use wasm_bindgen::prelude::*;
 
 mod biomes;
 mod climate;
+/// Ordered sea and lake shoreline polylines over `Vertices.p`.
+mod coast;
 /// Phase 3 Step 3.1: anthropological-layer entity data model + `Pack` holder.
 /// Types-only — no generators (Step 3.2/3.3 add `gen_states.rs` /
 /// `gen_cultures.rs` / `gen_religions.rs`), no rendering, no RNG. Exposed so
 /// the Phase 4 timeline projector and a future `pack` worker message kind can
 /// reference `entities::Pack` from `lib.rs`.
 pub(crate) mod entities;
+/// Hydraulic and thermal erosion over `cells.h` (post-process and brush).
+mod erosion;
 /// Phase 4 Step 4.2: event generation engine — deterministic timeline
 /// generation (succession, war, plague, golden age, schism, found/expand,
 /// migration) producing a chronologically-sorted `Timeline`.
@@ -27,10 +31,31 @@ mod gen_states;
 mod grid;
 mod heightmap;
 mod heightmap_edit;
+/// Undo / redo history (per-step cell diffs) for edits to the held grid.
+mod history;
+/// Replayable edit journals: world origin + `EditOp` list + checksum.
+mod journal;
+/// Köppen–Geiger climate classes from monthly (or annual) climate.
+mod koppen;
 pub mod mesh;
+/// Seeded fBm / ridged / domain-warped gradient noise at cell centres.
+mod noise;
+/// Merged per-label outlines (multipolygons + once-only borders) over `Vertices.p`.
+mod outline;
+/// Grayscale raster (PNG / PGM / raw) import onto a mesh as `cells.h`, and
+/// per-cell layer export as 16-bit / paletted PNG.
+mod raster;
+/// Regional refinement: regenerate a sub-area of a world at higher density.
+mod region;
 mod rivers;
+/// Cell-centre spatial index: exact nearest-cell, radius, rectangle and
+/// polygon queries over a mesh.
+pub mod spatial;
+/// Plate-tectonic heightmap generator, an alternative to the template floods.
+mod tectonics;
 /// Phase 4 Step 4.1: timeline data model (`Event`, `EventType`, `EventKind`,
-/// `EventPayload`) + `WorldAt(year)` projector (`project_world` / `project_delta`).
+/// `EventPayload`) + `WorldAt(year)` projector (`project_world` / `project_delta`),
+/// and the era climate drift projector (`project_climate`).
 /// See `agent/worldgen-implementation-plan.md` §Step 4.1 and design §3.3/§3.4.
 mod timeline;
 
@@ -52,10 +77,67 @@ pub fn init() {
 //
 // `generate_world` auto-stores its result; `store_grid_h` lets JS push a grid;
 // `release_grid_h` frees the slot. The `*_h` exports operate on the held grid.
+//
+// Heightmap edits on the held grid are recorded in `HELD_HISTORY` for
+// `undo_h` / `redo_h` / `history_jump_h`. Replacing the grid clears it.
+// `HELD_ORIGIN` remembers how a generated held grid was made, so its edits
+// can be saved as a journal (`edit_journal_h`). `HELD_DRAINAGE` keeps the
+// last drainage pass so `recompute_drainage_local_h` can update it in place.
+// An undo / redo / jump sets `HELD_STALE`: its climate, biomes and drainage
+// are recomputed (with `HELD_CLIMATE`, the last options used) by the next
+// export that reads them, unless `recompute_dependents_h` runs first.
+// `HELD_SPATIAL` is the spatial index over the held mesh, built by the first
+// pick or area query after the grid is held.
 // ---------------------------------------------------------------------------
 
 thread_local! {
     static HELD_GRID: RefCell<Option<grid::Grid>> = const { RefCell::new(None) };
+    static HELD_HISTORY: RefCell<history::History> = RefCell::new(history::History::default());
+    static HELD_ORIGIN: RefCell<Option<journal::WorldOrigin>> = const { RefCell::new(None) };
+    static HELD_DRAINAGE: RefCell<Option<rivers::DrainageResult>> = const { RefCell::new(None) };
+    static HELD_CLIMATE: RefCell<Option<climate::ClimateOpts>> = const { RefCell::new(None) };
+    static HELD_STALE: Cell<bool> = const { Cell::new(false) };
+    static HELD_SPATIAL: RefCell<Option<spatial::SpatialIndex>> = const { RefCell::new(None) };
+}
+
+/// Replace the held grid (or free it with `None`), dropping its edit history
+/// and timeline climate. `origin` is how the grid was generated, if it was.
+fn hold_grid(grid: Option<grid::Grid>, origin: Option<journal::WorldOrigin>) {
+    HELD_GRID.with(|g| *g.borrow_mut() = grid);
+    HELD_HISTORY.with(|h| *h.borrow_mut() = history::History::default());
+    HELD_CLIMATE.with(|c| *c.borrow_mut() = origin.as_ref().map(|o| o.climate.clone()));
+    HELD_ORIGIN.with(|o| *o.borrow_mut() = origin);
+    HELD_DRAINAGE.with(|d| *d.borrow_mut() = None);
+    HELD_STALE.with(|s| s.set(false));
+    HELD_SPATIAL.with(|s| *s.borrow_mut() = None);
+}
+
+/// Run `f` with the spatial index over `mesh`, the held grid's mesh,
+/// building it on first use (see `HELD_SPATIAL`).
+fn with_held_spatial<R>(mesh: &mesh::Mesh, f: impl FnOnce(&spatial::SpatialIndex) -> R) -> R {
+    HELD_SPATIAL.with(|s| {
+        let mut cache = s.borrow_mut();
+        f(cache.get_or_insert_with(|| spatial::SpatialIndex::build(mesh)))
+    })
+}
+
+/// Bring the held grid's climate, biomes and drainage up to date after a
+/// history step (see `step_held_history`); a no-op otherwise.
+fn refresh_held_dependents(grid: &mut grid::Grid) {
+    if HELD_STALE.with(|s| s.get()) {
+        let opts = HELD_CLIMATE
+            .with(|c| c.borrow().clone())
+            .unwrap_or_default();
+        recompute_held_dependents(grid, &opts);
+    }
+}
+
+/// Record the change from `before` to `cells` as one undo step. These are
+/// whole-map edits, so `HELD_DRAINAGE` is dropped too: the next
+/// `recompute_drainage_local_h` falls back to a full pass.
+fn record_held_edit(label: &str, before: &history::Snapshot, cells: &grid::CellData) {
+    HELD_HISTORY.with(|h| h.borrow_mut().record(label, before, cells));
+    HELD_DRAINAGE.with(|d| *d.borrow_mut() = None);
 }
 
 /// Store a Grid (deserialized from JS) into the Rust-side handle slot.
@@ -65,14 +147,14 @@ thread_local! {
 pub fn store_grid_h(grid_js: JsValue) {
     let grid: grid::Grid =
         serde_wasm_bindgen::from_value(grid_js).expect("store_grid_h: failed to deserialize Grid");
-    HELD_GRID.with(|g| *g.borrow_mut() = Some(grid));
+    hold_grid(Some(grid), None);
 }
 
 /// Release the held grid (drops it). Called when the worker is done with a
 /// world or before loading a new one.
 #[wasm_bindgen]
 pub fn release_grid_h() {
-    HELD_GRID.with(|g| *g.borrow_mut() = None);
+    hold_grid(None, None);
 }
 
 /// Check whether the Rust side is currently holding a grid.
@@ -96,6 +178,25 @@ pub fn generate_mesh(cell_count: u32, seed: u32) -> JsValue {
     mesh::generate_mesh(cell_count, seed)
 }
 
+/// `generate_mesh` for an explicit surface topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+/// Globe meshes additionally carry per-cell `lat_lon`.
+#[wasm_bindgen]
+pub fn generate_mesh_with_topology(cell_count: u32, seed: u32, topology_js: JsValue) -> JsValue {
+    mesh::generate_mesh_with_topology(cell_count, seed, topology_js)
+}
+
+/// Build a flat mesh over caller-supplied `[x, y]` seed points (no jitter —
+/// cell `i` sits exactly at `points[i]`). Throws `{ kind, ... }` describing
+/// the first problem if the points are rejected.
+#[wasm_bindgen]
+pub fn generate_mesh_from_points(
+    points_js: JsValue,
+    world_w: f64,
+    world_h: f64,
+) -> Result<JsValue, JsValue> {
+    mesh::generate_mesh_from_points(points_js, world_w, world_h)
+}
+
 /// Step 1.2: generate the heightmap `cells.h` (Uint8Array, `0..=100`,
 /// `< 20` == water) from a deserialized `Mesh`. Seeded blob/pit/range/trough
 /// floods ported from FMG's `heightmap-generator.ts`. Exposed as
@@ -107,6 +208,59 @@ pub fn generate_heightmap(mesh_js: JsValue, seed: u32) -> js_sys::Uint8Array {
     heightmap::generate_heightmap(mesh, seed)
 }
 
+/// The named heightmap template library (FMG's templates) as an array of
+/// `{ id, name, template }`. Any `id` or `name` can be passed wherever a
+/// template is accepted.
+#[wasm_bindgen]
+pub fn heightmap_templates() -> JsValue {
+    serde_wasm_bindgen::to_value(heightmap::TEMPLATES)
+        .expect("heightmap_templates: serde to JsValue")
+}
+
+/// Check a heightmap template (library id/name or template text) without
+/// running it. Returns the step count, or throws a `{ kind, line, ... }`
+/// `TemplateError` for the first malformed line.
+#[wasm_bindgen]
+pub fn validate_heightmap_template(template: &str) -> Result<u32, JsValue> {
+    heightmap::validate_template(template)
+        .map(|steps| steps as u32)
+        .map_err(|e| {
+            serde_wasm_bindgen::to_value(&e)
+                .expect("validate_heightmap_template: TemplateError serde to JsValue")
+        })
+}
+
+/// `generate_heightmap` driven by a template (library id/name or template
+/// text) instead of the built-in default. Throws a `TemplateError` if the
+/// template is malformed.
+#[wasm_bindgen]
+pub fn generate_heightmap_from_template(
+    mesh_js: JsValue,
+    seed: u32,
+    template: &str,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let mesh: mesh::Mesh = serde_wasm_bindgen::from_value(mesh_js)
+        .expect("generate_heightmap_from_template: failed to deserialize Mesh");
+    match heightmap::generate_from_template(&mesh, seed as u64, template) {
+        Ok(h) => Ok(js_sys::Uint8Array::from(h.as_slice())),
+        Err(e) => Err(serde_wasm_bindgen::to_value(&e)
+            .expect("generate_heightmap_from_template: TemplateError serde to JsValue")),
+    }
+}
+
+/// `generate_heightmap` by plate tectonics instead of template floods.
+/// Returns `{ h, plate, plates }`: the heightmap, each cell's plate index and
+/// the plates (`{ seed_cell, velocity, continental, area }`). `opts` is a
+/// `TectonicOpts`; absent fields take their defaults.
+#[wasm_bindgen]
+pub fn generate_tectonics(mesh_js: JsValue, seed: u32, opts_js: JsValue) -> JsValue {
+    let mesh: mesh::Mesh = serde_wasm_bindgen::from_value(mesh_js)
+        .expect("generate_tectonics: failed to deserialize Mesh");
+    let opts: tectonics::TectonicOpts = serde_wasm_bindgen::from_value(opts_js).unwrap_or_default();
+    serde_wasm_bindgen::to_value(&tectonics::generate(&mesh, seed as u64, &opts))
+        .expect("generate_tectonics: serde to JsValue")
+}
+
 /// Step 1.2 (world-assembly form): build a `Grid` from a deserialized `Mesh`
 /// and store the generated heightmap into `grid.cells.h`. Returns a `Grid`
 /// with only `cells.h` populated (the other `CellData` fields are zeroed).
@@ -202,6 +356,9 @@ pub fn edit_heightmap(grid_js: JsValue, ops_js: JsValue) -> JsValue {
 /// its `heldGrid.cells.h` from the returned array (or just use the array
 /// directly for the texture upload).
 ///
+/// Each op is recorded as its own undo step (see `undo_h`), snapshotting
+/// only the cells its `heightmap_edit::footprint` covers when it has one.
+///
 /// Exposed as `edit_heightmap_h(ops)` to JS.
 #[wasm_bindgen]
 pub fn edit_heightmap_h(ops_js: JsValue) -> js_sys::Uint8Array {
@@ -210,7 +367,17 @@ pub fn edit_heightmap_h(ops_js: JsValue) -> js_sys::Uint8Array {
     HELD_GRID.with(|g| {
         let mut guard = g.borrow_mut();
         let grid = guard.as_mut().expect("edit_heightmap_h: no held grid");
-        heightmap_edit::edit_heightmap(grid, &ops);
+        for op in &ops {
+            let footprint = with_held_spatial(&grid.mesh, |index| {
+                heightmap_edit::footprint(&grid.mesh, index, op)
+            });
+            let before = match footprint {
+                Some(cells) => history::Snapshot::of_cells(&grid.cells, cells),
+                None => history::Snapshot::of(&grid.cells),
+            };
+            heightmap_edit::edit_heightmap(grid, std::slice::from_ref(op));
+            HELD_HISTORY.with(|h| h.borrow_mut().record_op(op, &before, &grid.cells));
+        }
         // Zero-copy view into the grid's h vector in WASM linear memory.
         // SAFETY: the Uint8Array view is valid as long as the backing memory
         // isn't freed or reallocated. The grid is held alive for the JS
@@ -219,9 +386,62 @@ pub fn edit_heightmap_h(ops_js: JsValue) -> js_sys::Uint8Array {
     })
 }
 
-/// Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Uses the
-/// `cells.spacing` spatial grid + neighbor refinement. Returns the cell id
-/// as a `u32`, or `-1` if the grid has no cells. O(1)-ish, deterministic.
+/// Erode a heightmap (`h`, any generator's output) over `mesh`: flux-based
+/// hydraulic erosion with deposition plus talus thermal erosion, keeping the
+/// coastline. `opts` is an `ErosionOpts`; absent fields take their defaults.
+/// Returns the eroded copy of `h`.
+#[wasm_bindgen]
+pub fn erode_heightmap(mesh_js: JsValue, h: &[u8], opts_js: JsValue) -> js_sys::Uint8Array {
+    let mesh: mesh::Mesh = serde_wasm_bindgen::from_value(mesh_js)
+        .expect("erode_heightmap: failed to deserialize Mesh");
+    let opts: erosion::ErosionOpts = serde_wasm_bindgen::from_value(opts_js).unwrap_or_default();
+    let mut h = h.to_vec();
+    erosion::erode(&mesh, &mut h, &opts);
+    js_sys::Uint8Array::from(h.as_slice())
+}
+
+/// `erode_heightmap` on the held grid's `cells.h`, in place, leaving locked
+/// cells alone. The coastline is unchanged, so entity indices are kept;
+/// follow with `recompute_dependents_h` to refresh climate, biomes and
+/// drainage.
+///
+/// Exposed as `erode_heightmap_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn erode_heightmap_h(opts_js: JsValue) -> js_sys::Uint8Array {
+    let opts: erosion::ErosionOpts = serde_wasm_bindgen::from_value(opts_js).unwrap_or_default();
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("erode_heightmap_h: no held grid");
+        let before = history::Snapshot::of(&grid.cells);
+        let weight: Vec<f64> = (0..grid.cells.h.len())
+            .map(|c| if grid.cells.is_locked(c) { 0.0 } else { 1.0 })
+            .collect();
+        erosion::erode_weighted(&grid.mesh, &mut grid.cells.h, &opts, &weight);
+        record_held_edit("Erode", &before, &grid.cells);
+        js_sys::Uint8Array::from(grid.cells.h.as_slice())
+    })
+}
+
+/// The held grid's lock mask, `1` per locked cell, for an overlay. Cells are
+/// locked and unlocked with `Lock` / `Unlock` ops through `edit_heightmap_h`,
+/// so locking is undoable and journaled like any edit.
+///
+/// Exposed as `locked_h()` to JS.
+#[wasm_bindgen]
+pub fn locked_h() -> js_sys::Uint8Array {
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("locked_h: no held grid");
+        let mask: Vec<u8> = (0..grid.cells.h.len())
+            .map(|c| grid.cells.is_locked(c) as u8)
+            .collect();
+        js_sys::Uint8Array::from(mask.as_slice())
+    })
+}
+
+/// Step 2.5.4: pick the nearest cell to world-space `(x, y)`. Exact search
+/// over the mesh spatial index. Returns the cell id as a `u32`, or `-1` if the
+/// grid has no cells. Deterministic (ties go to the lower cell id).
 ///
 /// Exposed as `pick_cell(grid, x, y)` to JS.
 #[wasm_bindgen]
@@ -242,18 +462,115 @@ pub fn pick_cell_h(x: f64, y: f64) -> i32 {
     HELD_GRID.with(|g| {
         let guard = g.borrow();
         let grid = guard.as_ref().expect("pick_cell_h: no held grid");
-        match heightmap::pick_cell(&grid.mesh, x, y) {
+        match with_held_spatial(&grid.mesh, |index| index.nearest(&grid.mesh, x, y)) {
             Some(id) => id as i32,
             None => -1,
         }
     })
 }
 
+/// Cells of the held grid whose centre is within `radius` of `(x, y)`, as an
+/// ascending `Uint32Array`. Seam-aware on wrapping topologies.
+///
+/// Exposed as `cells_in_radius_h(x, y, radius)` to JS.
+#[wasm_bindgen]
+pub fn cells_in_radius_h(x: f64, y: f64, radius: f64) -> JsValue {
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("cells_in_radius_h: no held grid");
+        let cells = with_held_spatial(&grid.mesh, |index| {
+            index.within_radius(&grid.mesh, x, y, radius)
+        });
+        js_sys::Uint32Array::from(cells.as_slice()).into()
+    })
+}
+
+/// Cells of the held grid whose centre lies in the rectangle with corners
+/// `(x0, y0)` / `(x1, y1)`, as an ascending `Uint32Array`.
+///
+/// Exposed as `cells_in_rect_h(x0, y0, x1, y1)` to JS.
+#[wasm_bindgen]
+pub fn cells_in_rect_h(x0: f64, y0: f64, x1: f64, y1: f64) -> JsValue {
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("cells_in_rect_h: no held grid");
+        let cells = with_held_spatial(&grid.mesh, |index| {
+            index.in_rect(&grid.mesh, x0, y0, x1, y1)
+        });
+        js_sys::Uint32Array::from(cells.as_slice()).into()
+    })
+}
+
+/// Cells of the held grid whose centre lies inside `polygon` (an array of
+/// `[x, y]` vertices, implicitly closed, even-odd rule), as an ascending
+/// `Uint32Array`.
+///
+/// Exposed as `cells_in_polygon_h(polygon)` to JS.
+#[wasm_bindgen]
+pub fn cells_in_polygon_h(polygon_js: JsValue) -> JsValue {
+    let polygon: Vec<[f64; 2]> = serde_wasm_bindgen::from_value(polygon_js)
+        .expect("cells_in_polygon_h: failed to deserialize polygon");
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("cells_in_polygon_h: no held grid");
+        let cells = with_held_spatial(&grid.mesh, |index| index.in_polygon(&grid.mesh, &polygon));
+        js_sys::Uint32Array::from(cells.as_slice()).into()
+    })
+}
+
+/// Merge a mesh's cells by a per-cell label array (`Int32Array`, `-1` =
+/// unassigned) into one multipolygon per label plus the once-only borders
+/// between labels. Returns an `Outlines` (`{ labels, borders }`); rings are
+/// vertex ids into `mesh.vertices.p`.
+///
+/// Exposed as `cell_outlines(mesh, labels)` to JS.
+#[wasm_bindgen]
+pub fn cell_outlines(mesh_js: JsValue, labels_js: JsValue) -> JsValue {
+    let mesh: mesh::Mesh =
+        serde_wasm_bindgen::from_value(mesh_js).expect("cell_outlines: failed to deserialize Mesh");
+    let labels: Vec<i32> = serde_wasm_bindgen::from_value(labels_js)
+        .expect("cell_outlines: failed to deserialize labels");
+    let out = outline::outlines(&mesh, &labels);
+    serde_wasm_bindgen::to_value(&out).expect("cell_outlines: Outlines serde to JsValue")
+}
+
+/// `cell_outlines` over the held grid's mesh. No Grid serde.
+///
+/// Exposed as `cell_outlines_h(labels)` to JS.
+#[wasm_bindgen]
+pub fn cell_outlines_h(labels_js: JsValue) -> JsValue {
+    let labels: Vec<i32> = serde_wasm_bindgen::from_value(labels_js)
+        .expect("cell_outlines_h: failed to deserialize labels");
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("cell_outlines_h: no held grid");
+        let out = outline::outlines(&grid.mesh, &labels);
+        serde_wasm_bindgen::to_value(&out).expect("cell_outlines_h: Outlines serde to JsValue")
+    })
+}
+
+/// Replace `cells.h` wholesale (reset, template, tectonics, image import)
+/// and unassign every entity index, except on locked cells, which keep both.
+fn replace_heightmap(cells: &mut grid::CellData, h: Vec<u8>) {
+    for (c, v) in h.into_iter().enumerate() {
+        if cells.is_locked(c) {
+            continue;
+        }
+        cells.h[c] = v;
+        cells.state[c] = -1;
+        cells.province[c] = -1;
+        cells.culture[c] = -1;
+        cells.religion[c] = -1;
+        cells.burg[c] = 0;
+    }
+}
+
 /// Step 2.5.4: reset `grid.cells.h` back to the original seeded heightmap.
 /// Regenerates `h` from `grid.seed` + `grid.mesh` using the same
 /// `heightmap::generate` used by `generate_world`. Also reinitializes the
 /// entity index arrays (`state`/`province`/`culture`/`religion`/`burg`) to
 /// their "unassigned" sentinels, since Reset means "discard all edits".
+/// Locked cells (`CellData::locked`) keep both their height and entities.
 /// Returns the updated `Grid` as `JsValue`.
 ///
 /// Exposed as `reset_heightmap(grid)` to JS.
@@ -261,14 +578,7 @@ pub fn pick_cell_h(x: f64, y: f64) -> i32 {
 pub fn reset_heightmap(grid_js: JsValue) -> JsValue {
     let mut grid: grid::Grid = serde_wasm_bindgen::from_value(grid_js)
         .expect("reset_heightmap: failed to deserialize Grid");
-    grid.cells.h = heightmap::generate(&grid.mesh, grid.seed);
-    // Reset entity indices to unassigned.
-    let n = grid.cells.h.len();
-    grid.cells.state = vec![-1i32; n];
-    grid.cells.province = vec![-1i32; n];
-    grid.cells.culture = vec![-1i32; n];
-    grid.cells.religion = vec![-1i32; n];
-    grid.cells.burg = vec![0i16; n];
+    replace_heightmap(&mut grid.cells, heightmap::generate(&grid.mesh, grid.seed));
     serde_wasm_bindgen::to_value(&grid).expect("reset_heightmap: grid serde to JsValue")
 }
 
@@ -281,17 +591,132 @@ pub fn reset_heightmap_h() -> js_sys::Uint8Array {
     HELD_GRID.with(|g| {
         let mut guard = g.borrow_mut();
         let grid = guard.as_mut().expect("reset_heightmap_h: no held grid");
-        grid.cells.h = heightmap::generate(&grid.mesh, grid.seed);
-        let n = grid.cells.h.len();
-        grid.cells.state = vec![-1i32; n];
-        grid.cells.province = vec![-1i32; n];
-        grid.cells.culture = vec![-1i32; n];
-        grid.cells.religion = vec![-1i32; n];
-        grid.cells.burg = vec![0i16; n];
+        let before = history::Snapshot::of(&grid.cells);
+        replace_heightmap(&mut grid.cells, heightmap::generate(&grid.mesh, grid.seed));
+        record_held_edit("Reset", &before, &grid.cells);
         js_sys::Uint8Array::from(grid.cells.h.as_slice())
     })
 }
 
+/// Move the held grid through its edit history with `step`, then return the
+/// resulting `cells.h` (or `None` if `step` couldn't move, e.g. nothing to
+/// undo). Entity indices are restored along with `h`; climate, biomes and
+/// drainage are marked stale, so the next export reading them recomputes
+/// them first (`refresh_held_dependents`), and `HELD_DRAINAGE` no longer
+/// matches `h`, so it is dropped.
+fn step_held_history(
+    step: impl FnOnce(&mut history::History, &mut grid::CellData) -> bool,
+    what: &str,
+) -> Option<js_sys::Uint8Array> {
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard
+            .as_mut()
+            .unwrap_or_else(|| panic!("{what}: no held grid"));
+        let moved = HELD_HISTORY.with(|h| step(&mut h.borrow_mut(), &mut grid.cells));
+        if moved {
+            HELD_STALE.with(|s| s.set(true));
+            HELD_DRAINAGE.with(|d| *d.borrow_mut() = None);
+        }
+        moved.then(|| js_sys::Uint8Array::from(grid.cells.h.as_slice()))
+    })
+}
+
+/// Undo the latest heightmap edit on the held grid (brush op, erosion,
+/// reset, template, tectonics or image import). Returns the restored
+/// `cells.h`, or `undefined` if there is nothing to undo. Entity indices
+/// come back too; climate, biomes and drainage are recomputed on their next
+/// read, or call `recompute_dependents_h` to get them all at once.
+///
+/// Exposed as `undo_h()` to JS.
+#[wasm_bindgen]
+pub fn undo_h() -> Option<js_sys::Uint8Array> {
+    step_held_history(|h, cells| h.undo(cells), "undo_h")
+}
+
+/// Re-apply the latest undone edit. Returns the new `cells.h`, or
+/// `undefined` if there is nothing to redo. Dependent layers follow as for
+/// `undo_h`.
+///
+/// Exposed as `redo_h()` to JS.
+#[wasm_bindgen]
+pub fn redo_h() -> Option<js_sys::Uint8Array> {
+    step_held_history(|h, cells| h.redo(cells), "redo_h")
+}
+
+/// Undo or redo until `step` edits are applied (`0` = before the oldest
+/// kept edit, `history_h().steps.length` = latest). Returns the new
+/// `cells.h`, or `undefined` if `step` is out of range.
+///
+/// Exposed as `history_jump_h(step)` to JS.
+#[wasm_bindgen]
+pub fn history_jump_h(step: u32) -> Option<js_sys::Uint8Array> {
+    step_held_history(|h, cells| h.jump(step as usize, cells), "history_jump_h")
+}
+
+/// The held grid's edit history as `{ cursor, steps: [{ label, cells }] }`,
+/// for a history panel.
+///
+/// Exposed as `history_h()` to JS.
+#[wasm_bindgen]
+pub fn history_h() -> JsValue {
+    HELD_HISTORY.with(|h| {
+        serde_wasm_bindgen::to_value(&h.borrow().info()).expect("history_h: serde to JsValue")
+    })
+}
+
+/// Drop the held grid's edit history (the grid itself is unchanged). The
+/// applied edits stay in its journal.
+///
+/// Exposed as `clear_history_h()` to JS.
+#[wasm_bindgen]
+pub fn clear_history_h() {
+    HELD_HISTORY.with(|h| h.borrow_mut().clear());
+}
+
+/// The held grid as a `journal::EditJournal`: how its base world was
+/// generated, the `EditOp`s applied since (up to the current undo step) and a
+/// checksum of its `cells.h`. `null` if it can't be journaled: the grid was
+/// stored or imported rather than generated, or an applied step wasn't an
+/// `EditOp` (erosion, reset, template, tectonics, image import).
+///
+/// Exposed as `edit_journal_h()` to JS.
+#[wasm_bindgen]
+pub fn edit_journal_h() -> JsValue {
+    let Some(origin) = HELD_ORIGIN.with(|o| o.borrow().clone()) else {
+        return JsValue::NULL;
+    };
+    let Some(ops) = HELD_HISTORY.with(|h| h.borrow().applied_ops()) else {
+        return JsValue::NULL;
+    };
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("edit_journal_h: no held grid");
+        let journal = journal::record(&origin, ops, grid);
+        serde_wasm_bindgen::to_value(&journal).expect("edit_journal_h: serde to JsValue")
+    })
+}
+
+/// Rebuild the world of an `EditJournal` (from `edit_journal_h`) and return
+/// it like `generate_world`, also storing it as the held grid; its edits
+/// carry over into the new grid's journal. Throws a `JournalError`
+/// (`{ kind, ... }`) on a generator version mismatch, a bad template or a
+/// checksum mismatch.
+#[wasm_bindgen]
+pub fn replay_edit_journal(journal_js: JsValue) -> Result<JsValue, JsValue> {
+    let journal: journal::EditJournal = serde_wasm_bindgen::from_value(journal_js)
+        .expect("replay_edit_journal: failed to deserialize EditJournal");
+    let grid = journal::replay(&journal).map_err(|e| {
+        serde_wasm_bindgen::to_value(&e)
+            .expect("replay_edit_journal: JournalError serde to JsValue")
+    })?;
+    let js =
+        serde_wasm_bindgen::to_value(&grid).expect("replay_edit_journal: grid serde to JsValue");
+    hold_grid(Some(grid), Some(journal.origin));
+    HELD_HISTORY.with(|h| *h.borrow_mut() = history::History::from_ops(journal.ops));
+    Ok(js)
+}
+
 /// Step 2.5.2: Tier-1 local recompute of temp + biome for an affected cell
 /// set. Runs `recompute_temp_local` then `recompute_biome_local` in place on
 /// `grid.cells`, and returns `{ temp: Int8Array, biome: Uint8Array }` holding
@@ -315,7 +740,7 @@ pub fn recompute_temp_biome_local(
         serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
 
     // Temp first (biome depends on temp).
-    let coords = climate::calculate_map_coordinates(&opts);
+    let coords = climate::calculate_mesh_coordinates(&grid.mesh, &opts);
     climate::recompute_temp_local_with_coords(&mut grid, &cell_ids, &opts, &coords);
     // Biome next (reads updated temp).
     biomes::recompute_biome_local(&mut grid, &cell_ids);
@@ -354,9 +779,10 @@ pub fn recompute_temp_biome_local_h(cell_ids_js: JsValue, opts_js: JsValue) -> J
         let grid = guard
             .as_mut()
             .expect("recompute_temp_biome_local_h: no held grid");
+        refresh_held_dependents(grid);
 
         // Temp first (biome depends on temp).
-        let coords = climate::calculate_map_coordinates(&opts);
+        let coords = climate::calculate_mesh_coordinates(&grid.mesh, &opts);
         climate::recompute_temp_local_with_coords(grid, &cell_ids, &opts, &coords);
         // Biome next (reads updated temp).
         biomes::recompute_biome_local(grid, &cell_ids);
@@ -422,7 +848,7 @@ pub fn recompute_dependents_h(opts_js: JsValue) -> JsValue {
         let grid = guard
             .as_mut()
             .expect("recompute_dependents_h: no held grid");
-        let result = recompute_dependents_inner(grid, &opts);
+        let result = recompute_held_dependents(grid, &opts);
         serde_wasm_bindgen::to_value(&result).expect("recompute_dependents_h: serde to JsValue")
     })
 }
@@ -456,7 +882,7 @@ pub fn recompute_dependents_h2(opts_js: JsValue) -> JsValue {
         let grid = guard
             .as_mut()
             .expect("recompute_dependents_h2: no held grid");
-        let result = recompute_dependents_inner(grid, &opts);
+        let result = recompute_held_dependents(grid, &opts);
 
         let obj = js_sys::Object::new();
 
@@ -606,16 +1032,18 @@ struct DrainageGeometry {
 #[wasm_bindgen]
 pub fn get_drainage_geometry_h() -> JsValue {
     HELD_GRID.with(|g| {
-        let guard = g.borrow();
+        let mut guard = g.borrow_mut();
         let grid = guard
-            .as_ref()
+            .as_mut()
             .expect("get_drainage_geometry_h: no held grid");
+        refresh_held_dependents(grid);
         let drainage = rivers::compute_drainage(
             &grid.mesh,
             &grid.cells.h,
             &grid.cells.temp,
             &grid.cells.prec,
         );
+        HELD_DRAINAGE.with(|d| *d.borrow_mut() = Some(drainage.clone()));
         let mut lakes = drainage.lakes;
         for (i, lake) in lakes.iter_mut().enumerate() {
             lake.id = (i + 1) as u32;
@@ -628,6 +1056,240 @@ pub fn get_drainage_geometry_h() -> JsValue {
     })
 }
 
+/// Mid-stroke drainage update on the held grid: the river counterpart of
+/// `recompute_temp_biome_local_h`. `cellIds` are the cells edited since the
+/// last drainage pass; `rivers::update_drainage` recomputes only the
+/// catchments they can affect, starting from the drainage the last
+/// `recompute_dependents_h`/`_h2` or `get_drainage_geometry_h` left in
+/// `HELD_DRAINAGE` (or a full pass if there is none). Writes `fl`/`r`/`conf`
+/// back into the held grid.
+///
+/// Returns `{ cells: Uint32Array, fl: Uint16Array, r: Uint16Array,
+/// conf: Uint16Array, rivers: RiverGeo[], lakes: LakeGeo[] }`: the
+/// recomputed cells with their new values (in `cells` order), and the full
+/// river and lake lists. River ids outside the recomputed cells don't change.
+///
+/// Exposed as `recompute_drainage_local_h(cellIds)` to JS.
+#[wasm_bindgen]
+pub fn recompute_drainage_local_h(cell_ids_js: JsValue) -> JsValue {
+    let cell_ids: Vec<u32> = serde_wasm_bindgen::from_value(cell_ids_js)
+        .expect("recompute_drainage_local_h: failed to deserialize cellIds");
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard
+            .as_mut()
+            .expect("recompute_drainage_local_h: no held grid");
+        refresh_held_dependents(grid);
+        let (mesh, cells) = (&grid.mesh, &mut grid.cells);
+        let (drainage, region) = HELD_DRAINAGE.with(|d| {
+            let mut held = d.borrow_mut();
+            let prev = held.take().unwrap_or_else(|| {
+                rivers::compute_drainage(mesh, &cells.h, &cells.temp, &cells.prec)
+            });
+            let (drainage, region) =
+                rivers::update_drainage(mesh, &cells.h, &cells.temp, &cells.prec, &prev, &cell_ids);
+            *held = Some(drainage.clone());
+            (drainage, region)
+        });
+        cells.fl = drainage.fl;
+        cells.r = drainage.r;
+        cells.conf = drainage.conf;
+
+        let mut lakes = drainage.lakes;
+        for (i, lake) in lakes.iter_mut().enumerate() {
+            lake.id = (i + 1) as u32;
+        }
+        let geo = serde_wasm_bindgen::to_value(&DrainageGeometry {
+            rivers: drainage.rivers,
+            lakes,
+        })
+        .expect("recompute_drainage_local_h: serde to JsValue");
+
+        let obj = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).expect("set");
+        };
+        set("cells", &js_sys::Uint32Array::from(region.as_slice()));
+        for (key, values) in [("fl", &cells.fl), ("r", &cells.r), ("conf", &cells.conf)] {
+            let values: Vec<u16> = region.iter().map(|&c| values[c as usize]).collect();
+            set(key, &js_sys::Uint16Array::from(values.as_slice()));
+        }
+        for key in ["rivers", "lakes"] {
+            set(key, &js_sys::Reflect::get(&geo, &key.into()).expect("get"));
+        }
+        obj.into()
+    })
+}
+
+/// Ordered sea and lake shorelines of the held grid, each Chaikin-smoothed
+/// `smoothing` times (`0` = raw Voronoi edges; capped at
+/// `coast::MAX_SMOOTHING`). Traced on request only, from the lakes of the
+/// drainage kept in `HELD_DRAINAGE` (a full `rivers::compute_drainage` when
+/// there is none) numbered like `get_drainage_geometry_h`, so
+/// `Shoreline.feature` of a lake shore matches `LakeGeo.id` there. Returns
+/// `Shoreline[]`.
+///
+/// Exposed as `shorelines_h(smoothing)` to JS.
+#[wasm_bindgen]
+pub fn shorelines_h(smoothing: u32) -> JsValue {
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("shorelines_h: no held grid");
+        refresh_held_dependents(grid);
+        let held = HELD_DRAINAGE.with(|d| d.borrow().as_ref().map(|d| d.lakes.clone()));
+        let mut lakes = held.unwrap_or_else(|| {
+            rivers::compute_drainage(
+                &grid.mesh,
+                &grid.cells.h,
+                &grid.cells.temp,
+                &grid.cells.prec,
+            )
+            .lakes
+        });
+        for (i, lake) in lakes.iter_mut().enumerate() {
+            lake.id = (i + 1) as u32;
+        }
+        let out = coast::shorelines(&grid.mesh, &grid.cells.h, &lakes, smoothing);
+        serde_wasm_bindgen::to_value(&out).expect("shorelines_h: serde to JsValue")
+    })
+}
+
+/// Monthly climate of the held grid (`climate::generate_seasons`; set
+/// `opts.axial_tilt` for other worlds). Returns `{ temp: Int8Array[12],
+/// prec: Uint8Array[12], frost: Uint8Array, onset: Uint8Array }`: per-month
+/// cell layers, months below freezing per cell (snowbound passes), and the
+/// month the wet season arrives (`255` where there is none).
+///
+/// Exposed as `seasons_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn seasons_h(opts_js: JsValue) -> JsValue {
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("seasons_h: no held grid");
+        let seasons = climate::generate_seasons(&grid.mesh, &grid.cells.h, &opts);
+
+        let obj = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).expect("set");
+        };
+        let temp = js_sys::Array::new();
+        for month in &seasons.temp {
+            temp.push(&js_sys::Int8Array::from(month.as_slice()));
+        }
+        let prec = js_sys::Array::new();
+        for month in &seasons.prec {
+            prec.push(&js_sys::Uint8Array::from(month.as_slice()));
+        }
+        set("temp", &temp);
+        set("prec", &prec);
+        let (frost, onset) = (seasons.frost_months(), seasons.wet_season_onset());
+        set("frost", &js_sys::Uint8Array::from(frost.as_slice()));
+        set("onset", &js_sys::Uint8Array::from(onset.as_slice()));
+        obj.into()
+    })
+}
+
+/// The wind passes behind the held grid's precipitation
+/// (`climate::generate_precipitation_traced`, over the held `cells.h` /
+/// `cells.temp`). Returns `{ wind: Float32Array, humidity: Float32Array,
+/// orographic: Float32Array }`: the prevailing wind per cell as interleaved
+/// `x, y` pairs (`y` south, length = strength), the humidity left in the air
+/// leaving the cell, and the orographic share of its rain.
+///
+/// Exposed as `precipitation_trace_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn precipitation_trace_h(opts_js: JsValue) -> JsValue {
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("precipitation_trace_h: no held grid");
+        refresh_held_dependents(grid);
+        let (mesh, cells) = (&grid.mesh, &grid.cells);
+        let coords = climate::calculate_mesh_coordinates(mesh, &opts);
+        let (_, trace) =
+            climate::generate_precipitation_traced(mesh, &cells.h, &cells.temp, &opts, &coords);
+
+        let obj = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).expect("set");
+        };
+        let wind: Vec<f32> = trace.wind.iter().flatten().copied().collect();
+        for (key, values) in [
+            ("wind", &wind),
+            ("humidity", &trace.humidity),
+            ("orographic", &trace.orographic),
+        ] {
+            set(key, &js_sys::Float32Array::from(values.as_slice()));
+        }
+        obj.into()
+    })
+}
+
+/// Köppen–Geiger class per cell of the held grid (ids into
+/// `koppen_table()`, `0` = ocean). With `opts.seasonal` the classes come from
+/// the monthly climate (`climate::generate_seasons`); otherwise from the held
+/// `cells.temp` / `cells.prec` alone (`koppen::classify_annual`), which can't
+/// tell dry seasons apart. Returns a `Uint8Array`.
+///
+/// Exposed as `koppen_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn koppen_h(opts_js: JsValue) -> JsValue {
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("koppen_h: no held grid");
+        refresh_held_dependents(grid);
+        let cells = &grid.cells;
+        let classes = if opts.seasonal {
+            let seasons = climate::generate_seasons(&grid.mesh, &cells.h, &opts);
+            koppen::classify_seasons(&cells.h, &seasons)
+        } else {
+            koppen::classify_annual(&cells.h, &cells.temp, &cells.prec)
+        };
+        js_sys::Uint8Array::from(classes.as_slice()).into()
+    })
+}
+
+/// The Köppen–Geiger classes: `{ id, code, name, color }[]` in id order
+/// (`color` packed `0xRRGGBB`).
+///
+/// Exposed as `koppen_table()` to JS.
+#[wasm_bindgen]
+pub fn koppen_table() -> JsValue {
+    serde_wasm_bindgen::to_value(&koppen::KOPPEN).expect("koppen_table: serde to JsValue")
+}
+
+/// Surface ocean currents of the held grid (`climate::ocean_currents`).
+/// Returns `{ flow: Float32Array, anomaly: Float32Array }`: the current per
+/// cell as interleaved `x, y` pairs (`y` south, zero on land), and the sea
+/// surface temperature anomaly (°C) with the share carried onto the coasts.
+///
+/// Exposed as `ocean_currents_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn ocean_currents_h(opts_js: JsValue) -> JsValue {
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    HELD_GRID.with(|g| {
+        let guard = g.borrow();
+        let grid = guard.as_ref().expect("ocean_currents_h: no held grid");
+        let currents = climate::ocean_currents(&grid.mesh, &grid.cells.h, &opts);
+
+        let obj = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).expect("set");
+        };
+        let flow: Vec<f32> = currents.flow.iter().flatten().copied().collect();
+        let anomaly = currents.anomaly;
+        set("flow", &js_sys::Float32Array::from(flow.as_slice()));
+        set("anomaly", &js_sys::Float32Array::from(anomaly.as_slice()));
+        obj.into()
+    })
+}
+
 /// Serde helper: only the small (non-numeric-array) fields of `DependentResult`,
 /// used by `recompute_dependents_h2` to serde-encode the tiny collections while
 /// the large numeric arrays go through zero-copy TypedArrays.
@@ -665,6 +1327,34 @@ pub fn recompute_dependents_inner(
         &grid.cells.temp,
         &grid.cells.prec,
     );
+    dependents_from_drainage(grid, opts, drainage)
+}
+
+/// `recompute_dependents_inner` on the held grid, keeping its drainage in
+/// `HELD_DRAINAGE` for `recompute_drainage_local_h` and its options in
+/// `HELD_CLIMATE` for `refresh_held_dependents`.
+fn recompute_held_dependents(
+    grid: &mut grid::Grid,
+    opts: &climate::ClimateOpts,
+) -> grid::DependentResult {
+    let drainage = rivers::compute_drainage(
+        &grid.mesh,
+        &grid.cells.h,
+        &grid.cells.temp,
+        &grid.cells.prec,
+    );
+    HELD_DRAINAGE.with(|d| *d.borrow_mut() = Some(drainage.clone()));
+    HELD_CLIMATE.with(|c| *c.borrow_mut() = Some(opts.clone()));
+    HELD_STALE.with(|s| s.set(false));
+    dependents_from_drainage(grid, opts, drainage)
+}
+
+/// Steps 2–7 of `recompute_dependents_inner`, after `drainage` (step 1).
+fn dependents_from_drainage(
+    grid: &mut grid::Grid,
+    opts: &climate::ClimateOpts,
+    drainage: rivers::DrainageResult,
+) -> grid::DependentResult {
     grid.cells.fl = drainage.fl.clone();
     grid.cells.r = drainage.r.clone();
     grid.cells.conf = drainage.conf.clone();
@@ -759,6 +1449,9 @@ fn compute_coastline(mesh: &crate::mesh::Mesh, h: &[u8]) -> Vec<u8> {
 /// - `dissolved_states`: state ids that lost ALL their land cells (empty until
 ///   Phase 3 adds the Pack with state records; detection needs pre-edit state)
 ///
+/// Locked cells (`CellData::locked`) are skipped: a hand-placed burg or state
+/// cell keeps its indices even if it ends up under water.
+///
 /// This is a pure function of `(grid.cells)` — no RNG, deterministic.
 /// Mutates `cells.state`, `cells.province`, `cells.culture`, `cells.religion`,
 /// `cells.burg` in place.
@@ -775,7 +1468,7 @@ fn repair_entities(cells: &mut grid::CellData) -> (Vec<String>, Vec<u32>) {
     // Clear entity indices on water cells. Land cells keep their assignments
     // (Phase 3 generators will overwrite with fresh ids anyway).
     for i in 0..n {
-        if cells.h[i] < sea {
+        if cells.h[i] < sea && !cells.is_locked(i) {
             // Water cell: unassign all entity indices.
             cells.state[i] = -1;
             cells.province[i] = -1;
@@ -815,10 +1508,281 @@ pub fn generate_world(seed: u32, cell_count: u32, opts_js: JsValue) -> JsValue {
     let grid = generate_world_inner(seed, cell_count, &opts);
     let js = serde_wasm_bindgen::to_value(&grid).expect("generate_world: grid serde to JsValue");
     // Store the grid in Rust-side handle for zero-serde subsequent calls.
-    HELD_GRID.with(|g| *g.borrow_mut() = Some(grid));
+    let origin = journal::WorldOrigin {
+        seed,
+        cell_count,
+        topology: mesh::Topology::Flat,
+        heightmap: journal::HeightmapSource::Default,
+        climate: opts,
+    };
+    hold_grid(Some(grid), Some(origin));
+    js
+}
+
+/// `generate_world` on a mesh of the given topology (`"Flat"` / `"Cylinder"` / `"Globe"`).
+/// Like `generate_world`, the result is also stored as the held grid.
+#[wasm_bindgen]
+pub fn generate_world_with_topology(
+    seed: u32,
+    cell_count: u32,
+    topology_js: JsValue,
+    opts_js: JsValue,
+) -> JsValue {
+    let topology: mesh::Topology = serde_wasm_bindgen::from_value(topology_js)
+        .expect("generate_world_with_topology: failed to deserialize Topology");
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    let grid = generate_world_with_topology_inner(seed, cell_count, topology, &opts);
+    let js = serde_wasm_bindgen::to_value(&grid)
+        .expect("generate_world_with_topology: grid serde to JsValue");
+    let origin = journal::WorldOrigin {
+        seed,
+        cell_count,
+        topology,
+        heightmap: journal::HeightmapSource::Default,
+        climate: opts,
+    };
+    hold_grid(Some(grid), Some(origin));
+    js
+}
+
+/// `generate_world_with_topology` with the heightmap drawn from a template
+/// (library id/name such as `"archipelago"`, or template text). `topology`
+/// and `opts` fall back to `Flat` / defaults when absent. Throws a
+/// `TemplateError` if the template is malformed; otherwise the result is
+/// also stored as the held grid.
+#[wasm_bindgen]
+pub fn generate_world_from_template(
+    seed: u32,
+    cell_count: u32,
+    template: &str,
+    topology_js: JsValue,
+    opts_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let topology: mesh::Topology = serde_wasm_bindgen::from_value(topology_js).unwrap_or_default();
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    let grid = generate_world_from_template_inner(seed, cell_count, template, topology, &opts)
+        .map_err(|e| {
+            serde_wasm_bindgen::to_value(&e)
+                .expect("generate_world_from_template: TemplateError serde to JsValue")
+        })?;
+    let js = serde_wasm_bindgen::to_value(&grid)
+        .expect("generate_world_from_template: grid serde to JsValue");
+    let origin = journal::WorldOrigin {
+        seed,
+        cell_count,
+        topology,
+        heightmap: journal::HeightmapSource::Template {
+            template: template.to_string(),
+        },
+        climate: opts,
+    };
+    hold_grid(Some(grid), Some(origin));
+    Ok(js)
+}
+
+/// `generate_world_with_topology` with a plate-tectonic heightmap
+/// (`tectonic_opts` a `TectonicOpts`). `topology` and both option objects
+/// fall back to `Flat` / defaults when absent. Like `generate_world`, the
+/// result is also stored as the held grid.
+#[wasm_bindgen]
+pub fn generate_world_tectonic(
+    seed: u32,
+    cell_count: u32,
+    topology_js: JsValue,
+    tectonic_opts_js: JsValue,
+    opts_js: JsValue,
+) -> JsValue {
+    let topology: mesh::Topology = serde_wasm_bindgen::from_value(topology_js).unwrap_or_default();
+    let tectonic_opts: tectonics::TectonicOpts =
+        serde_wasm_bindgen::from_value(tectonic_opts_js).unwrap_or_default();
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    let grid = generate_world_tectonic_inner(seed, cell_count, topology, &tectonic_opts, &opts);
+    let js = serde_wasm_bindgen::to_value(&grid)
+        .expect("generate_world_tectonic: grid serde to JsValue");
+    let origin = journal::WorldOrigin {
+        seed,
+        cell_count,
+        topology,
+        heightmap: journal::HeightmapSource::Tectonic {
+            opts: tectonic_opts,
+        },
+        climate: opts,
+    };
+    hold_grid(Some(grid), Some(origin));
     js
 }
 
+/// Regenerate the held grid's heightmap by plate tectonics with the grid's
+/// seed, resetting entity indices like `reset_heightmap_h`. Returns the new
+/// `cells.h`.
+///
+/// Exposed as `apply_tectonics_h(opts)` to JS.
+#[wasm_bindgen]
+pub fn apply_tectonics_h(opts_js: JsValue) -> js_sys::Uint8Array {
+    let opts: tectonics::TectonicOpts = serde_wasm_bindgen::from_value(opts_js).unwrap_or_default();
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("apply_tectonics_h: no held grid");
+        let before = history::Snapshot::of(&grid.cells);
+        let h = tectonics::generate(&grid.mesh, grid.seed, &opts).h;
+        replace_heightmap(&mut grid.cells, h);
+        record_held_edit("Tectonics", &before, &grid.cells);
+        js_sys::Uint8Array::from(grid.cells.h.as_slice())
+    })
+}
+
+/// Regenerate the held grid's heightmap from a template (library id/name or
+/// template text) with the grid's seed, resetting entity indices like
+/// `reset_heightmap_h`. Returns the new `cells.h`; throws a `TemplateError`
+/// (leaving the grid untouched) if the template is malformed.
+///
+/// Exposed as `apply_heightmap_template_h(template)` to JS.
+#[wasm_bindgen]
+pub fn apply_heightmap_template_h(template: &str) -> Result<js_sys::Uint8Array, JsValue> {
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard
+            .as_mut()
+            .expect("apply_heightmap_template_h: no held grid");
+        let h =
+            heightmap::generate_from_template(&grid.mesh, grid.seed, template).map_err(|e| {
+                serde_wasm_bindgen::to_value(&e)
+                    .expect("apply_heightmap_template_h: TemplateError serde to JsValue")
+            })?;
+        let before = history::Snapshot::of(&grid.cells);
+        replace_heightmap(&mut grid.cells, h);
+        record_held_edit("Template", &before, &grid.cells);
+        Ok(js_sys::Uint8Array::from(grid.cells.h.as_slice()))
+    })
+}
+
+/// `ImportOpts` from JS: `undefined` / `null` are the defaults, anything else
+/// that doesn't read as `ImportOpts` is `ImageError::Malformed`.
+fn import_opts_from_js(import_opts_js: JsValue) -> Result<raster::ImportOpts, raster::ImageError> {
+    serde_wasm_bindgen::from_value::<Option<raster::ImportOpts>>(import_opts_js)
+        .map(Option::unwrap_or_default)
+        .map_err(|e| raster::ImageError::Malformed {
+            reason: format!("import options: {e}"),
+        })
+}
+
+/// Build a world whose heightmap comes from a grayscale image (PNG or PGM
+/// bytes, or a raw buffer described by `import_opts.raw`) instead of the
+/// generator: a fresh mesh of `topology` (default `Flat`), the image
+/// resampled onto it, then climate, biomes and drainage as in
+/// `generate_world`. Throws an `ImageError` (`{ kind, ... }`) if the image or
+/// `import_opts` is rejected; otherwise the result is also stored as the held
+/// grid.
+#[wasm_bindgen]
+pub fn import_heightmap(
+    bytes: &[u8],
+    cell_count: u32,
+    seed: u32,
+    topology_js: JsValue,
+    import_opts_js: JsValue,
+    opts_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let reject = |e: raster::ImageError| {
+        serde_wasm_bindgen::to_value(&e).expect("import_heightmap: ImageError serde to JsValue")
+    };
+    let topology: mesh::Topology = serde_wasm_bindgen::from_value(topology_js).unwrap_or_default();
+    let import_opts = import_opts_from_js(import_opts_js).map_err(reject)?;
+    let opts: climate::ClimateOpts =
+        serde_wasm_bindgen::from_value(opts_js).unwrap_or_else(|_| climate::ClimateOpts::default());
+    let image = raster::GrayImage::load(bytes, &import_opts).map_err(reject)?;
+    let mesh = mesh::build_with_topology(cell_count, seed, topology);
+    let grid = import_heightmap_inner(&mesh, seed, &image, &import_opts, &opts);
+    let js = serde_wasm_bindgen::to_value(&grid).expect("import_heightmap: grid serde to JsValue");
+    hold_grid(Some(grid), None);
+    Ok(js)
+}
+
+/// Replace the held grid's heightmap with a grayscale image resampled onto
+/// its mesh, resetting entity indices like `reset_heightmap_h`. Returns the
+/// new `cells.h`; follow with `recompute_dependents_h` to refresh climate,
+/// biomes and drainage. Throws an `ImageError` (leaving the grid untouched)
+/// if the image or `import_opts` is rejected.
+///
+/// Exposed as `import_heightmap_h(bytes, importOpts)` to JS.
+#[wasm_bindgen]
+pub fn import_heightmap_h(
+    bytes: &[u8],
+    import_opts_js: JsValue,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let reject = |e: raster::ImageError| {
+        serde_wasm_bindgen::to_value(&e).expect("import_heightmap_h: ImageError serde to JsValue")
+    };
+    let import_opts = import_opts_from_js(import_opts_js).map_err(reject)?;
+    let image = raster::GrayImage::load(bytes, &import_opts).map_err(reject)?;
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("import_heightmap_h: no held grid");
+        let before = history::Snapshot::of(&grid.cells);
+        let h = raster::heights_from_image(&grid.mesh, &image, import_opts.sea_level);
+        replace_heightmap(&mut grid.cells, h);
+        record_held_edit("Import", &before, &grid.cells);
+        Ok(js_sys::Uint8Array::from(grid.cells.h.as_slice()))
+    })
+}
+
+/// Rasterize one layer of the held grid (`h`, `temp`, `prec`, `fl`, `biome`,
+/// `state`, `culture`, `religion`) at `opts.width × opts.height` pixels and
+/// return PNG bytes: 16-bit grayscale for continuous layers, paletted RGBA
+/// for categorical ones. `pack_js` (an `entities::Pack`, or `null`) supplies
+/// the state / culture / religion colors. Throws an `ExportError` on a bad
+/// size or unreadable options / pack.
+///
+/// Exposed as `export_layer_png_h(opts, pack)` to JS.
+#[wasm_bindgen]
+pub fn export_layer_png_h(
+    opts_js: JsValue,
+    pack_js: JsValue,
+) -> Result<js_sys::Uint8Array, JsValue> {
+    let reject = |e: raster::ExportError| {
+        serde_wasm_bindgen::to_value(&e).expect("export_layer_png_h: ExportError serde to JsValue")
+    };
+    let malformed = |e: serde_wasm_bindgen::Error| {
+        reject(raster::ExportError::Malformed {
+            reason: e.to_string(),
+        })
+    };
+    let opts: raster::ExportOpts = serde_wasm_bindgen::from_value(opts_js).map_err(malformed)?;
+    let pack: Option<entities::Pack> =
+        serde_wasm_bindgen::from_value(pack_js).map_err(malformed)?;
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("export_layer_png_h: no held grid");
+        refresh_held_dependents(grid);
+        let raster = raster::rasterize(grid, pack.as_ref(), &opts).map_err(reject)?;
+        Ok(js_sys::Uint8Array::from(raster.to_png().as_slice()))
+    })
+}
+
+/// Refine a region of the Rust-side held grid at higher density. `region` is
+/// `{ kind: "Rect", x0, y0, x1, y1 }` or `{ kind: "Cells", cells }`. Returns a
+/// `RegionGrid` (`{ grid, origin, parent_cell }`), or `null` if the region is
+/// empty. The held grid is left untouched.
+///
+/// Exposed as `refine_region_h(region, cellCount, seed)` to JS.
+#[wasm_bindgen]
+pub fn refine_region_h(region_js: JsValue, cell_count: u32, seed: u32) -> JsValue {
+    let region: region::Region = serde_wasm_bindgen::from_value(region_js)
+        .expect("refine_region_h: failed to deserialize Region");
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("refine_region_h: no held grid");
+        refresh_held_dependents(grid);
+        match region::refine_region(grid, &region, cell_count, seed) {
+            Some(child) => serde_wasm_bindgen::to_value(&child)
+                .expect("refine_region_h: RegionGrid serde to JsValue"),
+            None => JsValue::NULL,
+        }
+    })
+}
+
 /// Phase 3 Step 3.2: generate states, provinces, and burgs for a fully-built
 /// `Grid` (mesh + heightmap + climate + biomes + drainage). Returns a
 /// `StatesResult` carrying the `Pack` + per-cell index arrays
@@ -931,6 +1895,50 @@ pub fn project_delta(
     serde_wasm_bindgen::to_value(&world).expect("project_delta: WorldAt serde to JsValue")
 }
 
+/// Project the held grid's climate at `target_year` (`timeline::project_climate`),
+/// to show alongside `WorldAt(target_year)`, under `drift_js`, the
+/// `ClimateKeyframe[]` the timeline was generated with
+/// (`TimelineParams.climate`). `undefined` or `[]` means no drift, and the
+/// year-0 climate comes back. Returns `{ year, temp_offset, prec_scale, temp:
+/// Int8Array, prec: Uint8Array, biome: Uint8Array }`.
+///
+/// Exposed as `project_climate_h(targetYear, drift)` to JS.
+#[wasm_bindgen]
+pub fn project_climate_h(target_year: i32, drift_js: JsValue) -> JsValue {
+    let drift: Option<timeline::ClimateDrift> = serde_wasm_bindgen::from_value(drift_js)
+        .expect("project_climate_h: failed to deserialize ClimateKeyframe[]");
+    let climate = project_held_climate(&drift.unwrap_or_default(), target_year);
+    let obj = js_sys::Object::new();
+    let set = |key: &str, value: &JsValue| {
+        js_sys::Reflect::set(&obj, &JsValue::from_str(key), value).expect("set");
+    };
+    set("year", &JsValue::from(climate.year));
+    set("temp_offset", &JsValue::from(climate.shift.temp_offset));
+    set("prec_scale", &JsValue::from(climate.shift.prec_scale));
+    set("temp", &js_sys::Int8Array::from(climate.temp.as_slice()));
+    set("prec", &js_sys::Uint8Array::from(climate.prec.as_slice()));
+    set("biome", &js_sys::Uint8Array::from(climate.biome.as_slice()));
+    obj.into()
+}
+
+/// `project_climate_h` without the JS boundary, under `drift`.
+fn project_held_climate(drift: &timeline::ClimateDrift, target_year: i32) -> timeline::ClimateAt {
+    HELD_GRID.with(|g| {
+        let mut guard = g.borrow_mut();
+        let grid = guard.as_mut().expect("project_climate_h: no held grid");
+        refresh_held_dependents(grid);
+        let cells = &grid.cells;
+        timeline::project_climate(
+            &grid.mesh,
+            &cells.h,
+            &cells.temp,
+            &cells.prec,
+            drift,
+            target_year,
+        )
+    })
+}
+
 /// Phase 4.1: full projection of a `WorldAt` from base, returning a `JsValue`.
 /// Convenience wrapper around `project_world` for tests that can't call
 /// `#[wasm_bindgen]` functions returning JsValue on non-WASM targets.
@@ -1024,18 +2032,8 @@ pub fn generate_timeline(
         );
     let cells: &crate::mesh::Cells = &mesh.cells;
 
-    let timeline = event_engine::generate_timeline(
-        &pack,
-        &cs,
-        &cc,
-        &cr,
-        &cb,
-        &ch,
-        &cp,
-        cells,
-        seed,
-        &params,
-    );
+    let timeline =
+        generate_timeline_inner(&pack, &cs, &cc, &cr, &cb, &ch, &cp, cells, seed, &params);
     serde_wasm_bindgen::to_value(&timeline).expect("generate_timeline: Timeline serde to JsValue")
 }
 
@@ -1076,12 +2074,78 @@ pub fn generate_timeline_inner(
 /// plus drainage arrays (fl, r, conf) so fresh worlds have rivers from the
 /// start (not only after the first heightmap edit).
 pub fn generate_world_inner(seed: u32, cell_count: u32, opts: &climate::ClimateOpts) -> grid::Grid {
+    generate_world_with_topology_inner(seed, cell_count, mesh::Topology::Flat, opts)
+}
+
+/// `generate_world_inner` on a mesh of the given topology. `Topology::Flat`
+/// produces the exact same grid as `generate_world_inner`.
+pub fn generate_world_with_topology_inner(
+    seed: u32,
+    cell_count: u32,
+    topology: mesh::Topology,
+    opts: &climate::ClimateOpts,
+) -> grid::Grid {
     // 1.1 — generate the Voronoi mesh
-    let mesh = mesh::build(cell_count, seed);
+    let mesh = mesh::build_with_topology(cell_count, seed, topology);
 
-    // 1.2 — build Grid with heightmap (cells.h populated)
+    // 1.2 — heightmap from the default template
     let h = heightmap::generate(&mesh, seed as u64);
-    let mut grid = grid::Grid::from_mesh(&mesh, seed as u64);
+    assemble_world(&mesh, h, seed, opts)
+}
+
+/// `generate_world_with_topology_inner` with the heightmap drawn from a
+/// template (library id/name or template text) instead of the default one.
+pub fn generate_world_from_template_inner(
+    seed: u32,
+    cell_count: u32,
+    template: &str,
+    topology: mesh::Topology,
+    opts: &climate::ClimateOpts,
+) -> Result<grid::Grid, heightmap::TemplateError> {
+    // Reject a bad template before paying for the mesh.
+    heightmap::validate_template(template)?;
+    let mesh = mesh::build_with_topology(cell_count, seed, topology);
+    let h = heightmap::generate_from_template(&mesh, seed as u64, template)?;
+    Ok(assemble_world(&mesh, h, seed, opts))
+}
+
+/// `generate_world_with_topology_inner` with a plate-tectonic heightmap
+/// (`tectonics::generate`) instead of the default template.
+pub fn generate_world_tectonic_inner(
+    seed: u32,
+    cell_count: u32,
+    topology: mesh::Topology,
+    tectonic_opts: &tectonics::TectonicOpts,
+    opts: &climate::ClimateOpts,
+) -> grid::Grid {
+    let mesh = mesh::build_with_topology(cell_count, seed, topology);
+    let h = tectonics::generate(&mesh, seed as u64, tectonic_opts).h;
+    assemble_world(&mesh, h, seed, opts)
+}
+
+/// A world over `mesh` whose heightmap is `image` resampled onto it (see
+/// `raster`), with climate, biomes and drainage filled in — ready for
+/// `recompute_dependents_inner` like any generated world.
+pub fn import_heightmap_inner(
+    mesh: &mesh::Mesh,
+    seed: u32,
+    image: &raster::GrayImage,
+    import_opts: &raster::ImportOpts,
+    opts: &climate::ClimateOpts,
+) -> grid::Grid {
+    let h = raster::heights_from_image(mesh, image, import_opts.sea_level);
+    assemble_world(mesh, h, seed, opts)
+}
+
+/// Steps 1.2–2.5.3 of world generation over a mesh and its heightmap:
+/// climate, biomes and drainage.
+fn assemble_world(
+    mesh: &mesh::Mesh,
+    h: Vec<u8>,
+    seed: u32,
+    opts: &climate::ClimateOpts,
+) -> grid::Grid {
+    let mut grid = grid::Grid::from_mesh(mesh, seed as u64);
     grid.cells.h = h;
 
     // 1.3 — climate: populate cells.temp and cells.prec
@@ -2077,75 +3141,76 @@ mod tests {
         }
     }
 
-    /// `pick_cell` correctly handles points exactly on a Voronoi edge where
-    /// the true nearest cell is 2+ hops from the bucket cell. This documents
-    /// the known 1-hop limitation (adversarial review F9).
+    /// Locked cells keep their burg and state when they end up underwater.
+    #[test]
+    fn repair_entities_skips_locked_cells() {
+        let opts = climate::ClimateOpts::default();
+        let mut grid = generate_world_inner(42, 2000, &opts);
+        let n = grid.cells.h.len();
+        let targets: Vec<usize> = (0..n)
+            .filter(|&i| grid.cells.h[i] >= 25 && grid.cells.h[i] <= 40)
+            .take(2)
+            .collect();
+        let (kept, cleared) = (targets[0], targets[1]);
+        for &t in &targets {
+            grid.cells.burg[t] = 9;
+            grid.cells.state[t] = 4;
+            grid.cells.h[t] = 10;
+        }
+        grid.cells.locked = vec![false; n];
+        grid.cells.locked[kept] = true;
+
+        let result = recompute_dependents_inner(&mut grid, &opts);
+        assert_eq!(result.removed_burgs, vec![format!("Burg@cell{cleared}")]);
+        assert_eq!((grid.cells.burg[kept], grid.cells.state[kept]), (9, 4));
+        assert_eq!(
+            (grid.cells.burg[cleared], grid.cells.state[cleared]),
+            (0, -1)
+        );
+    }
+
+    /// `pick_cell` returns the exact nearest cell, also for points on a
+    /// Voronoi edge where the true nearest cell is 2+ hops from the bucket
+    /// cell (the old 1-hop limitation, adversarial review F9). Ties go to the
+    /// lower cell id.
     #[test]
-    #[ignore = "known limitation: 1-hop refinement may miss 2-hop nearest; run to measure gap"]
     fn pick_cell_two_hop_edge_case() {
         let mesh = mesh::build(3000, 42);
-
-        // Find a case where the bucket cell's 1-hop neighbors don't include
-        // the true nearest cell. We scan for query points where the brute-force
-        // nearest differs from pick_cell's answer.
         let brute = |x: f64, y: f64| -> u32 {
-            mesh.points
-                .iter()
-                .enumerate()
-                .min_by_key(|(_, &[px, py])| ((px - x).powi(2) + (py - y).powi(2)) as i64)
-                .map(|(i, _)| i as u32)
-                .unwrap()
+            let d = |c: usize| mesh.distance_sq(mesh.points[c], [x, y]);
+            (0..mesh.points.len())
+                .min_by(|&a, &b| d(a).total_cmp(&d(b)).then(a.cmp(&b)))
+                .unwrap() as u32
         };
 
-        let mut two_hop_misses = 0;
-        let mut total = 0;
         let mut rng = rand::rngs::StdRng::seed_from_u64(9999);
-        for _ in 0..500 {
-            let x = rng.gen_range(0.0..1.0);
-            let y = rng.gen_range(0.0..1.0);
-            let picked = crate::heightmap::pick_cell(&mesh, x, y).unwrap();
-            let true_nearest = brute(x, y);
-            if picked != true_nearest {
-                // Check if the true nearest is 2+ hops from the picked cell
-                // by walking toward true_nearest via adjacency.
-                let mut dist = 0;
-                let mut cur = picked as usize;
-                while cur != true_nearest as usize && dist < 10 {
-                    let lo = mesh.cells.i[cur] as usize;
-                    let hi = mesh.cells.i[cur + 1] as usize;
-                    let mut found = false;
-                    for &nb in &mesh.cells.c[lo..hi] {
-                        let nb = nb as usize;
-                        let [px, py] = mesh.points[nb];
-                        let [tx, ty] = mesh.points[true_nearest as usize];
-                        let [cx, cy] = mesh.points[cur];
-                        let d_to_target = (px - tx).powi(2) + (py - ty).powi(2);
-                        let d_cur = (cx - tx).powi(2) + (cy - ty).powi(2);
-                        if d_to_target < d_cur {
-                            cur = nb;
-                            found = true;
-                            break;
-                        }
-                    }
-                    if !found {
-                        break;
-                    }
-                    dist += 1;
-                }
-                if dist >= 2 {
-                    two_hop_misses += 1;
-                }
+        let mut queries: Vec<[f64; 2]> = (0..500)
+            .map(|_| {
+                [
+                    rng.gen_range(0.0..mesh.world_w),
+                    rng.gen_range(0.0..mesh.world_h),
+                ]
+            })
+            .collect();
+        // Midpoints of neighbouring centres lie on the Voronoi edge between
+        // them.
+        for cell in (0..mesh.points.len()).step_by(10) {
+            let [x0, y0] = mesh.points[cell];
+            for &nb in mesh.cells.neighbors_of_cell(cell) {
+                let [x1, y1] = mesh.points[nb as usize];
+                queries.push([(x0 + x1) / 2.0, (y0 + y1) / 2.0]);
             }
-            total += 1;
         }
-        eprintln!(
-            "pick_cell 2-hop misses: {}/{} ({:.1}%)",
-            two_hop_misses,
-            total,
-            two_hop_misses as f64 / total as f64 * 100.0
+
+        let misses: Vec<[f64; 2]> = queries
+            .into_iter()
+            .filter(|&[x, y]| crate::heightmap::pick_cell(&mesh, x, y) != Some(brute(x, y)))
+            .collect();
+        assert_eq!(
+            misses.len(),
+            0,
+            "pick_cell missed the nearest cell at {misses:?}"
         );
-        // This test is informational — it documents the gap. If the 2-hop
-        // rate becomes significant (>5%), consider 2-hop expansion.
     }
 
     /// `reset_heightmap` clears culture and religion arrays (in addition to
@@ -2360,6 +3425,86 @@ mod tests {
         HELD_GRID.with(|g| *g.borrow_mut() = None);
     }
 
+    /// Undoing an edit leaves the dependent layers stale; the next read
+    /// recomputes them with the held options, matching a fresh pass.
+    #[test]
+    fn history_steps_refresh_dependents_on_read() {
+        let opts = climate::ClimateOpts::default();
+        let mut grid = generate_world_inner(7, 1000, &opts);
+        recompute_held_dependents(&mut grid, &opts);
+        let temp = grid.cells.temp.clone();
+
+        let mut history = history::History::default();
+        let before = history::Snapshot::of(&grid.cells);
+        for h in &mut grid.cells.h {
+            *h = h.saturating_add(15);
+        }
+        history.record("Raise", &before, &grid.cells);
+        recompute_held_dependents(&mut grid, &opts);
+        assert_ne!(grid.cells.temp, temp);
+
+        history.undo(&mut grid.cells);
+        let expected = recompute_dependents_inner(&mut grid.clone(), &opts);
+        HELD_STALE.with(|s| s.set(true));
+        refresh_held_dependents(&mut grid);
+        assert!(!HELD_STALE.with(|s| s.get()));
+        assert_eq!(grid.cells.temp, temp);
+        assert_eq!(grid.cells.temp, expected.temp);
+        assert_eq!(grid.cells.prec, expected.prec);
+        assert_eq!(grid.cells.biome, expected.biome);
+        assert_eq!(grid.cells.fl, expected.fl);
+        assert_eq!(grid.cells.r, expected.r);
+        assert_eq!(grid.cells.conf, expected.conf);
+    }
+
+    /// The held grid's climate is projected under the drift it is given.
+    #[test]
+    fn held_climate_follows_the_given_drift() {
+        let opts = climate::ClimateOpts::default();
+        let grid = generate_world_inner(5, 500, &opts);
+        hold_grid(Some(grid.clone()), None);
+        let drift = vec![timeline::ClimateKeyframe {
+            year: 0,
+            temp_offset: -4.0,
+            prec_scale: 0.8,
+        }];
+        let cells = &grid.cells;
+        let expected =
+            timeline::project_climate(&grid.mesh, &cells.h, &cells.temp, &cells.prec, &drift, 300);
+        assert_eq!(project_held_climate(&drift, 300), expected);
+        assert_ne!(expected.temp, cells.temp);
+        hold_grid(None, None);
+    }
+
+    /// Projecting the held climate with no drift gives back the year-0
+    /// climate.
+    #[test]
+    fn held_climate_without_drift_is_the_year_zero_climate() {
+        let opts = climate::ClimateOpts::default();
+        let grid = generate_world_inner(5, 500, &opts);
+        hold_grid(Some(grid.clone()), None);
+        let climate = project_held_climate(&Vec::new(), 300);
+        assert_eq!(climate.shift.temp_offset, 0.0);
+        assert_eq!(climate.shift.prec_scale, 1.0);
+        assert_eq!(climate.temp, grid.cells.temp);
+        assert_eq!(climate.prec, grid.cells.prec);
+        hold_grid(None, None);
+    }
+
+    /// A whole-map edit drops the held drainage, so the next local drainage
+    /// update starts from a full pass.
+    #[test]
+    fn whole_map_edits_drop_held_drainage() {
+        let opts = climate::ClimateOpts::default();
+        let mut grid = generate_world_inner(3, 500, &opts);
+        recompute_held_dependents(&mut grid, &opts);
+        assert!(HELD_DRAINAGE.with(|d| d.borrow().is_some()));
+        let before = history::Snapshot::of(&grid.cells);
+        grid.cells.h.fill(30);
+        record_held_edit("Reset", &before, &grid.cells);
+        assert!(HELD_DRAINAGE.with(|d| d.borrow().is_none()));
+    }
+
     /// 60k timing gate for `recompute_dependents`. The full recompute cascade
     /// (rivers → lakes → coastline → climate → biome → repair) on a 60k-cell
     /// grid must complete in < 500ms in native release (the authoritative
@@ -2438,4 +3583,97 @@ mod tests {
             xxhash_rust::xxh64::xxh64(&b, 0)
         );
     }
+
+    /// A globe world runs the whole pipeline (no hull cells for drainage to
+    /// pour into) and is deterministic.
+    #[test]
+    fn generate_world_globe_runs_pipeline() {
+        let opts = climate::ClimateOpts::default();
+        let g = generate_world_with_topology_inner(3, 3000, mesh::Topology::Globe, &opts);
+        let n = g.mesh.points.len();
+        assert_eq!(g.mesh.topology, mesh::Topology::Globe);
+        assert_eq!(g.cells.h.len(), n);
+        assert_eq!(g.cells.biome.len(), n);
+        assert_eq!(g.cells.fl.len(), n);
+        let again = generate_world_with_topology_inner(3, 3000, mesh::Topology::Globe, &opts);
+        assert_eq!(g.cells.h, again.cells.h);
+        assert_eq!(g.cells.temp, again.cells.temp);
+        assert_eq!(g.cells.prec, again.cells.prec);
+    }
+
+    /// A template world runs the whole pipeline on the template's heights;
+    /// a malformed template is rejected before any work.
+    #[test]
+    fn generate_world_from_template_uses_template() {
+        let opts = climate::ClimateOpts::default();
+        let g =
+            generate_world_from_template_inner(5, 2000, "Archipelago", mesh::Topology::Flat, &opts)
+                .unwrap();
+        let n = g.mesh.points.len();
+        assert_eq!(
+            g.cells.h,
+            heightmap::generate_from_template(&g.mesh, 5, "archipelago").unwrap()
+        );
+        assert_eq!(g.cells.biome.len(), n);
+        assert_eq!(g.cells.fl.len(), n);
+        assert_ne!(g.cells.h, generate_world_inner(5, 2000, &opts).cells.h);
+        let err =
+            generate_world_from_template_inner(5, 2000, "Hill 1 2", mesh::Topology::Flat, &opts)
+                .err()
+                .unwrap();
+        assert!(matches!(
+            err,
+            heightmap::TemplateError::ArgumentCount { line: 1, .. }
+        ));
+    }
+
+    /// Tectonic heights feed the pipeline on a cylinder, leaving both land and sea.
+    #[test]
+    fn generate_world_tectonic_uses_plates() {
+        let opts = climate::ClimateOpts::default();
+        let tectonic_opts = tectonics::TectonicOpts::default();
+        let g =
+            generate_world_tectonic_inner(5, 2000, mesh::Topology::Cylinder, &tectonic_opts, &opts);
+        assert_eq!(g.cells.h, tectonics::generate(&g.mesh, 5, &tectonic_opts).h);
+        assert_eq!(g.cells.fl.len(), g.mesh.points.len());
+        assert!(g.cells.h.iter().any(|&h| h >= heightmap::SEA_LEVEL));
+        assert!(g.cells.h.iter().any(|&h| h < heightmap::SEA_LEVEL));
+    }
+
+    /// An imported image becomes the world's heightmap and the grid runs
+    /// through `recompute_dependents_inner` unchanged.
+    #[test]
+    fn imported_heightmap_feeds_recompute() {
+        let opts = climate::ClimateOpts::default();
+        let mesh = mesh::build(2000, 9);
+        // A bright disc (land) on a dark sea.
+        let size = 64u32;
+        let data: Vec<u8> = (0..size * size)
+            .map(|k| {
+                let (x, y) = ((k % size) as f64 - 31.5, (k / size) as f64 - 31.5);
+                if x * x + y * y < 20.0f64.powi(2) {
+                    200
+                } else {
+                    10
+                }
+            })
+            .collect();
+        let pgm = [format!("P5 {size} {size} 255\n").as_bytes(), &data].concat();
+        let image = raster::GrayImage::decode(&pgm).unwrap();
+        let import_opts = raster::ImportOpts::default();
+        let mut grid = import_heightmap_inner(&mesh, 9, &image, &import_opts, &opts);
+        let centre = heightmap::pick_cell(&mesh, 5000.0, 4000.0).unwrap() as usize;
+        let corner = heightmap::pick_cell(&mesh, 0.0, 0.0).unwrap() as usize;
+        assert!(grid.cells.h[centre] >= heightmap::SEA_LEVEL);
+        assert!(grid.cells.h[corner] < heightmap::SEA_LEVEL);
+        let h = grid.cells.h.clone();
+        let result = recompute_dependents_inner(&mut grid, &opts);
+        assert_eq!(grid.cells.h, h);
+        assert_eq!(result.biome.len(), h.len());
+        assert!(
+            coast::shorelines(&grid.mesh, &grid.cells.h, &result.lakes, 0)
+                .iter()
+                .any(|s| s.kind == grid::ShoreKind::Sea)
+        );
+    }
 }
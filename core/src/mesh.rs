@@ -9,7 +9,8 @@
 //!
 //! 1. Sample `cell_count` points uniformly in the world rectangle using a
 //!    `StdRng::seed_from_u64(seed)` (the single RNG source — see the
-//!    Determinism Contract, technical-requirements §4).
+//!    Determinism Contract, technical-requirements §4). `build_from_points`
+//!    skips this step and uses caller-supplied points verbatim.
 //! 2. Bulk-load them into a `spade::DelaunayTriangulation` via `bulk_load_stable`,
 //!    which preserves insertion order — so Delaunay vertex index `i` corresponds
 //!    to input point `i`, giving deterministic cell ids.
@@ -69,7 +70,7 @@ pub const WORLD_H: f64 = 8000.0;
 /// `cells.i` is shared between `v` and `c` — a cell has as many vertices as
 /// neighbors in a closed Voronoi polygon. (Edge cells with infinite vertices
 /// are clamped to the world rectangle so they still close.)
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Mesh {
     pub points: Vec<[f64; 2]>,
     pub cells: Cells,
@@ -81,11 +82,22 @@ pub struct Mesh {
     // worlds in later phases (adversarial review M5).
     pub world_w: f64,
     pub world_h: f64,
+    /// Surface the mesh tiles. Meshes serialized before topologies existed
+    /// have no such field on the wire and deserialize as `Flat`.
+    #[serde(default)]
+    pub topology: Topology,
+    /// Per-cell `[lat, lon]` in degrees, parallel to `points`. Only populated
+    /// for `Topology::Globe` (empty otherwise — flat maps derive latitude from
+    /// `y` via `climate::calculate_map_coordinates`).
+    #[serde(default)]
+    pub lat_lon: Vec<[f64; 2]>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Cells {
     pub v: Vec<u32>,
+    /// Neighbour ids, parallel to `v`: within a cell's slice, `c[k]` lies
+    /// across the ring edge `(v[k-1], v[k])` (cyclically).
     pub c: Vec<u32>,
     pub i: Vec<u32>,
     pub b: Vec<u8>,
@@ -105,11 +117,90 @@ pub struct Cells {
     pub cells_y: u32,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Vertices {
     pub p: Vec<[f64; 2]>,
 }
 
+/// Which surface a `Mesh` tiles.
+///
+/// `Flat` is the original world rectangle with clamped hull cells.
+/// `Cylinder` is the same rectangle with the east and west edges glued: the
+/// triangulation is periodic in `x`, so `cells.c` links cells across the
+/// antimeridian and only the top/bottom rows are border cells. `Globe`
+/// tiles the whole unit sphere: there is no hull (every cell is closed by real
+/// neighbours, `cells.b` is all zero) and `points` / `vertices.p` are the
+/// equirectangular projection of the spherical positions into
+/// `world_w × world_h`, so every 2D consumer keeps working unchanged while
+/// `Mesh::lat_lon` carries the exact spherical coordinates.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Topology {
+    #[default]
+    Flat,
+    Cylinder,
+    Globe,
+}
+
+impl Topology {
+    /// `true` if `x = 0` and `x = world_w` are the same meridian (cylinder
+    /// and globe — the globe's projection is periodic in longitude too).
+    pub fn wraps_x(self) -> bool {
+        matches!(self, Topology::Cylinder | Topology::Globe)
+    }
+}
+
+impl Mesh {
+    /// A flat mesh over the given geometry (`Topology::Flat`, no `lat_lon`).
+    pub fn new(
+        points: Vec<[f64; 2]>,
+        cells: Cells,
+        vertices: Vertices,
+        world_w: f64,
+        world_h: f64,
+    ) -> Mesh {
+        Mesh {
+            points,
+            cells,
+            vertices,
+            world_w,
+            world_h,
+            topology: Topology::Flat,
+            lat_lon: Vec::new(),
+        }
+    }
+
+    /// Signed shortest `x1 − x0`, taking the east–west seam into account on
+    /// topologies that wrap in `x`.
+    pub fn delta_x(&self, x0: f64, x1: f64) -> f64 {
+        let dx = x1 - x0;
+        if !self.topology.wraps_x() {
+            return dx;
+        }
+        let w = self.world_w;
+        dx - w * (dx / w).round()
+    }
+
+    /// Squared distance between two world points, measured across the seam
+    /// when the topology wraps in `x`.
+    pub fn distance_sq(&self, a: [f64; 2], b: [f64; 2]) -> f64 {
+        let dx = self.delta_x(a[0], b[0]);
+        let dy = b[1] - a[1];
+        dx * dx + dy * dy
+    }
+
+    /// Column `col` of a `cols`-wide bucket grid laid over the world, wrapped
+    /// across the east–west seam when the topology wraps in `x`. `None` past
+    /// the edge of a flat map.
+    pub fn bucket_col(&self, col: i32, cols: usize) -> Option<usize> {
+        let col = if self.topology.wraps_x() {
+            col.rem_euclid(cols as i32)
+        } else {
+            col
+        };
+        (0..cols as i32).contains(&col).then_some(col as usize)
+    }
+}
+
 impl Cells {
     /// Return the neighbor cell ids of `cell` from the Delaunay/Voronoi CSR
     /// topology.
@@ -117,7 +208,8 @@ impl Cells {
     /// `i` is the CSR offset array of length `N+1`; cell `c`'s neighbor slice
     /// is `c[i[c]..i[c+1]]` from `cells.c`. This returns the actual edge-sharing
     /// neighbors of a Voronoi cell (the Delaunay adjacency), replacing the
-    /// square-grid assumption that the event engine previously used.
+    /// square-grid assumption that the event engine previously used. On
+    /// wrapping topologies the slice includes neighbours across the seam.
     ///
     /// Returns an empty slice if `cell` is out of bounds.
     pub fn neighbors_of_cell(&self, cell: usize) -> &[u32] {
@@ -137,6 +229,16 @@ impl Cells {
 /// postMessage it (Phase 2 will replace the boundary with transferable
 /// TypedArrays for zero-copy — but the shape stays the same).
 pub fn build(cell_count: u32, seed: u32) -> Mesh {
+    build_region(cell_count, seed, WORLD_W, WORLD_H)
+}
+
+/// `build` over an arbitrary `world_w × world_h` rectangle instead of the
+/// full world — the child mesh of a regional refinement (`region.rs`), in the
+/// child's local coordinates `[0, world_w) × [0, world_h)`. The mesh records
+/// the dimensions in `Mesh::world_w`/`world_h` (adversarial review M5), so
+/// every downstream generator works on it unchanged.
+/// `build_region(n, seed, WORLD_W, WORLD_H)` is exactly `build(n, seed)`.
+pub fn build_region(cell_count: u32, seed: u32, world_w: f64, world_h: f64) -> Mesh {
     let n = cell_count.clamp(4, 1_000_000) as usize;
 
     // 1. Seed points: Poisson-disk + Lloyd relaxation → well-spaced seed points.
@@ -156,43 +258,7 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
     //    N ≤ 12 we fall back to the pre-Poisson uniform-random sampler, which
     //    jittered into general position produces valid cells at these counts.
     let mut rng = StdRng::seed_from_u64(seed as u64);
-    let mut points_in: Vec<Point2<f64>> = if n <= 12 {
-        // NOTE: we skip the Lloyd relaxation here. At N ≤ 12 the relaxation
-        // centroid step collapses near-collinear triples into coincident
-        // points (the 4-point seed=42 case produced two degenerate cells with
-        // k=2 after 3 Lloyd passes). Uniform random + jitter already lands in
-        // general position with probability 1; Lloyd only makes it worse at
-        // this scale. For N > 12 the Poisson-disk branch keeps Lloyd (it's the
-        // whole point of blue-noise spacing there).
-        let mut pts = Vec::with_capacity(n);
-        for _ in 0..n {
-            let x = rng.gen_range(0.0..WORLD_W);
-            let y = rng.gen_range(0.0..WORLD_H);
-            pts.push(Point2::new(x, y));
-        }
-        pts
-    } else {
-        poisson_disk_sample(n, &mut rng)
-    };
-    // Clamp to a tiny margin inside the world edge so that the small_jitter
-    // applied later (±5e-6) cannot push points outside [0, WORLD_W]×[0, WORLD_H].
-    let margin = 1e-5;
-    for p in &mut points_in {
-        p.x = p.x.clamp(margin, WORLD_W - margin);
-        p.y = p.y.clamp(margin, WORLD_H - margin);
-    }
-    // Lloyd relaxation only runs on the Poisson-disk branch (N > 12); see the
-    // N ≤ 12 fallback above for the rationale.
-    if n > 12 {
-        for _ in 0..3 {
-            points_in = lloyd_relax(&points_in, 0.5, &mut rng);
-            // Re-clamp after each relaxation step (Lloyd can drift points out).
-            for p in &mut points_in {
-                p.x = p.x.clamp(margin, WORLD_W - margin);
-                p.y = p.y.clamp(margin, WORLD_H - margin);
-            }
-        }
-    }
+    let points_in = sample_points(n, world_w, world_h, &mut rng);
 
     // 2. Order-preserving bulk load → vertex index == input point index.
     //    Duplicates collapse to the lowest index (FMG-style), so cell ids stay
@@ -203,10 +269,22 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
         .iter()
         .map(|p| Point2::new(p.x + small_jitter(&mut rng), p.y + small_jitter(&mut rng)))
         .collect();
+    triangulate(jittered, world_w, world_h)
+}
+
+/// Steps 2–6 of `build_region`: triangulate `points` (already in general
+/// position, inside the world rectangle), walk the Voronoi dual into CSR
+/// arrays, clamp hull vertices to the world edge and build the spacing index.
+/// Shared by the sampled builders and `build_from_points`.
+fn triangulate(jittered: Vec<Point2<f64>>, world_w: f64, world_h: f64) -> Mesh {
     let tris: DelaunayTriangulation<Point2<f64>> =
         DelaunayTriangulation::bulk_load_stable(jittered)
             .expect("bulk_load_stable only fails on < 3 points or all-collinear input");
+    voronoi_mesh(&tris, world_w, world_h)
+}
 
+/// Steps 3–6 of `triangulate`, over a loaded triangulation.
+fn voronoi_mesh(tris: &DelaunayTriangulation<Point2<f64>>, world_w: f64, world_h: f64) -> Mesh {
     // spade may deduplicate coincident points; the resulting triangulation has
     // `num_vertices() <= n`. We keep its vertex indices as the canonical cell
     // ids (this is the documented behavior — `bulk_load_stable` keeps the
@@ -270,7 +348,7 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
                 VoronoiVertex::Outer(_) => {
                     border = true;
                     let dir = edge.direction_vector();
-                    let clamped = clamp_to_world(cell_pos, dir);
+                    let clamped = clamp_to_world(cell_pos, dir, world_w, world_h);
                     let id = v_positions.len() as u32;
                     v_positions.push(clamped);
                     id
@@ -317,16 +395,12 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
     //    do a linear nearest-cell search — O(N · slots) = O(N · N) = O(N²). At
     //    60k this is ~7e9 ops (~couple seconds), acceptable at build time; a KD-
     //    tree could make it O(N log N) if a later phase needs faster mesh builds.
-    let aspect = WORLD_W / WORLD_H;
-    let cells_x = ((num_cells as f64).sqrt() * (aspect.sqrt())).round() as u32;
-    let cells_x = cells_x.max(1);
-    let cells_y = ((cells_x as f64) / aspect).round() as u32;
-    let cells_y = cells_y.max(1);
-    let spacing = build_spacing(&points, cells_x, cells_y);
+    let (cells_x, cells_y) = spacing_grid_dims(num_cells, world_w, world_h);
+    let spacing = build_spacing(&points, cells_x, cells_y, world_w, world_h);
 
-    Mesh {
+    Mesh::new(
         points,
-        cells: Cells {
+        Cells {
             v: v_flat,
             c: c_flat,
             i: i_arr,
@@ -335,12 +409,557 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
             cells_x,
             cells_y,
         },
-        vertices: Vertices { p: v_positions },
-        world_w: WORLD_W,
-        world_h: WORLD_H,
+        Vertices { p: v_positions },
+        world_w,
+        world_h,
+    )
+}
+
+/// Why `build_from_points` rejected a point list. Indices refer to the
+/// caller's input order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum PointsError {
+    /// Fewer than 3 points — no triangulation exists.
+    TooFew { count: usize },
+    /// `world_w` / `world_h` is not a positive finite number.
+    BadWorldSize,
+    /// A coordinate is NaN or infinite.
+    NonFinite { index: usize },
+    /// A point lies outside `[0, world_w] × [0, world_h]`.
+    OutOfBounds { index: usize },
+    /// A point repeats an earlier one exactly.
+    Duplicate { index: usize, first: usize },
+    /// Every point lies on one line — the Voronoi cells would be open strips.
+    Collinear,
+    /// The point list is not an array of `[x, y]` pairs.
+    Malformed { message: String },
+}
+
+impl std::fmt::Display for PointsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PointsError::TooFew { count } => write!(f, "need at least 3 points, got {count}"),
+            PointsError::BadWorldSize => write!(f, "world size must be positive and finite"),
+            PointsError::NonFinite { index } => write!(f, "point {index} is not finite"),
+            PointsError::OutOfBounds { index } => write!(f, "point {index} is outside the world"),
+            PointsError::Duplicate { index, first } => {
+                write!(f, "point {index} duplicates point {first}")
+            }
+            PointsError::Collinear => write!(f, "all points are collinear"),
+            PointsError::Malformed { message } => write!(f, "malformed point list: {message}"),
+        }
+    }
+}
+
+/// Build a flat mesh over caller-supplied seed points (hand-placed, or
+/// imported from another generator) instead of sampling them.
+///
+/// Runs the same triangulation, hull clamping, spacing index and CSR packing
+/// as `build`, but applies **no** jitter or relaxation: cell `i` sits exactly
+/// at `points[i]`, so landmark coordinates are pinned and cell ids follow the
+/// input order. Because nothing nudges the input into general position,
+/// inputs the sampled path would silently repair are rejected instead — see
+/// `PointsError`. Co-circular points (e.g. a regular lattice) are fine; spade
+/// breaks the tie deterministically.
+pub fn build_from_points(
+    points: &[[f64; 2]],
+    world_w: f64,
+    world_h: f64,
+) -> Result<Mesh, PointsError> {
+    if !(world_w.is_finite() && world_h.is_finite() && world_w > 0.0 && world_h > 0.0) {
+        return Err(PointsError::BadWorldSize);
+    }
+    if points.len() < 3 {
+        return Err(PointsError::TooFew {
+            count: points.len(),
+        });
+    }
+    // BTreeMap on the bit patterns (all finite, so `+0.0`/`-0.0` is the only
+    // alias — normalized by adding 0.0) → first index of each position.
+    let mut seen: BTreeMap<(u64, u64), usize> = BTreeMap::new();
+    for (index, &[x, y]) in points.iter().enumerate() {
+        if !(x.is_finite() && y.is_finite()) {
+            return Err(PointsError::NonFinite { index });
+        }
+        if !((0.0..=world_w).contains(&x) && (0.0..=world_h).contains(&y)) {
+            return Err(PointsError::OutOfBounds { index });
+        }
+        let key = ((x + 0.0).to_bits(), (y + 0.0).to_bits());
+        if let Some(&first) = seen.get(&key) {
+            return Err(PointsError::Duplicate { index, first });
+        }
+        seen.insert(key, index);
+    }
+    // Collinear as spade's exact predicates judge it: a float cross product
+    // can call exactly collinear points a triangle, and spade then builds a
+    // triangulation without faces.
+    let pts = points.iter().map(|&[x, y]| Point2::new(x, y)).collect();
+    let tris: DelaunayTriangulation<Point2<f64>> =
+        DelaunayTriangulation::bulk_load_stable(pts).expect("points are finite and distinct");
+    if tris.all_vertices_on_line() {
+        return Err(PointsError::Collinear);
+    }
+    Ok(voronoi_mesh(&tris, world_w, world_h))
+}
+
+/// Step 1 of `build`: `n` well-spaced seed points in the world rectangle
+/// (Poisson-disk + Lloyd for `n > 12`, uniform below), clamped a hair inside
+/// the world edge. Shared by every rectangular topology so the same seed
+/// yields the same point layout whether or not the map wraps.
+fn sample_points(n: usize, world_w: f64, world_h: f64, rng: &mut StdRng) -> Vec<Point2<f64>> {
+    let mut points_in: Vec<Point2<f64>> = if n <= 12 {
+        // NOTE: we skip the Lloyd relaxation here. At N ≤ 12 the relaxation
+        // centroid step collapses near-collinear triples into coincident
+        // points (the 4-point seed=42 case produced two degenerate cells with
+        // k=2 after 3 Lloyd passes). Uniform random + jitter already lands in
+        // general position with probability 1; Lloyd only makes it worse at
+        // this scale. For N > 12 the Poisson-disk branch keeps Lloyd (it's the
+        // whole point of blue-noise spacing there).
+        let mut pts = Vec::with_capacity(n);
+        for _ in 0..n {
+            let x = rng.gen_range(0.0..world_w);
+            let y = rng.gen_range(0.0..world_h);
+            pts.push(Point2::new(x, y));
+        }
+        pts
+    } else {
+        poisson_disk_sample(n, world_w, world_h, rng)
+    };
+    // Clamp to a tiny margin inside the world edge so that the small_jitter
+    // applied later (±5e-6) cannot push points outside [0, world_w]×[0, world_h].
+    let margin = 1e-5;
+    for p in &mut points_in {
+        p.x = p.x.clamp(margin, world_w - margin);
+        p.y = p.y.clamp(margin, world_h - margin);
+    }
+    // Lloyd relaxation only runs on the Poisson-disk branch (N > 12); see the
+    // N ≤ 12 fallback above for the rationale.
+    if n > 12 {
+        for _ in 0..3 {
+            points_in = lloyd_relax(&points_in, 0.5, world_w, world_h, rng);
+            // Re-clamp after each relaxation step (Lloyd can drift points out).
+            for p in &mut points_in {
+                p.x = p.x.clamp(margin, world_w - margin);
+                p.y = p.y.clamp(margin, world_h - margin);
+            }
+        }
+    }
+    points_in
+}
+
+/// Build a mesh for the requested `topology`. `Topology::Flat` is exactly
+/// `build`; the others dispatch to `build_cylinder` / `build_globe`.
+pub fn build_with_topology(cell_count: u32, seed: u32, topology: Topology) -> Mesh {
+    match topology {
+        Topology::Flat => build(cell_count, seed),
+        Topology::Cylinder => build_cylinder(cell_count, seed),
+        Topology::Globe => build_globe(cell_count, seed),
+    }
+}
+
+/// Generate a deterministic Voronoi mesh that wraps east–west.
+///
+/// Seed points are sampled exactly as in `build` (same seed → same layout).
+/// To make the triangulation periodic in `x`, every point within a few cell
+/// spacings of the west edge gets a ghost copy shifted by `+world_w`, and
+/// every point near the east edge a copy shifted by `−world_w`. A real cell
+/// next to the seam then sees its cross-seam neighbours as ghosts, and each
+/// ghost maps back to the real cell it copies.
+///
+/// The top and bottom edges are closed with two straight *frame* rows of
+/// points half a cell spacing outside the world. Without them the hull along
+/// those edges is made of near-collinear real points whose long hull-cap
+/// edges would reach ghosts on one side of the seam but not the other
+/// (asymmetric adjacency). With the frame every real cell is interior: ring
+/// entries facing a frame point are dropped, which leaves exactly the flat
+/// mesh's border convention — the last kept vertex before the gap (the
+/// triangle shared with the next real neighbour) is the single boundary
+/// vertex, clamped onto `y = 0` / `y = world_h`, and the cell is flagged in
+/// `cells.b`. Only the `N` real cells are emitted, so the wire format is
+/// unchanged. (A boundary sliver with only two real neighbours keeps one
+/// extra boundary vertex, listing the preceding neighbour twice, so it still
+/// closes into a triangle.)
+///
+/// Voronoi vertices of seam cells keep their unwrapped position (`x` may lie
+/// slightly outside `[0, world_w]`) so every polygon stays contiguous; a
+/// renderer draws it once and lets the world edge clip it, or again shifted
+/// by `±world_w`. Vertex ids are not shared across the seam (the two sides
+/// see the same circumcenter at positions `world_w` apart).
+///
+/// `cell_count` is clamped to `[32, 1_000_000]`: below that the band is
+/// only a handful of cells wide and a cell could neighbour both a point and
+/// its own ghost.
+pub fn build_cylinder(cell_count: u32, seed: u32) -> Mesh {
+    let n = cell_count.clamp(32, 1_000_000) as usize;
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let points_in = sample_points(n, WORLD_W, WORLD_H, &mut rng);
+    let mut extended: Vec<Point2<f64>> = points_in
+        .iter()
+        .map(|p| Point2::new(p.x + small_jitter(&mut rng), p.y + small_jitter(&mut rng)))
+        .collect();
+
+    // Ghost band: 4 mean cell spacings is comfortably wider than any
+    // Voronoi neighbourhood of a Lloyd-relaxed point set.
+    let cell_spacing = (WORLD_W * WORLD_H / n as f64).sqrt();
+    let band = (4.0 * cell_spacing).min(WORLD_W);
+    // `ghost_of[k]` is the real cell that extended point `n + k` copies.
+    let mut ghost_of: Vec<usize> = Vec::new();
+    for id in 0..n {
+        let p = extended[id];
+        if points_in[id].x < band {
+            ghost_of.push(id);
+            extended.push(Point2::new(p.x + WORLD_W, p.y));
+        }
+        if points_in[id].x > WORLD_W - band {
+            ghost_of.push(id);
+            extended.push(Point2::new(p.x - WORLD_W, p.y));
+        }
+    }
+    let frame_start = extended.len();
+    // Frame rows span the ghost band plus one spacing on either side.
+    let cols = (WORLD_W / cell_spacing).ceil().max(1.0) as i64;
+    let step = WORLD_W / cols as f64;
+    let reach = ((band + cell_spacing) / step).ceil() as i64;
+    for k in -reach..=cols + reach {
+        let x = k as f64 * step;
+        extended.push(Point2::new(x, -0.5 * cell_spacing));
+        extended.push(Point2::new(x, WORLD_H + 0.5 * cell_spacing));
+    }
+    let total = extended.len();
+
+    let tris: DelaunayTriangulation<Point2<f64>> =
+        DelaunayTriangulation::bulk_load_stable(extended)
+            .expect("bulk_load_stable only fails on < 3 points or all-collinear input");
+    assert_eq!(
+        tris.num_vertices(),
+        total,
+        "build_cylinder: ghost points collided"
+    );
+    // Real cell id behind an extended vertex index; `None` for frame points.
+    let real = |idx: usize| -> Option<u32> {
+        if idx < n {
+            Some(idx as u32)
+        } else if idx < frame_start {
+            Some(ghost_of[idx - n] as u32)
+        } else {
+            None
+        }
+    };
+
+    let mut voronoi_vertex_ids: BTreeMap<usize, u32> = BTreeMap::new();
+    let mut v_flat: Vec<u32> = Vec::with_capacity(n * 6);
+    let mut c_flat: Vec<u32> = Vec::with_capacity(n * 6);
+    let mut i_arr: Vec<u32> = Vec::with_capacity(n + 1);
+    let mut b_arr: Vec<u8> = vec![0; n];
+    let mut v_positions: Vec<[f64; 2]> = Vec::new();
+    #[allow(clippy::needless_range_loop)]
+    for cell_id in 0..n {
+        i_arr.push(v_flat.len() as u32);
+        let vhandle = tris.vertex(FixedVertexHandle::from_index(cell_id));
+        // Ring entries: (circumcenter face key, position, real neighbour).
+        let mut ring: Vec<(usize, [f64; 2], Option<u32>)> = Vec::new();
+        for edge in vhandle.as_voronoi_face().adjacent_edges() {
+            // Every real cell is enclosed by the frame, so all its Voronoi
+            // vertices are finite circumcenters.
+            let VoronoiVertex::Inner(face) = edge.from() else {
+                unreachable!("build_cylinder: real cell on the hull");
+            };
+            let de = edge.as_delaunay_edge();
+            let n0 = de.from().fix().index();
+            let n1 = de.to().fix().index();
+            let p = face.circumcenter();
+            ring.push((
+                face.fix().index(),
+                [p.x, p.y.clamp(0.0, WORLD_H)],
+                real(if n0 == cell_id { n1 } else { n0 }),
+            ));
+        }
+        let border = ring.iter().any(|e| e.2.is_none());
+        let mut kept: Vec<(usize, [f64; 2], u32)> = Vec::with_capacity(ring.len());
+        for (k, &(key, pos, nb)) in ring.iter().enumerate() {
+            match nb {
+                Some(nb) => kept.push((key, pos, nb)),
+                // A sliver touching the boundary can have only two real
+                // neighbours; keep the vertex where its frame gap starts so
+                // the polygon still has three corners. That boundary edge has
+                // no real cell behind it, so it repeats the neighbour before
+                // the gap (the only place a neighbour is listed twice).
+                None if ring.iter().filter(|e| e.2.is_some()).count() < 3 => {
+                    let prev = ring[(k + ring.len() - 1) % ring.len()].2;
+                    if let Some(prev) = prev {
+                        kept.push((key, pos, prev));
+                    }
+                }
+                None => {}
+            }
+        }
+        for (key, pos, nb) in kept {
+            let vv_id = *voronoi_vertex_ids.entry(key).or_insert_with(|| {
+                v_positions.push(pos);
+                (v_positions.len() - 1) as u32
+            });
+            v_flat.push(vv_id);
+            c_flat.push(nb);
+        }
+        b_arr[cell_id] = if border { 1 } else { 0 };
+    }
+    i_arr.push(v_flat.len() as u32);
+
+    let points: Vec<[f64; 2]> = (0..n)
+        .map(|i| {
+            let p = tris.vertex(FixedVertexHandle::from_index(i)).position();
+            [p.x, p.y]
+        })
+        .collect();
+    let (cells_x, cells_y) = spacing_grid_dims(n, WORLD_W, WORLD_H);
+    let spacing = build_spacing(&points, cells_x, cells_y, WORLD_W, WORLD_H);
+
+    let cells = Cells {
+        v: v_flat,
+        c: c_flat,
+        i: i_arr,
+        b: b_arr,
+        spacing,
+        cells_x,
+        cells_y,
+    };
+    Mesh {
+        topology: Topology::Cylinder,
+        ..Mesh::new(points, cells, Vertices { p: v_positions }, WORLD_W, WORLD_H)
     }
 }
 
+/// Generate a deterministic whole-planet Voronoi mesh on the unit sphere.
+///
+/// ## Algorithm
+///
+/// 1. Sample `cell_count` points on S² with a Fibonacci (golden-angle) spiral,
+///    which is already near-uniform in area, so no Lloyd pass is needed. A
+///    seeded longitude offset plus a small seeded per-point jitter make the
+///    layout depend on `seed` and keep the input in general position.
+/// 2. Spherical Delaunay via stereographic projection (the d3-geo-voronoi
+///    construction): project every point except point 0 from point 0 onto the
+///    plane through the origin. Stereographic projection maps circles to
+///    circles, so the planar Delaunay of the projected points is exactly the
+///    spherical Delaunay of those points; the triangles touching point 0 are
+///    the fan from point 0 to the planar convex hull.
+/// 3. Every triangle is oriented counter-clockwise as seen from outside the
+///    sphere; the Voronoi vertex of a triangle is its normalized 3D
+///    circumcenter (one vertex id per triangle, so ids are shared by all three
+///    cells without memoization).
+/// 4. Each cell walks its triangle fan in that orientation, emitting the same
+///    CSR `v`/`c`/`i` layout as `build`. Projected to `x = lon`, `y = −lat`
+///    the ring comes out clockwise, matching the flat mesh.
+///
+/// `cell_count` is clamped to `[12, 1_000_000]` (12 = the icosahedron, the
+/// smallest spiral that still gives every cell ≥ 3 neighbours comfortably).
+///
+/// Cells straddling the antimeridian have projected vertices on both the
+/// `x = 0` and `x = world_w` edges; 2D renderers that care should split those
+/// polygons, 3D renderers should use `lat_lon` instead.
+pub fn build_globe(cell_count: u32, seed: u32) -> Mesh {
+    let n = cell_count.clamp(12, 1_000_000) as usize;
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let sphere = fibonacci_sphere(n, &mut rng);
+    let triangles = spherical_delaunay(&sphere);
+
+    // Voronoi vertices: one per Delaunay triangle.
+    let v_positions: Vec<[f64; 2]> = triangles
+        .iter()
+        .map(|&[a, b, c]| {
+            let (pa, pb, pc) = (sphere[a as usize], sphere[b as usize], sphere[c as usize]);
+            let cc = normalize3(cross3(sub3(pb, pa), sub3(pc, pa)));
+            project_lat_lon(unit_to_lat_lon(cc))
+        })
+        .collect();
+
+    // Triangle fan around every cell: `fan[v]` holds `(x, y, t)` for each
+    // triangle `t = (v, x, y)` (counter-clockwise from outside).
+    let mut fan: Vec<Vec<(u32, u32, u32)>> = vec![Vec::new(); n];
+    for (t, &[a, b, c]) in triangles.iter().enumerate() {
+        let t = t as u32;
+        fan[a as usize].push((b, c, t));
+        fan[b as usize].push((c, a, t));
+        fan[c as usize].push((a, b, t));
+    }
+
+    let mut v_flat: Vec<u32> = Vec::with_capacity(n * 6);
+    let mut c_flat: Vec<u32> = Vec::with_capacity(n * 6);
+    let mut i_arr: Vec<u32> = Vec::with_capacity(n + 1);
+    for ring in &fan {
+        i_arr.push(v_flat.len() as u32);
+        // Start at the first-recorded triangle (deterministic: triangles are
+        // enumerated in spade's fixed face order) and step to the triangle
+        // sharing our trailing edge `(v, y)` until the ring closes. Triangle
+        // `t_k = (v, a_k, a_k+1)` contributes Voronoi vertex `circ(t_k)`, and
+        // the Voronoi edge `circ(t_k-1) → circ(t_k)` separates `v` from `a_k`,
+        // so that is the neighbour recorded alongside it — the same
+        // `c[k]` ↔ `(v[k-1], v[k])` pairing the planar builders produce.
+        let (first_x, mut y, mut t) = ring[0];
+        let mut x = first_x;
+        for _ in 0..ring.len() {
+            v_flat.push(t);
+            c_flat.push(x);
+            if y == first_x {
+                break;
+            }
+            let &(nx, ny, nt) = ring
+                .iter()
+                .find(|e| e.0 == y)
+                .expect("build_globe: spherical triangulation is not a closed manifold");
+            x = nx;
+            y = ny;
+            t = nt;
+        }
+    }
+    i_arr.push(v_flat.len() as u32);
+
+    let lat_lon: Vec<[f64; 2]> = sphere.iter().map(|&p| unit_to_lat_lon(p)).collect();
+    let points: Vec<[f64; 2]> = lat_lon.iter().map(|&ll| project_lat_lon(ll)).collect();
+
+    let (cells_x, cells_y) = spacing_grid_dims(n, WORLD_W, WORLD_H);
+    let spacing = build_spacing(&points, cells_x, cells_y, WORLD_W, WORLD_H);
+
+    let cells = Cells {
+        v: v_flat,
+        c: c_flat,
+        i: i_arr,
+        b: vec![0; n],
+        spacing,
+        cells_x,
+        cells_y,
+    };
+    Mesh {
+        topology: Topology::Globe,
+        lat_lon,
+        ..Mesh::new(points, cells, Vertices { p: v_positions }, WORLD_W, WORLD_H)
+    }
+}
+
+/// `n` seeded points on the unit sphere along a golden-angle spiral. Point 0
+/// sits next to the north pole, point `n − 1` next to the south pole.
+fn fibonacci_sphere(n: usize, rng: &mut StdRng) -> Vec<[f64; 3]> {
+    let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+    let lon_offset = rng.gen_range(0.0..2.0 * PI);
+    // Mean angular spacing between neighbouring points.
+    let step = (4.0 * PI / n as f64).sqrt();
+    let dz_band = 2.0 / n as f64;
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let z0 = 1.0 - (2.0 * i as f64 + 1.0) / n as f64;
+        let z = (z0 + rng.gen_range(-0.25..0.25) * dz_band).clamp(-1.0, 1.0);
+        let r = (1.0 - z * z).sqrt();
+        // Tangential jitter of ±¼ spacing; divided by the ring radius so it is
+        // an arc length, capped near the poles where `r → 0`.
+        let dlon = rng.gen_range(-0.25..0.25) * step / r.max(step);
+        let lon = i as f64 * golden_angle + lon_offset + dlon;
+        out.push([r * lon.cos(), r * lon.sin(), z]);
+    }
+    out
+}
+
+/// Delaunay triangulation of points on the unit sphere, each triangle
+/// counter-clockwise as seen from outside. See `build_globe` step 2.
+fn spherical_delaunay(sphere: &[[f64; 3]]) -> Vec<[u32; 3]> {
+    let pole = sphere[0];
+    // Orthonormal basis (e1, e2) of the plane perpendicular to `pole`.
+    let helper = if pole[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let e1 = normalize3(cross3(helper, pole));
+    let e2 = cross3(pole, e1);
+    let projected: Vec<Point2<f64>> = sphere[1..]
+        .iter()
+        .map(|&q| {
+            let d = 1.0 - dot3(q, pole);
+            Point2::new(dot3(q, e1) / d, dot3(q, e2) / d)
+        })
+        .collect();
+    let tris: DelaunayTriangulation<Point2<f64>> =
+        DelaunayTriangulation::bulk_load_stable(projected)
+            .expect("bulk_load_stable only fails on < 3 points or all-collinear input");
+    assert_eq!(
+        tris.num_vertices(),
+        sphere.len() - 1,
+        "spherical_delaunay: projected points collided"
+    );
+
+    let orient = |[a, b, c]: [u32; 3]| -> [u32; 3] {
+        let (pa, pb, pc) = (sphere[a as usize], sphere[b as usize], sphere[c as usize]);
+        if dot3(cross3(pa, pb), pc) < 0.0 {
+            [a, c, b]
+        } else {
+            [a, b, c]
+        }
+    };
+    // Projected vertex `k` is sphere point `k + 1`.
+    let mut out: Vec<[u32; 3]> = Vec::with_capacity(2 * sphere.len());
+    for face in tris.inner_faces() {
+        let [a, b, c] = face.vertices().map(|v| v.fix().index() as u32 + 1);
+        out.push(orient([a, b, c]));
+    }
+    for edge in tris.convex_hull() {
+        let a = edge.from().fix().index() as u32 + 1;
+        let b = edge.to().fix().index() as u32 + 1;
+        out.push(orient([0, a, b]));
+    }
+    out
+}
+
+/// `[lat, lon]` in degrees of a unit vector (`z` = north).
+fn unit_to_lat_lon(p: [f64; 3]) -> [f64; 2] {
+    let lat = p[2].clamp(-1.0, 1.0).asin().to_degrees();
+    let lon = p[1].atan2(p[0]).to_degrees();
+    [lat, lon]
+}
+
+/// Equirectangular projection of `[lat, lon]` into the world rectangle: west
+/// edge = −180°, north edge = +90°. Matches the flat-map latitude convention
+/// (`y = 0` is north) so `climate` reads a full-globe band off `y`.
+fn project_lat_lon([lat, lon]: [f64; 2]) -> [f64; 2] {
+    let x = (lon + 180.0) / 360.0 * WORLD_W;
+    let y = (90.0 - lat) / 180.0 * WORLD_H;
+    [x.clamp(0.0, WORLD_W), y.clamp(0.0, WORLD_H)]
+}
+
+fn dot3(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn sub3(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn normalize3(a: [f64; 3]) -> [f64; 3] {
+    let len = dot3(a, a).sqrt();
+    if len == 0.0 {
+        return a;
+    }
+    [a[0] / len, a[1] / len, a[2] / len]
+}
+
+/// `(cells_x, cells_y)` of the sampling grid for `num_cells` cells: `√N`
+/// columns, aspect-corrected so slots are roughly square.
+fn spacing_grid_dims(num_cells: usize, world_w: f64, world_h: f64) -> (u32, u32) {
+    let aspect = world_w / world_h;
+    let cells_x = ((num_cells as f64).sqrt() * (aspect.sqrt())).round() as u32;
+    let cells_x = cells_x.max(1);
+    let cells_y = ((cells_x as f64) / aspect).round() as u32;
+    let cells_y = cells_y.max(1);
+    (cells_x, cells_y)
+}
+
 /// Build the sampling grid: `spacing[slot]` = a cell id that lies in (or very
 /// near) each grid slot. O(N), not O(N²): we bucket each cell into its slot
 /// (`cell_id → slot_id`), then for any empty slot we fall back to the nearest
@@ -348,10 +967,16 @@ pub fn build(cell_count: u32, seed: u32) -> Mesh {
 /// heightmap's purpose — `find_grid_cell` only needs *a* cell near `(x, y)`,
 /// not provably the global nearest — and keeps 60k mesh builds at ~1s instead of
 /// 60s+ in debug.
-fn build_spacing(points: &[[f64; 2]], cells_x: u32, cells_y: u32) -> Vec<u32> {
+fn build_spacing(
+    points: &[[f64; 2]],
+    cells_x: u32,
+    cells_y: u32,
+    world_w: f64,
+    world_h: f64,
+) -> Vec<u32> {
     let n_slots = (cells_x as usize) * (cells_y as usize);
-    let sx = WORLD_W / cells_x as f64;
-    let sy = WORLD_H / cells_y as f64;
+    let sx = world_w / cells_x as f64;
+    let sy = world_h / cells_y as f64;
     // Slot id for each cell.
     let mut slot_of_cell: Vec<u32> = Vec::with_capacity(points.len());
     for &[px, py] in points {
@@ -414,7 +1039,7 @@ fn build_spacing(points: &[[f64; 2]], cells_x: u32, cells_y: u32) -> Vec<u32> {
 /// Returns exactly `n` points (or fewer if the world is too small to fit
 /// `n` points at the requested `r_min`, which only happens at extreme
 /// `n` / tiny world ratios — the caller clamps `n` to `[4, 1_000_000]`).
-fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
+fn poisson_disk_sample(n: usize, world_w: f64, world_h: f64, rng: &mut StdRng) -> Vec<Point2<f64>> {
     if n == 0 {
         return vec![];
     }
@@ -423,15 +1048,15 @@ fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
     // that so the final count lands near n (Bridson's algorithm is a
     // rejection sampler — it stops when the active list is empty, which
     // happens once the disk is saturated).
-    let area = WORLD_W * WORLD_H;
+    let area = world_w * world_h;
     let r_min = (area / n as f64).sqrt() * 0.9;
     let r_min = r_min.max(1.0); // guard against degenerate tiny worlds
 
     // Grid: cell size = r_min / √2 ensures any two points within r_min are
     // in the same or adjacent cells (so a 3×3 neighbourhood check suffices).
     let cell_size = r_min / 2.0_f64.sqrt();
-    let cols = (WORLD_W / cell_size).ceil() as usize + 1;
-    let rows = (WORLD_H / cell_size).ceil() as usize + 1;
+    let cols = (world_w / cell_size).ceil() as usize + 1;
+    let rows = (world_h / cell_size).ceil() as usize + 1;
 
     // `grid[s]` = list of point indices in cell `s` (row-major).
     let mut grid: Vec<Vec<usize>> = vec![vec![]; cols * rows];
@@ -471,8 +1096,8 @@ fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
         };
 
     // Seed: a random point in the world rectangle.
-    let sx = rng.gen_range(0.0..WORLD_W);
-    let sy = rng.gen_range(0.0..WORLD_H);
+    let sx = rng.gen_range(0.0..world_w);
+    let sy = rng.gen_range(0.0..world_h);
     let seed = Point2::new(sx, sy);
     insert(seed, &mut grid, &mut points);
 
@@ -493,7 +1118,7 @@ fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
             let dist = rng.gen_range(r_min..2.0 * r_min);
             let cx = ap.x + dist * angle.cos();
             let cy = ap.y + dist * angle.sin();
-            if cx < 0.0 || cx >= WORLD_W || cy < 0.0 || cy >= WORLD_H {
+            if cx < 0.0 || cx >= world_w || cy < 0.0 || cy >= world_h {
                 continue;
             }
             let cand = Point2::new(cx, cy);
@@ -515,8 +1140,8 @@ fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
     // remainder with uniform random points, which is deterministic given the
     // seed (§4) and preserves the exact cell count contract.
     while points.len() < n {
-        let x = rng.gen_range(0.0..WORLD_W);
-        let y = rng.gen_range(0.0..WORLD_H);
+        let x = rng.gen_range(0.0..world_w);
+        let y = rng.gen_range(0.0..world_h);
         points.push(Point2::new(x, y));
     }
 
@@ -535,7 +1160,13 @@ fn poisson_disk_sample(n: usize, rng: &mut StdRng) -> Vec<Point2<f64>> {
 ///
 /// The RNG is unused (reserved for future tie-breaking); the function is
 /// deterministic given the input points.
-fn lloyd_relax(points: &[Point2<f64>], step: f64, _rng: &mut StdRng) -> Vec<Point2<f64>> {
+fn lloyd_relax(
+    points: &[Point2<f64>],
+    step: f64,
+    world_w: f64,
+    world_h: f64,
+    _rng: &mut StdRng,
+) -> Vec<Point2<f64>> {
     let n = points.len();
     if n < 3 {
         return points.to_vec();
@@ -545,12 +1176,12 @@ fn lloyd_relax(points: &[Point2<f64>], step: f64, _rng: &mut StdRng) -> Vec<Poin
     // its neighbours; any neighbour whose bisector intersects the cell must be
     // within ~2× the typical spacing. We use a generous cell size so the 3×3
     // neighbourhood covers all relevant points.
-    let area = WORLD_W * WORLD_H;
+    let area = world_w * world_h;
     let r_typ = (area / n as f64).sqrt();
     let cell_size = r_typ * 2.5;
     let cell_size = cell_size.max(1.0);
-    let cols = (WORLD_W / cell_size).ceil() as usize + 1;
-    let rows = (WORLD_H / cell_size).ceil() as usize + 1;
+    let cols = (world_w / cell_size).ceil() as usize + 1;
+    let rows = (world_h / cell_size).ceil() as usize + 1;
 
     // Build the grid: `grid[s]` = list of point indices in cell `s`.
     let mut grid: Vec<Vec<usize>> = vec![vec![]; cols * rows];
@@ -590,9 +1221,9 @@ fn lloyd_relax(points: &[Point2<f64>], step: f64, _rng: &mut StdRng) -> Vec<Poin
         // Start with the world rectangle as the initial cell polygon.
         let mut poly: Vec<[f64; 2]> = vec![
             [0.0, 0.0],
-            [WORLD_W, 0.0],
-            [WORLD_W, WORLD_H],
-            [0.0, WORLD_H],
+            [world_w, 0.0],
+            [world_w, world_h],
+            [0.0, world_h],
         ];
         for &j in &candidates {
             let q = points[j];
@@ -626,7 +1257,7 @@ fn lloyd_relax(points: &[Point2<f64>], step: f64, _rng: &mut StdRng) -> Vec<Poin
                 cy /= 6.0 * area;
                 let nx = p.x + (cx - p.x) * step;
                 let ny = p.y + (cy - p.y) * step;
-                new_points.push(Point2::new(nx.clamp(0.0, WORLD_W), ny.clamp(0.0, WORLD_H)));
+                new_points.push(Point2::new(nx.clamp(0.0, world_w), ny.clamp(0.0, world_h)));
             } else {
                 new_points.push(p);
             }
@@ -692,7 +1323,7 @@ fn small_jitter(rng: &mut StdRng) -> f64 {
 /// `dir` until we hit one of the rectangle's four sides, then return that
 /// intersection. This guarantees the clamped vertex lies outside the cell (the
 /// infinite ray did) and on the world boundary, so the polygon still closes.
-fn clamp_to_world(cell_pos: Point2<f64>, dir: Point2<f64>) -> [f64; 2] {
+fn clamp_to_world(cell_pos: Point2<f64>, dir: Point2<f64>, world_w: f64, world_h: f64) -> [f64; 2] {
     // Find the smallest t > 0 such that cell_pos + t*dir hits a rectangle side.
     // Solve for each of the four sides and take the minimum positive t.
     let (px, py) = (cell_pos.x, cell_pos.y);
@@ -705,9 +1336,9 @@ fn clamp_to_world(cell_pos: Point2<f64>, dir: Point2<f64>) -> [f64; 2] {
             best_t = t;
         }
     }
-    // x = WORLD_W
+    // x = world_w
     if dx > 0.0 {
-        let t = (WORLD_W - px) / dx;
+        let t = (world_w - px) / dx;
         if t > 0.0 && t < best_t {
             best_t = t;
         }
@@ -719,9 +1350,9 @@ fn clamp_to_world(cell_pos: Point2<f64>, dir: Point2<f64>) -> [f64; 2] {
             best_t = t;
         }
     }
-    // y = WORLD_H
+    // y = world_h
     if dy > 0.0 {
-        let t = (WORLD_H - py) / dy;
+        let t = (world_h - py) / dy;
         if t > 0.0 && t < best_t {
             best_t = t;
         }
@@ -734,7 +1365,7 @@ fn clamp_to_world(cell_pos: Point2<f64>, dir: Point2<f64>) -> [f64; 2] {
     let x = px + best_t * dx;
     let y = py + best_t * dy;
     // Clamp defensively in case of FP slop right at the boundary.
-    [x.clamp(0.0, WORLD_W), y.clamp(0.0, WORLD_H)]
+    [x.clamp(0.0, world_w), y.clamp(0.0, world_h)]
 }
 
 /// `#[wasm_bindgen]` entry point. Serializes the `Mesh` to a `JsValue` via
@@ -745,6 +1376,34 @@ pub fn generate_mesh(cell_count: u32, seed: u32) -> JsValue {
     serde_wasm_bindgen::to_value(&mesh).expect("mesh serde to JsValue")
 }
 
+/// `#[wasm_bindgen]` entry point for `build_with_topology`. `topology_js` is
+/// the variant name (`"Flat"` / `"Cylinder"` / `"Globe"`).
+pub fn generate_mesh_with_topology(cell_count: u32, seed: u32, topology_js: JsValue) -> JsValue {
+    let topology: Topology = serde_wasm_bindgen::from_value(topology_js)
+        .expect("generate_mesh_with_topology: failed to deserialize Topology");
+    let mesh = build_with_topology(cell_count, seed, topology);
+    serde_wasm_bindgen::to_value(&mesh).expect("mesh serde to JsValue")
+}
+
+/// `#[wasm_bindgen]` entry point for `build_from_points`. `points_js` is an
+/// array of `[x, y]` pairs. Throws a `PointsError` object (`{ kind, ... }`)
+/// if the point list is rejected.
+pub fn generate_mesh_from_points(
+    points_js: JsValue,
+    world_w: f64,
+    world_h: f64,
+) -> Result<JsValue, JsValue> {
+    let reject =
+        |e: PointsError| serde_wasm_bindgen::to_value(&e).expect("PointsError serde to JsValue");
+    let points: Vec<[f64; 2]> = serde_wasm_bindgen::from_value(points_js).map_err(|e| {
+        reject(PointsError::Malformed {
+            message: e.to_string(),
+        })
+    })?;
+    let mesh = build_from_points(&points, world_w, world_h).map_err(reject)?;
+    Ok(serde_wasm_bindgen::to_value(&mesh).expect("mesh serde to JsValue"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -982,22 +1641,22 @@ mod tests {
     fn clamp_to_world_hits_each_side() {
         // Ray pointing west (dx<0) from the interior → hits x=0.
         let p = Point2::new(5000.0, 4000.0);
-        let [x, y] = clamp_to_world(p, Point2::new(-1.0, 0.0));
+        let [x, y] = clamp_to_world(p, Point2::new(-1.0, 0.0), WORLD_W, WORLD_H);
         assert!((x - 0.0).abs() < 1e-9, "west ray should hit x=0, got {x}");
         assert!((y - 4000.0).abs() < 1e-9, "y unchanged, got {y}");
         // Ray pointing east (dx>0) → hits x=WORLD_W.
-        let [x, y] = clamp_to_world(p, Point2::new(1.0, 0.0));
+        let [x, y] = clamp_to_world(p, Point2::new(1.0, 0.0), WORLD_W, WORLD_H);
         assert!(
             (x - WORLD_W).abs() < 1e-9,
             "east ray should hit x=WORLD_W, got {x}"
         );
         assert!((y - 4000.0).abs() < 1e-9);
         // Ray pointing north (dy<0) → hits y=0.
-        let [x, y] = clamp_to_world(p, Point2::new(0.0, -1.0));
+        let [x, y] = clamp_to_world(p, Point2::new(0.0, -1.0), WORLD_W, WORLD_H);
         assert!((x - 5000.0).abs() < 1e-9);
         assert!((y - 0.0).abs() < 1e-9, "north ray should hit y=0, got {y}");
         // Ray pointing south (dy>0) → hits y=WORLD_H.
-        let [x, y] = clamp_to_world(p, Point2::new(0.0, 1.0));
+        let [x, y] = clamp_to_world(p, Point2::new(0.0, 1.0), WORLD_W, WORLD_H);
         assert!((x - 5000.0).abs() < 1e-9);
         assert!(
             (y - WORLD_H).abs() < 1e-9,
@@ -1013,7 +1672,7 @@ mod tests {
         // From near the west edge, a 45° SE ray should hit x=0? No — dx>0 so it
         // travels east; nearest side is the east wall at t=(WORLD_W-100)/1.
         let p = Point2::new(100.0, 4000.0);
-        let [x, y] = clamp_to_world(p, Point2::new(1.0, 1.0));
+        let [x, y] = clamp_to_world(p, Point2::new(1.0, 1.0), WORLD_W, WORLD_H);
         // East wall is far (9900 units); south wall is far (4000 units) but dy
         // and dx are both 1.0 so east (t=9900) vs south (t=4000): south wins.
         assert!(
@@ -1035,7 +1694,7 @@ mod tests {
     #[test]
     fn clamp_to_world_zero_direction_falls_back() {
         let p = Point2::new(1234.0, 5678.0);
-        let [x, y] = clamp_to_world(p, Point2::new(0.0, 0.0));
+        let [x, y] = clamp_to_world(p, Point2::new(0.0, 0.0), WORLD_W, WORLD_H);
         assert!((x - 1234.0).abs() < 1e-9 && (y - 5678.0).abs() < 1e-9);
         assert!(x.is_finite() && y.is_finite());
     }
@@ -1074,7 +1733,7 @@ mod tests {
             [2.5 * sx + 100.0, WORLD_H * 0.5], // slot 2
             [3.5 * sx + 100.0, WORLD_H * 0.5], // slot 3
         ];
-        let spacing = build_spacing(&points, cells_x, cells_y);
+        let spacing = build_spacing(&points, cells_x, cells_y, WORLD_W, WORLD_H);
         assert_eq!(spacing.len(), 4);
         // Every entry must be a valid cell id (0 or 1 here).
         for &c in &spacing {
@@ -1272,4 +1931,316 @@ mod tests {
             assert!(seen.insert(key), "duplicate cell point at ({x},{y})");
         }
     }
+
+    /// Globe mesh: every cell is closed by real neighbours (no hull, so no
+    /// border cells), adjacency is symmetric, and the triangulation satisfies
+    /// Euler's formula for a sphere: cells − edges + voronoi_vertices = 2.
+    #[test]
+    fn globe_is_closed_manifold() {
+        let mesh = build_globe(2000, 7);
+        let n = mesh.points.len();
+        assert_eq!(n, 2000);
+        assert_eq!(mesh.topology, Topology::Globe);
+        assert!(mesh.cells.b.iter().all(|&b| b == 0));
+        let mut half_edges = 0usize;
+        for cell in 0..n {
+            let neigh = mesh.cells.neighbors_of_cell(cell);
+            assert!(
+                neigh.len() >= 3,
+                "cell {cell} has {} neighbours",
+                neigh.len()
+            );
+            half_edges += neigh.len();
+            for &nb in neigh {
+                assert_ne!(nb as usize, cell);
+                assert!(
+                    mesh.cells
+                        .neighbors_of_cell(nb as usize)
+                        .contains(&(cell as u32)),
+                    "asymmetric adjacency {cell}→{nb}"
+                );
+            }
+        }
+        let edges = half_edges / 2;
+        let faces = mesh.vertices.p.len();
+        assert_eq!(n as i64 - edges as i64 + faces as i64, 2);
+    }
+
+    /// Every cell ring references each neighbour once, and neighbour `c[k]`
+    /// shares the ring's edge `(v[k-1], v[k])` (the planar meshes' pairing).
+    #[test]
+    fn globe_rings_are_consistent() {
+        let mesh = build_globe(500, 3);
+        let i = &mesh.cells.i;
+        for cell in 0..mesh.points.len() {
+            let (lo, hi) = (i[cell] as usize, i[cell + 1] as usize);
+            let verts = &mesh.cells.v[lo..hi];
+            let neigh = &mesh.cells.c[lo..hi];
+            let unique: std::collections::BTreeSet<u32> = neigh.iter().copied().collect();
+            assert_eq!(unique.len(), neigh.len(), "cell {cell} repeats a neighbour");
+            for k in 0..verts.len() {
+                let nb = neigh[k] as usize;
+                let nb_verts = &mesh.cells.v[i[nb] as usize..i[nb + 1] as usize];
+                let prev = verts[(k + verts.len() - 1) % verts.len()];
+                assert!(
+                    nb_verts.contains(&verts[k]) && nb_verts.contains(&prev),
+                    "cell {cell} edge {k} not shared"
+                );
+            }
+        }
+    }
+
+    /// `lat_lon` is populated per cell, spans both hemispheres and all
+    /// longitudes, and `points` is its equirectangular projection.
+    #[test]
+    fn globe_lat_lon_matches_projection() {
+        let mesh = build_globe(3000, 11);
+        assert_eq!(mesh.lat_lon.len(), mesh.points.len());
+        let (mut min_lat, mut max_lat) = (f64::MAX, f64::MIN);
+        let (mut min_lon, mut max_lon) = (f64::MAX, f64::MIN);
+        for (k, &[lat, lon]) in mesh.lat_lon.iter().enumerate() {
+            assert!((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon));
+            let [x, y] = mesh.points[k];
+            assert!((x - (lon + 180.0) / 360.0 * WORLD_W).abs() < 1e-6);
+            assert!((y - (90.0 - lat) / 180.0 * WORLD_H).abs() < 1e-6);
+            min_lat = min_lat.min(lat);
+            max_lat = max_lat.max(lat);
+            min_lon = min_lon.min(lon);
+            max_lon = max_lon.max(lon);
+        }
+        assert!(max_lat > 85.0 && min_lat < -85.0);
+        assert!(max_lon > 170.0 && min_lon < -170.0);
+        // Flat meshes carry no lat/lon.
+        assert!(build(100, 1).lat_lon.is_empty());
+    }
+
+    /// Same seed → identical globe; different seed → different layout.
+    #[test]
+    fn globe_deterministic() {
+        let a = build_globe(1000, 5);
+        let b = build_globe(1000, 5);
+        assert_eq!(a.points, b.points);
+        assert_eq!(a.cells.v, b.cells.v);
+        assert_eq!(a.cells.c, b.cells.c);
+        assert_eq!(a.vertices.p, b.vertices.p);
+        assert_ne!(a.points, build_globe(1000, 6).points);
+    }
+
+    /// The smallest globe still closes (clamped to 12 cells).
+    #[test]
+    fn globe_minimum_cell_count() {
+        let mesh = build_globe(4, 42);
+        assert_eq!(mesh.points.len(), 12);
+        for cell in 0..12 {
+            assert!(mesh.cells.neighbors_of_cell(cell).len() >= 3);
+        }
+    }
+
+    /// `build_with_topology(Flat)` is exactly `build`, and a flat mesh without
+    /// the new fields on the wire still deserializes.
+    #[test]
+    fn flat_topology_is_build() {
+        let a = build_with_topology(800, 9, Topology::Flat);
+        let b = build(800, 9);
+        assert_eq!(a.points, b.points);
+        assert_eq!(a.cells.c, b.cells.c);
+        let mut json = serde_json::to_value(&b).unwrap();
+        let obj = json.as_object_mut().unwrap();
+        obj.remove("topology");
+        obj.remove("lat_lon");
+        let back: Mesh = serde_json::from_value(json).unwrap();
+        assert_eq!(back.topology, Topology::Flat);
+    }
+
+    /// Caller-supplied points are used verbatim (no jitter) and in input
+    /// order; the rest of the mesh is as consistent as a sampled one.
+    #[test]
+    fn from_points_pins_cells_exactly() {
+        let sampled = build(500, 3);
+        let mut points = sampled.points.clone();
+        points[0] = [1234.5, 678.25];
+        points[1] = [0.0, 0.0];
+        let mesh = build_from_points(&points, WORLD_W, WORLD_H).unwrap();
+        assert_eq!(mesh.points, points);
+        assert_eq!(mesh.topology, Topology::Flat);
+        assert_eq!(mesh.cells.i.len(), points.len() + 1);
+        for cell in 0..points.len() {
+            let neigh = mesh.cells.neighbors_of_cell(cell);
+            assert!(!neigh.is_empty());
+            for &nb in neigh {
+                assert!(mesh
+                    .cells
+                    .neighbors_of_cell(nb as usize)
+                    .contains(&(cell as u32)));
+            }
+        }
+        for &[x, y] in &mesh.vertices.p {
+            assert!(x.is_finite() && y.is_finite());
+        }
+        assert!(mesh
+            .cells
+            .spacing
+            .iter()
+            .all(|&c| (c as usize) < points.len()));
+        // Identical input → identical mesh.
+        let again = build_from_points(&points, WORLD_W, WORLD_H).unwrap();
+        assert_eq!(mesh.cells.v, again.cells.v);
+        assert_eq!(mesh.vertices.p, again.vertices.p);
+    }
+
+    /// A regular lattice is maximally co-circular; it must still triangulate
+    /// and every interior cell gets its 4 axis neighbours.
+    #[test]
+    fn from_points_accepts_lattice() {
+        let mut points = Vec::new();
+        for j in 0..8 {
+            for i in 0..10 {
+                points.push([500.0 + 1000.0 * i as f64, 500.0 + 1000.0 * j as f64]);
+            }
+        }
+        let mesh = build_from_points(&points, WORLD_W, WORLD_H).unwrap();
+        assert_eq!(mesh.points.len(), 80);
+        let cell = 3 * 10 + 4;
+        let neigh = mesh.cells.neighbors_of_cell(cell);
+        for nb in [cell - 1, cell + 1, cell - 10, cell + 10] {
+            assert!(neigh.contains(&(nb as u32)), "{nb} missing from {neigh:?}");
+        }
+    }
+
+    fn rejection(points: &[[f64; 2]], world_w: f64, world_h: f64) -> PointsError {
+        match build_from_points(points, world_w, world_h) {
+            Ok(_) => panic!("{points:?} should be rejected"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn from_points_rejects_bad_input() {
+        let ok = [[10.0, 10.0], [500.0, 20.0], [200.0, 400.0]];
+        assert!(build_from_points(&ok, WORLD_W, WORLD_H).is_ok());
+        assert_eq!(
+            rejection(&ok[..2], WORLD_W, WORLD_H),
+            PointsError::TooFew { count: 2 }
+        );
+        assert_eq!(rejection(&ok, 0.0, WORLD_H), PointsError::BadWorldSize);
+        let mut bad = ok.to_vec();
+        bad[1] = [f64::NAN, 5.0];
+        assert_eq!(
+            rejection(&bad, WORLD_W, WORLD_H),
+            PointsError::NonFinite { index: 1 }
+        );
+        bad[1] = [WORLD_W + 1.0, 5.0];
+        assert_eq!(
+            rejection(&bad, WORLD_W, WORLD_H),
+            PointsError::OutOfBounds { index: 1 }
+        );
+        bad.push([10.0, 10.0]);
+        bad[1] = [500.0, 20.0];
+        assert_eq!(
+            rejection(&bad, WORLD_W, WORLD_H),
+            PointsError::Duplicate { index: 3, first: 0 }
+        );
+        let line = [[0.0, 0.0], [100.0, 50.0], [300.0, 150.0], [50.0, 25.0]];
+        assert_eq!(rejection(&line, WORLD_W, WORLD_H), PointsError::Collinear);
+        // Exactly on y = 1.5x, though `(c - a) × (b - a)` rounds to nonzero.
+        let line = [
+            [1.456061387904242, 2.184092081856363],
+            [3.88766217720056e-05, 5.83149326580084e-05],
+            [3114.0, 4671.0],
+            [3471.0, 5206.5],
+        ];
+        assert_eq!(rejection(&line, WORLD_W, WORLD_H), PointsError::Collinear);
+    }
+
+    /// Cylinder mesh: adjacency is symmetric and links cells across the
+    /// seam, only the top/bottom rows are border cells, and no interior cell
+    /// lists a neighbour twice.
+    #[test]
+    fn cylinder_links_across_seam() {
+        let mesh = build_cylinder(2000, 42);
+        let n = mesh.points.len();
+        assert_eq!(n, 2000);
+        let mut seam_links = 0;
+        for cell in 0..n {
+            let neigh = mesh.cells.neighbors_of_cell(cell);
+            assert!(
+                neigh.len() >= 3,
+                "cell {cell} has {} neighbours",
+                neigh.len()
+            );
+            let unique: std::collections::BTreeSet<u32> = neigh.iter().copied().collect();
+            if mesh.cells.b[cell] == 0 {
+                assert_eq!(unique.len(), neigh.len(), "cell {cell} repeats a neighbour");
+            }
+            for &nb in neigh {
+                assert!(mesh
+                    .cells
+                    .neighbors_of_cell(nb as usize)
+                    .contains(&(cell as u32)));
+                let dx = (mesh.points[nb as usize][0] - mesh.points[cell][0]).abs();
+                // Border cells can have long hull-cap edges (as on the flat
+                // mesh); interior seam links must be short across the seam.
+                let interior = mesh.cells.b[cell] == 0 && mesh.cells.b[nb as usize] == 0;
+                if dx > WORLD_W / 2.0 && interior {
+                    seam_links += 1;
+                    let d = mesh.delta_x(mesh.points[cell][0], mesh.points[nb as usize][0]);
+                    assert!(d.abs() < WORLD_W / 10.0, "seam link {cell}→{nb} spans {d}");
+                }
+            }
+        }
+        assert!(seam_links > 0, "no adjacency across the seam");
+        let spacing = (WORLD_W * WORLD_H / n as f64).sqrt();
+        for cell in 0..n {
+            if mesh.cells.b[cell] == 1 {
+                let y = mesh.points[cell][1];
+                assert!(
+                    y < 3.0 * spacing || y > WORLD_H - 3.0 * spacing,
+                    "border cell {cell} at y={y} is not on the top/bottom edge"
+                );
+            }
+        }
+        // Cells hugging the west/east edges are interior now.
+        let west = (0..n)
+            .min_by(|&a, &b| mesh.points[a][0].total_cmp(&mesh.points[b][0]))
+            .unwrap();
+        let y = mesh.points[west][1];
+        if y > 3.0 * spacing && y < WORLD_H - 3.0 * spacing {
+            assert_eq!(mesh.cells.b[west], 0);
+        }
+    }
+
+    /// Same seed gives the same point layout as the flat mesh (only the
+    /// topology differs) and the cylinder build is deterministic.
+    #[test]
+    fn cylinder_shares_flat_points_and_is_deterministic() {
+        let flat = build(1500, 8);
+        let a = build_cylinder(1500, 8);
+        let b = build_cylinder(1500, 8);
+        assert_eq!(a.points, flat.points);
+        assert_eq!(a.cells.c, b.cells.c);
+        assert_eq!(a.vertices.p, b.vertices.p);
+        assert_eq!(a.topology, Topology::Cylinder);
+    }
+
+    #[test]
+    fn delta_x_wraps_only_on_wrapping_topologies() {
+        let cyl = build_cylinder(64, 1);
+        assert!((cyl.delta_x(9_900.0, 100.0) - 200.0).abs() < 1e-9);
+        assert!((cyl.delta_x(100.0, 9_900.0) + 200.0).abs() < 1e-9);
+        assert!((cyl.distance_sq([50.0, 0.0], [9_950.0, 0.0]) - 10_000.0).abs() < 1e-6);
+        let flat = build(64, 1);
+        assert!((flat.delta_x(9_900.0, 100.0) + 9_800.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bucket_col_wraps_only_on_wrapping_topologies() {
+        let cyl = build_cylinder(64, 1);
+        assert_eq!(cyl.bucket_col(-1, 8), Some(7));
+        assert_eq!(cyl.bucket_col(8, 8), Some(0));
+        assert_eq!(cyl.bucket_col(3, 8), Some(3));
+        let flat = build(64, 1);
+        assert_eq!(flat.bucket_col(-1, 8), None);
+        assert_eq!(flat.bucket_col(8, 8), None);
+        assert_eq!(flat.bucket_col(3, 8), Some(3));
+    }
 }
@@ -0,0 +1,290 @@
+//! Seeded gradient noise evaluated at cell centres: fBm, ridged
+//! multifractal and domain-warped fBm.
+//!
+//! Used by the `Fbm` / `Ridged` / `Warp` heightmap template steps and the
+//! matching `heightmap_edit` brush modes, for detail the flood tools can't
+//! make (ragged coasts, mid-frequency relief).
+//!
+//! ## Determinism
+//!
+//! All randomness comes from the caller's `StdRng`: `field` draws the
+//! permutation table (a Fisher–Yates shuffle of `0..256`) and then one
+//! offset pair per octave, in that order. Evaluation itself is pure `f64`
+//! arithmetic.
+//!
+//! ## Seams
+//!
+//! The lattice period along `x` is a whole number of features per world
+//! width (`scale` is rounded to make it so), doubling each octave. On
+//! topologies that wrap in `x` lattice columns are taken modulo that period,
+//! so the field is continuous across the east–west seam.
+
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+/// Which fractal to build from the gradient noise.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseKind {
+    /// Fractional Brownian motion: octaves of noise, halving in amplitude.
+    Fbm,
+    /// Ridged multifractal (Musgrave): sharp crests, smooth valleys.
+    Ridged,
+    /// fBm sampled at coordinates displaced by two more fBm fields.
+    Warp,
+}
+
+/// Upper bound on octaves per field.
+pub const MAX_OCTAVES: u32 = 8;
+
+/// Domain-warp displacement, in base-octave lattice units.
+const WARP_STRENGTH: f64 = 1.5;
+
+/// The eight unit gradients of 2-D Perlin noise.
+const GRADIENTS: [[f64; 2]; 8] = [
+    [1.0, 0.0],
+    [-1.0, 0.0],
+    [0.0, 1.0],
+    [0.0, -1.0],
+    [FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+    [-FRAC_1_SQRT_2, FRAC_1_SQRT_2],
+    [FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+    [-FRAC_1_SQRT_2, -FRAC_1_SQRT_2],
+];
+
+/// 2-D gradient noise over a shuffled permutation table.
+struct Gradient {
+    perm: [u8; 256],
+}
+
+impl Gradient {
+    fn new(rng: &mut StdRng) -> Self {
+        let mut perm = [0u8; 256];
+        for (k, p) in perm.iter_mut().enumerate() {
+            *p = k as u8;
+        }
+        for k in (1..256).rev() {
+            perm.swap(k, rng.gen_range(0..=k));
+        }
+        Gradient { perm }
+    }
+
+    fn corner(&self, ix: i64, iy: i64, dx: f64, dy: f64) -> f64 {
+        let a = self.perm[ix.rem_euclid(256) as usize] as usize;
+        let g = GRADIENTS[self.perm[(a + iy.rem_euclid(256) as usize) % 256] as usize % 8];
+        g[0] * dx + g[1] * dy
+    }
+
+    /// Noise at `(x, y)` in lattice units, roughly `-1..=1`. With `period`,
+    /// lattice columns repeat every `period` units.
+    fn sample(&self, x: f64, y: f64, period: Option<i64>) -> f64 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i64, y0 as i64);
+        let col = |i: i64| period.map_or(i, |p| i.rem_euclid(p));
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let (u, v) = (fade(fx), fade(fy));
+        let n00 = self.corner(col(ix), iy, fx, fy);
+        let n10 = self.corner(col(ix + 1), iy, fx - 1.0, fy);
+        let n01 = self.corner(col(ix), iy + 1, fx, fy - 1.0);
+        let n11 = self.corner(col(ix + 1), iy + 1, fx - 1.0, fy - 1.0);
+        let a = n00 + u * (n10 - n00);
+        let b = n01 + u * (n11 - n01);
+        // 2-D Perlin peaks at ±√½; stretch to about ±1.
+        (a + v * (b - a)) * SQRT_2
+    }
+}
+
+/// A seeded fractal over the world rectangle.
+struct Fractal {
+    gradient: Gradient,
+    /// Per-octave lattice offsets (keeps octaves from sharing a lattice origin).
+    offsets: Vec<[f64; 2]>,
+    /// Base-octave lattice units per world unit.
+    frequency: f64,
+    /// Base-octave lattice period along `x`, when the world wraps.
+    period: Option<i64>,
+}
+
+impl Fractal {
+    fn octave(&self, k: usize, x: f64, y: f64) -> f64 {
+        let f = (1u64 << k) as f64;
+        let [ox, oy] = self.offsets[k];
+        let period = self.period.map(|p| p << k);
+        // The offset is added after scaling, so the period still holds.
+        self.gradient.sample(x * f + ox, y * f + oy, period)
+    }
+
+    /// fBm at lattice coordinates `(x, y)`, normalized to about `-1..=1`.
+    fn fbm(&self, x: f64, y: f64) -> f64 {
+        let (mut sum, mut norm, mut amp) = (0.0, 0.0, 1.0);
+        for k in 0..self.offsets.len() {
+            sum += amp * self.octave(k, x, y);
+            norm += amp;
+            amp *= 0.5;
+        }
+        sum / norm
+    }
+
+    /// Ridged multifractal, mapped to about `-1..=1`.
+    fn ridged(&self, x: f64, y: f64) -> f64 {
+        let (mut sum, mut norm, mut amp, mut weight) = (0.0, 0.0, 1.0, 1.0);
+        for k in 0..self.offsets.len() {
+            let signal = (1.0 - self.octave(k, x, y).abs()).powi(2) * weight;
+            weight = (signal * 2.0).clamp(0.0, 1.0);
+            sum += amp * signal;
+            norm += amp;
+            amp *= 0.5;
+        }
+        sum / norm * 2.0 - 1.0
+    }
+
+    /// fBm at `(x, y)` displaced by fBm, Quílez-style.
+    fn warp(&self, x: f64, y: f64) -> f64 {
+        let qx = self.fbm(x + 5.2, y + 1.3);
+        let qy = self.fbm(x + 1.7, y + 9.2);
+        self.fbm(x + WARP_STRENGTH * qx, y + WARP_STRENGTH * qy)
+    }
+}
+
+/// Evaluate a `kind` fractal with features about `scale` world units across
+/// and `octaves` octaves (clamped to `1..=MAX_OCTAVES`) at every point.
+/// Values are roughly `-1..=1`. `world_w` and `wraps_x` describe the mesh
+/// the points come from.
+pub fn field(
+    points: &[[f64; 2]],
+    world_w: f64,
+    wraps_x: bool,
+    rng: &mut StdRng,
+    kind: NoiseKind,
+    scale: f64,
+    octaves: u32,
+) -> Vec<f64> {
+    let octaves = octaves.clamp(1, MAX_OCTAVES) as usize;
+    let gradient = Gradient::new(rng);
+    let offsets = (0..octaves)
+        .map(|_| [rng.gen_range(0.0..256.0), rng.gen_range(0.0..256.0)])
+        .collect();
+    let features = (world_w / scale.max(f64::EPSILON)).round().max(1.0);
+    let fractal = Fractal {
+        gradient,
+        offsets,
+        frequency: features / world_w,
+        period: wraps_x.then_some(features as i64),
+    };
+    points
+        .iter()
+        .map(|&[x, y]| {
+            let (x, y) = (x * fractal.frequency, y * fractal.frequency);
+            match kind {
+                NoiseKind::Fbm => fractal.fbm(x, y),
+                NoiseKind::Ridged => fractal.ridged(x, y),
+                NoiseKind::Warp => fractal.warp(x, y),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn grid_points(w: f64, h: f64, step: f64) -> Vec<[f64; 2]> {
+        let mut out = Vec::new();
+        let mut y = 0.0;
+        while y < h {
+            let mut x = 0.0;
+            while x < w {
+                out.push([x, y]);
+                x += step;
+            }
+            y += step;
+        }
+        out
+    }
+
+    /// Same rng state, same field; every kind stays in range and varies.
+    #[test]
+    fn fields_are_deterministic_and_bounded() {
+        let points = grid_points(10000.0, 8000.0, 100.0);
+        for kind in [NoiseKind::Fbm, NoiseKind::Ridged, NoiseKind::Warp] {
+            let a = field(
+                &points,
+                10000.0,
+                false,
+                &mut StdRng::seed_from_u64(3),
+                kind,
+                1500.0,
+                5,
+            );
+            let b = field(
+                &points,
+                10000.0,
+                false,
+                &mut StdRng::seed_from_u64(3),
+                kind,
+                1500.0,
+                5,
+            );
+            let c = field(
+                &points,
+                10000.0,
+                false,
+                &mut StdRng::seed_from_u64(4),
+                kind,
+                1500.0,
+                5,
+            );
+            assert_eq!(a, b, "{kind:?}");
+            assert_ne!(a, c, "{kind:?}");
+            let (lo, hi) = a
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            assert!(lo >= -1.5 && hi <= 1.5, "{kind:?}: {lo}..{hi}");
+            assert!(hi - lo > 0.5, "{kind:?}: {lo}..{hi}");
+        }
+    }
+
+    /// On a wrapping world `x = 0` and `x = world_w` give the same value.
+    #[test]
+    fn wrapping_field_is_seamless() {
+        let ys: Vec<f64> = (0..40).map(|k| k as f64 * 200.0 + 13.0).collect();
+        let left: Vec<[f64; 2]> = ys.iter().map(|&y| [0.0, y]).collect();
+        let right: Vec<[f64; 2]> = ys.iter().map(|&y| [10000.0, y]).collect();
+        for kind in [NoiseKind::Fbm, NoiseKind::Ridged, NoiseKind::Warp] {
+            let a = field(
+                &left,
+                10000.0,
+                true,
+                &mut StdRng::seed_from_u64(9),
+                kind,
+                1300.0,
+                4,
+            );
+            let b = field(
+                &right,
+                10000.0,
+                true,
+                &mut StdRng::seed_from_u64(9),
+                kind,
+                1300.0,
+                4,
+            );
+            for (p, q) in a.iter().zip(&b) {
+                assert!((p - q).abs() < 1e-9, "{kind:?}: {p} vs {q}");
+            }
+            let flat = field(
+                &right,
+                10000.0,
+                false,
+                &mut StdRng::seed_from_u64(9),
+                kind,
+                1300.0,
+                4,
+            );
+            assert_ne!(a, flat, "{kind:?}");
+        }
+    }
+}
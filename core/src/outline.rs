@@ -0,0 +1,630 @@
+//! Merged outlines of labelled cell sets.
+//!
+//! Given any per-cell label array (`cells.state`, `cells_province`, a
+//! `WorldAt.cells_culture`, a lake/feature id, ...) this dissolves the shared
+//! edges between same-label cells and returns, per label, a multipolygon:
+//! one `Polygon` per connected patch, each an outer ring plus holes, every
+//! ring an ordered loop of ids into `Vertices.p`.
+//!
+//! Alongside the fills it returns `borders`: the edges between two different
+//! labels, chained into polylines, each edge **once** (emitted by the side
+//! with the larger label). Stroking `borders` instead of every polygon ring
+//! avoids double-drawn shared boundaries.
+//!
+//! ## Edges
+//!
+//! Cell `c`'s ring edge `k` runs `v[k-1] → v[k]` with neighbour `c[k]` on the
+//! far side (`Cells::c`). An edge is *internal* when the neighbour carries the
+//! same label and lists the same edge reversed (its twin). Everything else is
+//! boundary — including edges with no twin (hull cells, whose clamped outer
+//! vertices are minted per cell), so the outline always traces exactly the
+//! union of the cell polygons the renderer draws.
+//!
+//! On a cylinder the two sides of a seam-crossing edge name the same
+//! vertices under different ids, `world_w` apart; they are twinned by
+//! position (through `Mesh::delta_x`), so a label straddling the seam is one
+//! patch whose rings cross it, to be unwrapped like `coast` shorelines. Only
+//! a patch running all the way round the cylinder, whose loops never close
+//! in the plane, is cut at the seam instead.
+//!
+//! Loops are traced by following a cell's ring and, where the next edge is
+//! internal, rotating through the twin into the neighbour — the standard
+//! half-edge walk. Pinch vertices (two same-label cells touching at a corner)
+//! therefore stay in their own patch. Labels `< 0` mean "unassigned" (the
+//! repo-wide `-1` convention) and produce no polygons. On a globe a label
+//! covering every cell has no boundary, hence no rings, and is omitted.
+//!
+//! Output order is deterministic: labels ascending, patches by lowest cell id,
+//! rings starting from the lowest-index boundary edge.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::mesh::Mesh;
+
+/// One connected patch: an outer ring and zero or more holes. Rings are
+/// vertex ids into `Vertices.p`, implicitly closed (the first id is not
+/// repeated). Outer rings wind like the cell polygons; holes the other way.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub outer: Vec<u32>,
+    pub holes: Vec<Vec<u32>>,
+}
+
+/// All patches of one label.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LabelOutline {
+    pub label: i32,
+    pub polygons: Vec<Polygon>,
+}
+
+/// A run of boundary between label `left` and label `right` (`left > right`;
+/// `right` may be `-1` for unassigned cells). `closed` when the run is a
+/// whole ring (an enclave), in which case the first id is not repeated.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Border {
+    pub left: i32,
+    pub right: i32,
+    pub vertices: Vec<u32>,
+    pub closed: bool,
+}
+
+/// `outlines` result.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Outlines {
+    pub labels: Vec<LabelOutline>,
+    pub borders: Vec<Border>,
+}
+
+/// Boundary loops of every same-label patch — the shared first half of
+/// `outlines` and `coast::shorelines`.
+pub(crate) struct PatchLoops {
+    /// Ring edge → owning cell.
+    pub owner: Vec<u32>,
+    /// Ring edge → its start vertex `v[k-1]` (the end vertex is `cells.v[p]`).
+    pub start: Vec<u32>,
+    /// Patch (keyed by its lowest cell id) → boundary loops, each a list of
+    /// ring edge indices in walk order.
+    pub loops: BTreeMap<u32, Vec<Vec<usize>>>,
+}
+
+/// Trace the boundary loops of the connected same-label patches of `mesh`
+/// (labels `< 0` and missing entries form no patches).
+pub(crate) fn patch_loops(mesh: &Mesh, labels: &[i32]) -> PatchLoops {
+    let cells = &mesh.cells;
+    let n = mesh.points.len();
+    let e = cells.v.len();
+    let label = |c: usize| labels.get(c).copied().unwrap_or(-1);
+
+    // Edge → owning cell, ring successor, and start vertex.
+    let mut owner = vec![0u32; e];
+    let mut next = vec![0usize; e];
+    let mut start = vec![0u32; e];
+    for c in 0..n {
+        let (lo, hi) = (cells.i[c] as usize, cells.i[c + 1] as usize);
+        for p in lo..hi {
+            owner[p] = c as u32;
+            next[p] = if p + 1 == hi { lo } else { p + 1 };
+            start[p] = cells.v[if p == lo { hi - 1 } else { p - 1 }];
+        }
+    }
+
+    // Twin of every edge, if the neighbour lists it reversed. Across a
+    // cylinder seam the two sides hold the shared vertices under different
+    // ids, `world_w` apart, so there the ends are matched by position.
+    let mut twin: Vec<Option<usize>> = vec![None; e];
+    let mut seam = vec![false; e];
+    for p in 0..e {
+        let nb = cells.c[p] as usize;
+        if nb >= n {
+            continue;
+        }
+        let ring = cells.i[nb] as usize..cells.i[nb + 1] as usize;
+        twin[p] = ring
+            .clone()
+            .find(|&q| cells.v[q] == start[p] && start[q] == cells.v[p] && cells.c[q] == owner[p]);
+        if twin[p].is_none() && crosses_seam(mesh, owner[p] as usize, nb) {
+            twin[p] = ring.into_iter().find(|&q| {
+                cells.c[q] == owner[p]
+                    && same_position(mesh, cells.v[q], start[p])
+                    && same_position(mesh, start[q], cells.v[p])
+            });
+            seam[p] = twin[p].is_some();
+        }
+    }
+    let mut internal: Vec<bool> = (0..e)
+        .map(|p| twin[p].is_some() && label(cells.c[p] as usize) == label(owner[p] as usize))
+        .collect();
+    let (patch, mut loops) = trace_loops(mesh, &label, &owner, &next, &twin, &internal);
+
+    // A patch running all the way round the cylinder has loops that never
+    // close in the plane, which no ring can describe: cut it at the seam.
+    let wrapping: BTreeSet<u32> = loops
+        .iter()
+        .filter(|(_, patch_loops)| {
+            patch_loops
+                .iter()
+                .any(|edges| wraps_around(mesh, &start, edges))
+        })
+        .map(|(&id, _)| id)
+        .collect();
+    let cut: Vec<usize> = (0..e)
+        .filter(|&p| seam[p] && internal[p] && wrapping.contains(&patch[owner[p] as usize]))
+        .collect();
+    if !cut.is_empty() {
+        for p in cut {
+            internal[p] = false;
+        }
+        (_, loops) = trace_loops(mesh, &label, &owner, &next, &twin, &internal);
+    }
+
+    PatchLoops {
+        owner,
+        start,
+        loops,
+    }
+}
+
+/// Group the labelled cells into patches across `internal` edges (each
+/// patch keyed by its lowest cell id, `u32::MAX` for unlabelled cells) and
+/// trace every patch's boundary loops as lists of ring edges.
+fn trace_loops(
+    mesh: &Mesh,
+    label: &impl Fn(usize) -> i32,
+    owner: &[u32],
+    next: &[usize],
+    twin: &[Option<usize>],
+    internal: &[bool],
+) -> (Vec<u32>, BTreeMap<u32, Vec<Vec<usize>>>) {
+    let cells = &mesh.cells;
+    let n = mesh.points.len();
+    let e = cells.v.len();
+
+    // Connected patches: union of same-label cells across internal edges.
+    let mut patch = vec![u32::MAX; n];
+    let mut stack = Vec::new();
+    for first in 0..n {
+        if label(first) < 0 || patch[first] != u32::MAX {
+            continue;
+        }
+        patch[first] = first as u32;
+        stack.push(first);
+        while let Some(c) = stack.pop() {
+            let (lo, hi) = (cells.i[c] as usize, cells.i[c + 1] as usize);
+            for (&nb, &inside) in cells.c[lo..hi].iter().zip(&internal[lo..hi]) {
+                let nb = nb as usize;
+                if inside && patch[nb] == u32::MAX {
+                    patch[nb] = first as u32;
+                    stack.push(nb);
+                }
+            }
+        }
+    }
+
+    // Trace boundary loops (as edge lists) per patch.
+    let boundary = |p: usize| label(owner[p] as usize) >= 0 && !internal[p];
+    let mut visited = vec![false; e];
+    let mut loops: BTreeMap<u32, Vec<Vec<usize>>> = BTreeMap::new();
+    for p0 in 0..e {
+        if visited[p0] || !boundary(p0) {
+            continue;
+        }
+        let mut edges = Vec::new();
+        let mut p = p0;
+        loop {
+            visited[p] = true;
+            edges.push(p);
+            let mut q = next[p];
+            // Rotate around the shared vertex until we leave the patch.
+            let mut guard = 0;
+            while internal[q] && guard < e {
+                q = next[twin[q].expect("internal edges have twins")];
+                guard += 1;
+            }
+            if q == p0 || visited[q] {
+                break;
+            }
+            p = q;
+        }
+        loops
+            .entry(patch[owner[p0] as usize])
+            .or_default()
+            .push(edges);
+    }
+
+    (patch, loops)
+}
+
+/// `true` if cells `a` and `b` sit on opposite sides of the east–west seam.
+fn crosses_seam(mesh: &Mesh, a: usize, b: usize) -> bool {
+    mesh.topology.wraps_x() && (mesh.points[a][0] - mesh.points[b][0]).abs() > mesh.world_w / 2.0
+}
+
+/// `true` if vertices `u` and `v` are the same point, across the seam when
+/// the topology wraps in `x`.
+fn same_position(mesh: &Mesh, u: u32, v: u32) -> bool {
+    let [ux, uy] = mesh.vertices.p[u as usize];
+    let [vx, vy] = mesh.vertices.p[v as usize];
+    let eps = 1e-9 * mesh.world_w.max(mesh.world_h);
+    mesh.delta_x(ux, vx).abs() <= eps && (uy - vy).abs() <= eps
+}
+
+/// `true` if a boundary loop winds once round the cylinder: its steps,
+/// each taken the short way across the seam, add up to a full `world_w`.
+fn wraps_around(mesh: &Mesh, start: &[u32], edges: &[usize]) -> bool {
+    let p = &mesh.vertices.p;
+    let dx: f64 = edges
+        .iter()
+        .map(|&q| mesh.delta_x(p[start[q] as usize][0], p[mesh.cells.v[q] as usize][0]))
+        .sum();
+    dx.abs() > mesh.world_w / 2.0
+}
+
+/// Merge the cells of `mesh` by `labels` (one entry per cell; missing
+/// entries count as `-1`).
+pub fn outlines(mesh: &Mesh, labels: &[i32]) -> Outlines {
+    let cells = &mesh.cells;
+    let label = |c: usize| labels.get(c).copied().unwrap_or(-1);
+    let PatchLoops {
+        owner,
+        start,
+        loops,
+    } = patch_loops(mesh, labels);
+    let prev_vertex = |p: usize| start[p];
+
+    // Assemble polygons: the loop winding like its cells (largest |area|)
+    // is the outer ring, the rest are holes.
+    let mut by_label: BTreeMap<i32, Vec<Polygon>> = BTreeMap::new();
+    for (patch_id, patch_loops) in &loops {
+        let cell_sign = ring_area(mesh, &cell_ring(mesh, *patch_id as usize)).signum();
+        let rings: Vec<Vec<u32>> = patch_loops
+            .iter()
+            .map(|edges| drop_spurs(edges.iter().map(|&p| prev_vertex(p))))
+            .collect();
+        let outer_idx = (0..rings.len())
+            .max_by(|&a, &b| {
+                let (sa, sb) = (
+                    ring_area(mesh, &rings[a]) * cell_sign,
+                    ring_area(mesh, &rings[b]) * cell_sign,
+                );
+                sa.total_cmp(&sb).then(b.cmp(&a))
+            })
+            .expect("a patch has at least one boundary loop");
+        let mut rings = rings;
+        let outer = rings.remove(outer_idx);
+        by_label
+            .entry(label(*patch_id as usize))
+            .or_default()
+            .push(Polygon {
+                outer,
+                holes: rings,
+            });
+    }
+
+    // Borders: runs of boundary edges this side owns (larger label), split
+    // where the label across changes.
+    let mut borders = Vec::new();
+    for patch_loops in loops.values() {
+        for edges in patch_loops {
+            let key = |p: usize| {
+                let (mine, across) = (label(owner[p] as usize), label(cells.c[p] as usize));
+                (mine > across).then_some((mine, across))
+            };
+            for ((left, right), run, closed) in split_runs(edges, key) {
+                borders.push(Border {
+                    left,
+                    right,
+                    vertices: run_vertices(mesh, &start, &run, closed),
+                    closed,
+                });
+            }
+        }
+    }
+
+    Outlines {
+        labels: by_label
+            .into_iter()
+            .map(|(label, polygons)| LabelOutline { label, polygons })
+            .collect(),
+        borders,
+    }
+}
+
+/// Split a boundary loop into maximal runs of consecutive edges with the
+/// same `Some` key (edges keyed `None` separate runs and are dropped).
+/// Returns `(key, edges, closed)`; a run is closed when it is the whole loop.
+pub(crate) fn split_runs<K: Copy + PartialEq>(
+    edges: &[usize],
+    key: impl Fn(usize) -> Option<K>,
+) -> Vec<(K, Vec<usize>, bool)> {
+    let keys: Vec<Option<K>> = edges.iter().map(|&p| key(p)).collect();
+    let m = edges.len();
+    // Start right after a key change so no run wraps the loop end.
+    let Some(first) = (0..m).find(|&k| keys[k] != keys[(k + m - 1) % m]) else {
+        return match keys.first() {
+            Some(&Some(k)) => vec![(k, edges.to_vec(), true)],
+            _ => Vec::new(),
+        };
+    };
+    let mut runs = Vec::new();
+    let mut k = 0;
+    while k < m {
+        let Some(run_key) = keys[(first + k) % m] else {
+            k += 1;
+            continue;
+        };
+        let mut run = Vec::new();
+        while k < m && keys[(first + k) % m] == Some(run_key) {
+            run.push(edges[(first + k) % m]);
+            k += 1;
+        }
+        runs.push((run_key, run, false));
+    }
+    runs
+}
+
+/// Vertex ids along a run of ring edges: every start vertex, plus the final
+/// end vertex when the run is open.
+pub(crate) fn run_vertices(mesh: &Mesh, start: &[u32], run: &[usize], closed: bool) -> Vec<u32> {
+    let mut vertices: Vec<u32> = run.iter().map(|&p| start[p]).collect();
+    if !closed {
+        if let Some(&last) = run.last() {
+            vertices.push(mesh.cells.v[last]);
+        }
+    }
+    vertices
+}
+
+/// Collect a closed vertex ring, dropping repeated vertices and zero-area
+/// spurs (`a, b, a` collapses to `a`) that hull edges can leave behind.
+fn drop_spurs(vertices: impl Iterator<Item = u32>) -> Vec<u32> {
+    let mut ring: Vec<u32> = Vec::new();
+    for v in vertices {
+        match ring.len() {
+            n if n >= 1 && ring[n - 1] == v => {}
+            n if n >= 2 && ring[n - 2] == v => {
+                ring.pop();
+            }
+            _ => ring.push(v),
+        }
+    }
+    // The same cases across the seam between the last and first vertex.
+    loop {
+        let n = ring.len();
+        if n >= 2 && ring[n - 1] == ring[0] {
+            ring.pop();
+        } else if n >= 3 && ring[n - 2] == ring[0] {
+            ring.truncate(n - 2);
+        } else if n >= 3 && ring[n - 1] == ring[1] {
+            ring.pop();
+            ring.remove(0);
+        } else {
+            return ring;
+        }
+    }
+}
+
+/// Cell `c`'s vertex ring.
+fn cell_ring(mesh: &Mesh, c: usize) -> Vec<u32> {
+    let cells = &mesh.cells;
+    cells.v[cells.i[c] as usize..cells.i[c + 1] as usize].to_vec()
+}
+
+/// Signed shoelace area of a vertex ring. Consecutive `x` steps go through
+/// `Mesh::delta_x`, so rings crossing a cylinder seam are measured unwrapped.
+fn ring_area(mesh: &Mesh, ring: &[u32]) -> f64 {
+    let p = &mesh.vertices.p;
+    let Some(&first) = ring.first() else {
+        return 0.0;
+    };
+    let [mut x, mut y] = p[first as usize];
+    let (x0, y0) = (x, y);
+    let mut twice = 0.0;
+    for &v in ring.iter().skip(1).chain(std::iter::once(&first)) {
+        let [vx, vy] = p[v as usize];
+        let nx = if v == first {
+            x0
+        } else {
+            x + mesh.delta_x(x, vx)
+        };
+        let ny = if v == first { y0 } else { vy };
+        twice += x * ny - nx * y;
+        x = nx;
+        y = ny;
+    }
+    twice / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh;
+
+    /// Total signed area of a label's polygons (outer minus holes).
+    fn label_area(mesh: &Mesh, outline: &LabelOutline) -> f64 {
+        outline
+            .polygons
+            .iter()
+            .map(|poly| {
+                ring_area(mesh, &poly.outer).abs()
+                    - poly
+                        .holes
+                        .iter()
+                        .map(|h| ring_area(mesh, h).abs())
+                        .sum::<f64>()
+            })
+            .sum()
+    }
+
+    /// Merged area equals the sum of the member cells' polygon areas, and
+    /// every border edge is emitted exactly once across all labels.
+    #[test]
+    fn outlines_preserve_area_and_emit_borders_once() {
+        let mesh = mesh::build(3000, 42);
+        // Four vertical bands plus an unassigned strip and an enclave.
+        let labels: Vec<i32> = mesh
+            .points
+            .iter()
+            .map(|&[x, y]| {
+                if (3200.0..4200.0).contains(&x) && (3000.0..4000.0).contains(&y) {
+                    9
+                } else if x > 9000.0 {
+                    -1
+                } else {
+                    (x / 2500.0) as i32
+                }
+            })
+            .collect();
+        let out = outlines(&mesh, &labels);
+        let found: Vec<i32> = out.labels.iter().map(|l| l.label).collect();
+        assert_eq!(found, vec![0, 1, 2, 3, 9]);
+        for outline in &out.labels {
+            let cells_area: f64 = (0..mesh.points.len())
+                .filter(|&c| labels[c] == outline.label)
+                .map(|c| ring_area(&mesh, &cell_ring(&mesh, c)).abs())
+                .sum();
+            let merged = label_area(&mesh, outline);
+            assert!(
+                (merged - cells_area).abs() < 1e-6 * cells_area,
+                "label {}: merged {merged} vs cells {cells_area}",
+                outline.label
+            );
+        }
+        // Label 1 (x in 2500..5000) surrounds the enclave: a hole.
+        let one = &out.labels[1];
+        assert!(one.polygons.iter().any(|p| !p.holes.is_empty()));
+        // The enclave is bordered by label 1 only, as one closed ring.
+        let enclave: Vec<&Border> = out.borders.iter().filter(|b| b.left == 9).collect();
+        assert_eq!(enclave.len(), 1);
+        assert!(enclave[0].closed && enclave[0].right == 1);
+        // Each undirected interior edge appears in at most one border.
+        let mut seen = std::collections::BTreeSet::new();
+        for b in &out.borders {
+            assert!(b.left > b.right);
+            let len = b.vertices.len();
+            let segs = if b.closed { len } else { len - 1 };
+            for k in 0..segs {
+                let (a, c) = (b.vertices[k], b.vertices[(k + 1) % len]);
+                assert!(
+                    seen.insert((a.min(c), a.max(c))),
+                    "edge {a}-{c} emitted twice"
+                );
+            }
+        }
+    }
+
+    /// One label over the whole map is a single polygon with no holes and
+    /// no borders; the outer ring winds like the cells.
+    #[test]
+    fn single_label_is_one_polygon() {
+        for mesh in [
+            mesh::build(800, 7),
+            mesh::build_with_topology(800, 7, mesh::Topology::Cylinder),
+        ] {
+            let labels = vec![0; mesh.points.len()];
+            let out = outlines(&mesh, &labels);
+            assert_eq!(out.labels.len(), 1);
+            assert_eq!(out.labels[0].polygons.len(), 1);
+            assert!(out.borders.is_empty());
+            let poly = &out.labels[0].polygons[0];
+            let sign = ring_area(&mesh, &cell_ring(&mesh, 0)).signum();
+            assert_eq!(ring_area(&mesh, &poly.outer).signum(), sign);
+        }
+    }
+
+    /// On a cylinder a label straddling the seam is one polygon with no
+    /// boundary along the seam, bordered by a single closed ring; the rest of
+    /// the map, which runs all the way round, keeps its area.
+    #[test]
+    fn seam_straddling_label_is_one_polygon() {
+        let mesh = mesh::build_with_topology(3000, 42, mesh::Topology::Cylinder);
+        let w = mesh.world_w;
+        let labels: Vec<i32> = mesh
+            .points
+            .iter()
+            .map(|&[x, y]| ((x < 1500.0 || x > w - 1500.0) && (3000.0..5000.0).contains(&y)) as i32)
+            .collect();
+        let out = outlines(&mesh, &labels);
+        assert_eq!(out.labels.len(), 2);
+        let straddling = &out.labels[1];
+        assert_eq!(straddling.polygons.len(), 1);
+        assert!(straddling.polygons[0].holes.is_empty());
+        for outline in &out.labels {
+            let cells_area: f64 = (0..mesh.points.len())
+                .filter(|&c| labels[c] == outline.label)
+                .map(|c| ring_area(&mesh, &cell_ring(&mesh, c)).abs())
+                .sum();
+            let merged = label_area(&mesh, outline);
+            assert!(
+                (merged - cells_area).abs() < 1e-6 * cells_area,
+                "label {}: merged {merged} vs cells {cells_area}",
+                outline.label
+            );
+        }
+        assert_eq!(out.borders.len(), 1);
+        assert!(out.borders[0].closed);
+        assert_eq!((out.borders[0].left, out.borders[0].right), (1, 0));
+    }
+
+    /// A globe is closed: one label covering it has no boundary at all, and
+    /// two hemispheres meet along a single closed border.
+    #[test]
+    fn globe_hemispheres_share_one_border() {
+        let mesh = mesh::build_globe(2000, 3);
+        let whole = outlines(&mesh, &vec![0; mesh.points.len()]);
+        assert!(whole.labels.is_empty() && whole.borders.is_empty());
+        let labels: Vec<i32> = mesh
+            .lat_lon
+            .iter()
+            .map(|&[lat, _]| (lat >= 0.0) as i32)
+            .collect();
+        let out = outlines(&mesh, &labels);
+        assert_eq!(out.borders.len(), 1);
+        assert!(out.borders[0].closed);
+        assert_eq!((out.borders[0].left, out.borders[0].right), (1, 0));
+    }
+
+    /// Rings never repeat a vertex or double back on themselves, including
+    /// across the wrap from the last vertex to the first.
+    #[test]
+    fn rings_have_no_spurs() {
+        assert_eq!(
+            drop_spurs([1, 2, 2, 3, 4, 3, 5].into_iter()),
+            vec![1, 2, 3, 5]
+        );
+        assert_eq!(drop_spurs([7, 1, 2, 3, 1].into_iter()), vec![1, 2, 3]);
+        for mesh in [
+            mesh::build(1200, 9),
+            mesh::build_with_topology(1200, 9, mesh::Topology::Cylinder),
+        ] {
+            let labels: Vec<i32> = (0..mesh.points.len())
+                .map(|c| {
+                    if mesh.cells.b[c] == 1 {
+                        (c % 3) as i32
+                    } else {
+                        c as i32 % 4
+                    }
+                })
+                .collect();
+            let out = outlines(&mesh, &labels);
+            for poly in out.labels.iter().flat_map(|l| &l.polygons) {
+                for ring in std::iter::once(&poly.outer).chain(&poly.holes) {
+                    let n = ring.len();
+                    assert!(n >= 3, "degenerate ring {ring:?}");
+                    for k in 0..n {
+                        assert_ne!(ring[k], ring[(k + 1) % n], "repeated vertex in {ring:?}");
+                        assert_ne!(ring[k], ring[(k + 2) % n], "spur in {ring:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn outlines_are_deterministic() {
+        let mesh = mesh::build(1500, 4);
+        let labels: Vec<i32> = (0..mesh.points.len() as i32).map(|c| c % 5 - 1).collect();
+        assert_eq!(outlines(&mesh, &labels), outlines(&mesh, &labels));
+    }
+}
@@ -0,0 +1,942 @@
+//! Raster import and export.
+//!
+//! ## Import
+//!
+//! Bring a heightmap painted in an external tool onto a `Mesh`. Accepted
+//! inputs, all 8- or 16-bit single-channel:
+//!
+//! - **PNG** (sniffed by signature). Low-bit and paletted grayscale are
+//!   expanded to 8 bits; RGB(A) images are accepted too and read as the mean
+//!   of the colour channels, since many tools save gray ramps as RGB. Alpha
+//!   is ignored.
+//! - **PGM**, binary (`P5`) or plain (`P2`), any `maxval` up to 65535.
+//! - **Raw** sample buffers (`.raw` / `.r16`) with caller-supplied size,
+//!   depth and byte order — see `RawFormat`.
+//!
+//! The image spans the whole world (`[0, world_w] × [0, world_h]`, row 0 at
+//! `y = 0`). Each cell takes the bilinear sample at its centre, normalized
+//! to `0..=1` by the image's full scale, and mapped onto `cells.h` piecewise
+//! linearly so that the normalized `sea_level` lands exactly on
+//! `SEA_LEVEL`: below it spans water `0..SEA_LEVEL`, above it land
+//! `SEA_LEVEL..=100`. On wrapping topologies the samples wrap in `x` too.
+//!
+//! ## Export
+//!
+//! Rasterize a per-cell layer at any pixel size (`rasterize`), pixel
+//! `(c, r)` sampling the world at its centre. Continuous layers (`h`,
+//! `temp`, `prec`, `fl`) become 16-bit grayscale, either from the nearest
+//! cell or inverse-distance weighted over the nearest cell and its
+//! neighbours (`Sampling::Interpolated`). Categorical layers (`biome` and
+//! the state / culture / religion ids) are always nearest-cell and become
+//! paletted RGBA: `BIOMES` colors for biomes, the entity's own `color` from
+//! the `Pack` for the rest, transparent where unassigned. `Raster::to_png`
+//! encodes either kind.
+
+use serde::{Deserialize, Serialize};
+
+use crate::biomes::BIOMES;
+use crate::entities::Pack;
+use crate::grid::Grid;
+use crate::heightmap::SEA_LEVEL;
+use crate::mesh::Mesh;
+use crate::spatial::SpatialIndex;
+
+/// A decoded single-channel image. `samples` are row-major, `0..=max`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrayImage {
+    pub width: u32,
+    pub height: u32,
+    /// Full-scale sample value (255, 65535, or a PGM `maxval`).
+    pub max: u16,
+    pub samples: Vec<u16>,
+}
+
+/// Layout of a headerless sample buffer.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RawFormat {
+    pub width: u32,
+    pub height: u32,
+    /// `8` or `16`.
+    pub bit_depth: u8,
+    /// Byte order of 16-bit samples. Terrain tools' `.r16` exports are
+    /// usually little-endian, which is the default.
+    #[serde(default = "default_little_endian")]
+    pub little_endian: bool,
+}
+
+fn default_little_endian() -> bool {
+    true
+}
+
+/// How an image becomes `cells.h`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct ImportOpts {
+    /// Normalized image value (`0..=1` of full scale) that becomes
+    /// `SEA_LEVEL`. The default `0.2` maps the image linearly onto
+    /// `0..=100`.
+    pub sea_level: f64,
+    /// Treat the bytes as a headerless buffer of this layout instead of
+    /// sniffing PNG / PGM.
+    pub raw: Option<RawFormat>,
+}
+
+impl Default for ImportOpts {
+    fn default() -> Self {
+        ImportOpts {
+            sea_level: SEA_LEVEL as f64 / 100.0,
+            raw: None,
+        }
+    }
+}
+
+/// Why an image was rejected.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum ImageError {
+    /// Neither a PNG nor a PGM signature, and no raw layout given.
+    UnknownFormat,
+    /// The header or data is damaged or truncated, or the import options
+    /// could not be read.
+    Malformed { reason: String },
+    /// A valid image this importer doesn't handle (bit depth, colour type).
+    Unsupported { reason: String },
+    /// A raw buffer's length doesn't match its declared layout.
+    SizeMismatch { expected: usize, found: usize },
+    /// Width or height is zero.
+    Empty,
+}
+
+impl std::fmt::Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::UnknownFormat => write!(f, "not a PNG or PGM image"),
+            ImageError::Malformed { reason } => write!(f, "malformed image: {reason}"),
+            ImageError::Unsupported { reason } => write!(f, "unsupported image: {reason}"),
+            ImageError::SizeMismatch { expected, found } => {
+                write!(f, "raw buffer has {found} bytes, layout needs {expected}")
+            }
+            ImageError::Empty => write!(f, "image has no pixels"),
+        }
+    }
+}
+
+fn malformed(reason: impl Into<String>) -> ImageError {
+    ImageError::Malformed {
+        reason: reason.into(),
+    }
+}
+
+fn unsupported(reason: impl Into<String>) -> ImageError {
+    ImageError::Unsupported {
+        reason: reason.into(),
+    }
+}
+
+/// `width × height × bytes_per` in bytes, or `Malformed` when a header's
+/// size doesn't fit in `usize` (it can on 32-bit targets).
+fn buffer_len(width: u32, height: u32, bytes_per: usize) -> Result<usize, ImageError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(bytes_per))
+        .ok_or_else(|| malformed(format!("{width}×{height} image is too large")))
+}
+
+impl GrayImage {
+    /// Decode a PNG or PGM file, sniffed by its signature.
+    pub fn decode(bytes: &[u8]) -> Result<GrayImage, ImageError> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            decode_png(bytes)
+        } else if bytes.starts_with(b"P5") || bytes.starts_with(b"P2") {
+            decode_pgm(bytes)
+        } else {
+            Err(ImageError::UnknownFormat)
+        }
+    }
+
+    /// Wrap a headerless sample buffer.
+    pub fn from_raw(bytes: &[u8], format: &RawFormat) -> Result<GrayImage, ImageError> {
+        if format.width == 0 || format.height == 0 {
+            return Err(ImageError::Empty);
+        }
+        let (samples, max) = match format.bit_depth {
+            8 => (bytes.iter().map(|&b| b as u16).collect::<Vec<_>>(), 255),
+            16 => {
+                let samples = bytes
+                    .chunks_exact(2)
+                    .map(|b| {
+                        if format.little_endian {
+                            u16::from_le_bytes([b[0], b[1]])
+                        } else {
+                            u16::from_be_bytes([b[0], b[1]])
+                        }
+                    })
+                    .collect();
+                (samples, u16::MAX)
+            }
+            d => return Err(unsupported(format!("{d}-bit raw samples"))),
+        };
+        let expected = buffer_len(format.width, format.height, format.bit_depth as usize / 8)?;
+        if bytes.len() != expected {
+            return Err(ImageError::SizeMismatch {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        Ok(GrayImage {
+            width: format.width,
+            height: format.height,
+            max,
+            samples,
+        })
+    }
+
+    /// Decode `bytes` as `opts` says: the raw layout if given, else sniffed.
+    pub fn load(bytes: &[u8], opts: &ImportOpts) -> Result<GrayImage, ImageError> {
+        match &opts.raw {
+            Some(format) => GrayImage::from_raw(bytes, format),
+            None => GrayImage::decode(bytes),
+        }
+    }
+
+    fn at(&self, col: usize, row: usize) -> f64 {
+        self.samples[row * self.width as usize + col] as f64
+    }
+
+    /// Bilinear sample at pixel-space `(u, v)` (pixel `(c, r)` has its centre
+    /// at `(c + 0.5, r + 0.5)`), normalized to `0..=1`. Rows clamp at the
+    /// edges; columns wrap when `wrap_x`, else clamp.
+    pub fn sample(&self, u: f64, v: f64, wrap_x: bool) -> f64 {
+        let (w, h) = (self.width as i64, self.height as i64);
+        let (fx, fy) = (u - 0.5, v - 0.5);
+        let (x0, y0) = (fx.floor(), fy.floor());
+        let (tx, ty) = (fx - x0, fy - y0);
+        let col = |c: i64| {
+            if wrap_x {
+                c.rem_euclid(w) as usize
+            } else {
+                c.clamp(0, w - 1) as usize
+            }
+        };
+        let row = |r: i64| r.clamp(0, h - 1) as usize;
+        let (c0, c1) = (col(x0 as i64), col(x0 as i64 + 1));
+        let (r0, r1) = (row(y0 as i64), row(y0 as i64 + 1));
+        let top = self.at(c0, r0) * (1.0 - tx) + self.at(c1, r0) * tx;
+        let bottom = self.at(c0, r1) * (1.0 - tx) + self.at(c1, r1) * tx;
+        (top * (1.0 - ty) + bottom * ty) / self.max.max(1) as f64
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<GrayImage, ImageError> {
+    let mut decoder = png::Decoder::new(bytes);
+    // Palette → RGB(A), 1/2/4-bit gray → 8-bit; 16-bit stays 16-bit.
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().map_err(|e| malformed(e.to_string()))?;
+    let mut buf = vec![0u8; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| malformed(e.to_string()))?;
+    if info.width == 0 || info.height == 0 {
+        return Err(ImageError::Empty);
+    }
+    // Colour channels to average; alpha (if any) follows them and is skipped.
+    let (colours, channels) = match info.color_type {
+        png::ColorType::Grayscale => (1, 1),
+        png::ColorType::GrayscaleAlpha => (1, 2),
+        png::ColorType::Rgb => (3, 3),
+        png::ColorType::Rgba => (3, 4),
+        png::ColorType::Indexed => return Err(unsupported("unexpanded palette")),
+    };
+    let (bytes_per, max) = match info.bit_depth {
+        png::BitDepth::Eight => (1, 255u16),
+        png::BitDepth::Sixteen => (2, u16::MAX),
+        d => return Err(unsupported(format!("{d:?} bit depth"))),
+    };
+    let read = |px: &[u8], k: usize| -> u32 {
+        if bytes_per == 1 {
+            px[k] as u32
+        } else {
+            u16::from_be_bytes([px[2 * k], px[2 * k + 1]]) as u32
+        }
+    };
+    let mut samples = Vec::with_capacity(buffer_len(info.width, info.height, 1)?);
+    for line in buf.chunks(info.line_size).take(info.height as usize) {
+        for px in line
+            .chunks_exact(channels * bytes_per)
+            .take(info.width as usize)
+        {
+            let sum: u32 = (0..colours).map(|k| read(px, k)).sum();
+            samples.push(((sum + colours as u32 / 2) / colours as u32) as u16);
+        }
+    }
+    Ok(GrayImage {
+        width: info.width,
+        height: info.height,
+        max,
+        samples,
+    })
+}
+
+/// Netpbm grayscale: `P5` (binary, big-endian when `maxval > 255`) or `P2`
+/// (plain decimal). `#` comments may appear anywhere in the header.
+fn decode_pgm(bytes: &[u8]) -> Result<GrayImage, ImageError> {
+    let plain = bytes.starts_with(b"P2");
+    let mut pos = 2;
+    let next_number = |pos: &mut usize| -> Result<u32, ImageError> {
+        loop {
+            match bytes.get(*pos) {
+                Some(b'#') => {
+                    while bytes.get(*pos).is_some_and(|&b| b != b'\n') {
+                        *pos += 1;
+                    }
+                }
+                Some(b) if b.is_ascii_whitespace() => *pos += 1,
+                Some(_) => break,
+                None => return Err(malformed("truncated PGM")),
+            }
+        }
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+            *pos += 1;
+        }
+        std::str::from_utf8(&bytes[start..*pos])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| malformed(format!("expected a number at byte {start}")))
+    };
+    let width = next_number(&mut pos)?;
+    let height = next_number(&mut pos)?;
+    let maxval = next_number(&mut pos)?;
+    if width == 0 || height == 0 {
+        return Err(ImageError::Empty);
+    }
+    if maxval == 0 || maxval > u16::MAX as u32 {
+        return Err(malformed(format!("maxval {maxval}")));
+    }
+    let pixels = buffer_len(width, height, 1)?;
+    let samples: Vec<u16> = if plain {
+        (0..pixels)
+            .map(|_| next_number(&mut pos).map(|v| v.min(maxval) as u16))
+            .collect::<Result<_, _>>()?
+    } else {
+        // Exactly one whitespace byte separates the header from the data.
+        let data = bytes.get(pos + 1..).unwrap_or(&[]);
+        let wide = maxval > 255;
+        let needed = buffer_len(width, height, if wide { 2 } else { 1 })?;
+        if data.len() < needed {
+            return Err(malformed(format!(
+                "PGM data has {} bytes, needs {needed}",
+                data.len()
+            )));
+        }
+        if wide {
+            data[..needed]
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .collect()
+        } else {
+            data[..needed].iter().map(|&b| b as u16).collect()
+        }
+    };
+    Ok(GrayImage {
+        width,
+        height,
+        max: maxval as u16,
+        samples,
+    })
+}
+
+/// Map a normalized sample onto `cells.h`: `sea_level` → `SEA_LEVEL`,
+/// linear on either side. Water never rounds up onto land.
+fn to_height(v: f64, sea_level: f64) -> u8 {
+    let sea = SEA_LEVEL as f64;
+    let t = sea_level.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    if v < t {
+        (v / t * sea).min(sea - 1.0).round() as u8
+    } else if t >= 1.0 {
+        100
+    } else {
+        (sea + (v - t) / (1.0 - t) * (100.0 - sea)).round() as u8
+    }
+}
+
+/// Resample `image` onto `mesh`: one height per cell from the bilinear
+/// sample at the cell centre.
+pub fn heights_from_image(mesh: &Mesh, image: &GrayImage, sea_level: f64) -> Vec<u8> {
+    let sx = image.width as f64 / mesh.world_w;
+    let sy = image.height as f64 / mesh.world_h;
+    let wrap_x = mesh.topology.wraps_x();
+    mesh.points
+        .iter()
+        .map(|&[x, y]| to_height(image.sample(x * sx, y * sy, wrap_x), sea_level))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// A per-cell layer `rasterize` can draw.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    H,
+    Temp,
+    Prec,
+    Fl,
+    Biome,
+    State,
+    Culture,
+    Religion,
+}
+
+impl Layer {
+    fn is_categorical(self) -> bool {
+        matches!(
+            self,
+            Layer::Biome | Layer::State | Layer::Culture | Layer::Religion
+        )
+    }
+}
+
+/// How a pixel takes its value from the cells around it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Sampling {
+    /// The cell whose centre is nearest the pixel centre.
+    #[default]
+    Nearest,
+    /// Inverse-distance weighted over the nearest cell and its neighbours.
+    /// Continuous layers only; categorical layers fall back to `Nearest`.
+    Interpolated,
+}
+
+/// What to rasterize, and at what size.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ExportOpts {
+    pub layer: Layer,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub sampling: Sampling,
+}
+
+/// Largest accepted raster side, in pixels.
+pub const MAX_EXPORT_SIDE: u32 = 16384;
+
+/// Largest accepted raster area, in pixels (8192²): a full `MAX_EXPORT_SIDE`
+/// square would need a 1 GiB RGBA buffer.
+pub const MAX_EXPORT_PIXELS: u64 = 1 << 26;
+
+/// Why an export was rejected.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum ExportError {
+    /// A side is zero or larger than `MAX_EXPORT_SIDE`, or the raster has
+    /// more than `MAX_EXPORT_PIXELS` pixels.
+    BadSize { width: u32, height: u32 },
+    /// The export options or pack could not be read.
+    Malformed { reason: String },
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::BadSize { width, height } => write!(
+                f,
+                "raster size {width}×{height} outside 1..={MAX_EXPORT_SIDE} \
+                 or over {MAX_EXPORT_PIXELS} pixels"
+            ),
+            ExportError::Malformed { reason } => write!(f, "malformed export input: {reason}"),
+        }
+    }
+}
+
+/// A rasterized layer. Pixels are row-major, row 0 at `y = 0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Raster {
+    /// Continuous layers, full-scale `0..=65535`:
+    /// `h` × 655.35, `(temp + 128)` × 257, `prec` × 257, `fl` as is.
+    Gray16 {
+        width: u32,
+        height: u32,
+        samples: Vec<u16>,
+    },
+    /// Categorical layers: `indices` into `palette` (RGBA). Entry 0 is
+    /// transparent (unassigned); the rest follow ascending layer ids.
+    Paletted {
+        width: u32,
+        height: u32,
+        palette: Vec<[u8; 4]>,
+        indices: Vec<u16>,
+    },
+}
+
+/// Fill for an id on the map that the `Pack` doesn't list.
+const MISSING_COLOR: u32 = 0x808080;
+
+fn rgba(color: u32) -> [u8; 4] {
+    let [_, r, g, b] = color.to_be_bytes();
+    [r, g, b, 255]
+}
+
+/// A continuous layer's value at cell `c`, already on the 16-bit scale.
+fn gray16_value(grid: &Grid, layer: Layer, c: usize) -> f64 {
+    let cells = &grid.cells;
+    match layer {
+        Layer::H => cells.h[c] as f64 * 655.35,
+        Layer::Temp => (cells.temp[c] as f64 + 128.0) * 257.0,
+        Layer::Prec => cells.prec[c] as f64 * 257.0,
+        Layer::Fl => cells.fl[c] as f64,
+        _ => unreachable!("categorical layer"),
+    }
+}
+
+/// A categorical layer's id at cell `c` (`< 0` = unassigned).
+fn category(grid: &Grid, layer: Layer, c: usize) -> i32 {
+    let cells = &grid.cells;
+    match layer {
+        Layer::Biome => cells.biome[c] as i32,
+        Layer::State => cells.state[c],
+        Layer::Culture => cells.culture[c],
+        Layer::Religion => cells.religion[c],
+        _ => unreachable!("continuous layer"),
+    }
+}
+
+/// Packed color for a categorical id.
+fn category_color(layer: Layer, id: i32, pack: Option<&Pack>) -> u32 {
+    let id = id as u32;
+    let found = match layer {
+        Layer::Biome => BIOMES.get(id as usize).map(|b| b.color),
+        Layer::State => pack.and_then(|p| p.states.iter().find(|s| s.id == id).map(|s| s.color)),
+        Layer::Culture => {
+            pack.and_then(|p| p.cultures.iter().find(|s| s.id == id).map(|s| s.color))
+        }
+        Layer::Religion => {
+            pack.and_then(|p| p.religions.iter().find(|s| s.id == id).map(|s| s.color))
+        }
+        _ => None,
+    };
+    found.unwrap_or(MISSING_COLOR)
+}
+
+/// Rasterize `opts.layer` of `grid` at `opts.width × opts.height` pixels
+/// spanning the whole world. `pack` supplies the state / culture / religion
+/// colors; ids it doesn't list (or every id, without a pack) draw gray.
+pub fn rasterize(
+    grid: &Grid,
+    pack: Option<&Pack>,
+    opts: &ExportOpts,
+) -> Result<Raster, ExportError> {
+    let (width, height) = (opts.width, opts.height);
+    if width == 0
+        || height == 0
+        || width > MAX_EXPORT_SIDE
+        || height > MAX_EXPORT_SIDE
+        || width as u64 * height as u64 > MAX_EXPORT_PIXELS
+    {
+        return Err(ExportError::BadSize { width, height });
+    }
+    let mesh = &grid.mesh;
+    let (sx, sy) = (mesh.world_w / width as f64, mesh.world_h / height as f64);
+    let centres = (0..height)
+        .flat_map(move |r| (0..width).map(move |c| [(c as f64 + 0.5) * sx, (r as f64 + 0.5) * sy]));
+    let index = SpatialIndex::build(mesh);
+    let nearest = |[x, y]: [f64; 2]| index.nearest(mesh, x, y).expect("mesh has cells") as usize;
+
+    if opts.layer.is_categorical() {
+        // Palette over every id present, ascending; per-cell palette index.
+        let n = mesh.points.len();
+        let ids: std::collections::BTreeSet<i32> = (0..n)
+            .map(|c| category(grid, opts.layer, c))
+            .filter(|&id| id >= 0)
+            .collect();
+        let mut palette = vec![[0u8; 4]];
+        let mut slot = std::collections::BTreeMap::new();
+        for id in ids {
+            slot.insert(id, palette.len() as u16);
+            palette.push(rgba(category_color(opts.layer, id, pack)));
+        }
+        let cell_index: Vec<u16> = (0..n)
+            .map(|c| {
+                slot.get(&category(grid, opts.layer, c))
+                    .copied()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let indices = centres.map(|q| cell_index[nearest(q)]).collect();
+        return Ok(Raster::Paletted {
+            width,
+            height,
+            palette,
+            indices,
+        });
+    }
+
+    let spacing = (mesh.world_w * mesh.world_h / mesh.points.len() as f64).sqrt();
+    let eps = (0.05 * spacing).powi(2);
+    let samples = centres
+        .map(|q| {
+            let p = nearest(q);
+            let v = match opts.sampling {
+                Sampling::Nearest => gray16_value(grid, opts.layer, p),
+                Sampling::Interpolated => {
+                    let (mut sw, mut sv) = (0.0, 0.0);
+                    let ring = mesh.cells.neighbors_of_cell(p);
+                    for k in std::iter::once(p as u32).chain(ring.iter().copied()) {
+                        let k = k as usize;
+                        let w = 1.0 / (mesh.distance_sq(q, mesh.points[k]) + eps);
+                        sw += w;
+                        sv += w * gray16_value(grid, opts.layer, k);
+                    }
+                    sv / sw
+                }
+            };
+            v.round().clamp(0.0, u16::MAX as f64) as u16
+        })
+        .collect();
+    Ok(Raster::Gray16 {
+        width,
+        height,
+        samples,
+    })
+}
+
+impl Raster {
+    /// The raster as RGBA8 bytes (gray samples keep their high byte).
+    pub fn to_rgba(&self) -> Vec<u8> {
+        match self {
+            Raster::Gray16 { samples, .. } => samples
+                .iter()
+                .flat_map(|&s| {
+                    let v = (s >> 8) as u8;
+                    [v, v, v, 255]
+                })
+                .collect(),
+            Raster::Paletted {
+                palette, indices, ..
+            } => indices.iter().flat_map(|&i| palette[i as usize]).collect(),
+        }
+    }
+
+    /// Encode as PNG: 16-bit grayscale, or 8-bit indexed with transparency
+    /// (falling back to RGBA8 past 256 palette entries).
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let (width, height) = match self {
+            Raster::Gray16 { width, height, .. } | Raster::Paletted { width, height, .. } => {
+                (*width, *height)
+            }
+        };
+        let mut enc = png::Encoder::new(&mut out, width, height);
+        let data: Vec<u8> = match self {
+            Raster::Gray16 { samples, .. } => {
+                enc.set_color(png::ColorType::Grayscale);
+                enc.set_depth(png::BitDepth::Sixteen);
+                samples.iter().flat_map(|s| s.to_be_bytes()).collect()
+            }
+            Raster::Paletted {
+                palette, indices, ..
+            } if palette.len() <= 256 => {
+                enc.set_color(png::ColorType::Indexed);
+                enc.set_depth(png::BitDepth::Eight);
+                enc.set_palette(
+                    palette
+                        .iter()
+                        .flat_map(|c| [c[0], c[1], c[2]])
+                        .collect::<Vec<_>>(),
+                );
+                enc.set_trns(palette.iter().map(|c| c[3]).collect::<Vec<_>>());
+                indices.iter().map(|&i| i as u8).collect()
+            }
+            Raster::Paletted { .. } => {
+                enc.set_color(png::ColorType::Rgba);
+                enc.set_depth(png::BitDepth::Eight);
+                self.to_rgba()
+            }
+        };
+        let mut writer = enc.write_header().expect("png: header");
+        writer.write_image_data(&data).expect("png: image data");
+        drop(writer);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh;
+
+    fn encode_png(width: u32, height: u32, depth: png::BitDepth, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut enc = png::Encoder::new(&mut out, width, height);
+        enc.set_color(png::ColorType::Grayscale);
+        enc.set_depth(depth);
+        enc.write_header().unwrap().write_image_data(data).unwrap();
+        out
+    }
+
+    /// The same 3×2 image in every supported container decodes to the same
+    /// normalized samples.
+    #[test]
+    fn formats_decode_alike() {
+        let values8 = [0u8, 51, 102, 153, 204, 255];
+        let p5 = [b"P5\n# painted\n3 2\n255\n".as_slice(), &values8].concat();
+        let p2 = b"P2 3 2 255\n0 51 102\n153 204 255\n";
+        let png8 = encode_png(3, 2, png::BitDepth::Eight, &values8);
+        let wide: Vec<u16> = values8.iter().map(|&v| v as u16 * 257).collect();
+        let be: Vec<u8> = wide.iter().flat_map(|v| v.to_be_bytes()).collect();
+        let le: Vec<u8> = wide.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let png16 = encode_png(3, 2, png::BitDepth::Sixteen, &be);
+        let p5_16 = [b"P5 3 2 65535\n".as_slice(), &be].concat();
+        let raw = |bytes: &[u8], bit_depth, little_endian| {
+            let format = RawFormat {
+                width: 3,
+                height: 2,
+                bit_depth,
+                little_endian,
+            };
+            GrayImage::from_raw(bytes, &format).unwrap()
+        };
+        let images = [
+            GrayImage::decode(&p5).unwrap(),
+            GrayImage::decode(p2).unwrap(),
+            GrayImage::decode(&png8).unwrap(),
+            GrayImage::decode(&png16).unwrap(),
+            GrayImage::decode(&p5_16).unwrap(),
+            raw(&values8, 8, true),
+            raw(&le, 16, true),
+            raw(&be, 16, false),
+        ];
+        for img in &images {
+            assert_eq!((img.width, img.height), (3, 2));
+            let norm: Vec<f64> = img
+                .samples
+                .iter()
+                .map(|&s| s as f64 / img.max as f64)
+                .collect();
+            for (k, n) in norm.iter().enumerate() {
+                assert!((n - k as f64 * 0.2).abs() < 1e-9, "{norm:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn bad_inputs_are_rejected() {
+        assert_eq!(GrayImage::decode(b"GIF89a"), Err(ImageError::UnknownFormat));
+        assert!(matches!(
+            GrayImage::decode(b"P5 4 4 255\n\x01\x02"),
+            Err(ImageError::Malformed { .. })
+        ));
+        assert!(matches!(
+            GrayImage::decode(b"P2 2 1 255\n7 x"),
+            Err(ImageError::Malformed { .. })
+        ));
+        assert_eq!(GrayImage::decode(b"P5 0 4 255\n"), Err(ImageError::Empty));
+        assert!(matches!(
+            GrayImage::decode(b"P5 4294967295 4294967295 65535\n\x01"),
+            Err(ImageError::Malformed { .. })
+        ));
+        let format = RawFormat {
+            width: 4,
+            height: 4,
+            bit_depth: 16,
+            little_endian: true,
+        };
+        assert_eq!(
+            GrayImage::from_raw(&[0; 16], &format),
+            Err(ImageError::SizeMismatch {
+                expected: 32,
+                found: 16
+            })
+        );
+        let png = encode_png(2, 2, png::BitDepth::Eight, &[1, 2, 3, 4]);
+        assert!(matches!(
+            GrayImage::decode(&png[..png.len() - 20]),
+            Err(ImageError::Malformed { .. })
+        ));
+    }
+
+    /// The sea-level threshold lands exactly on `SEA_LEVEL`; the default is
+    /// a straight `0..=1 → 0..=100` ramp.
+    #[test]
+    fn sea_level_mapping() {
+        let sea = ImportOpts::default().sea_level;
+        assert_eq!(to_height(0.0, sea), 0);
+        assert_eq!(to_height(0.5, sea), 50);
+        assert_eq!(to_height(1.0, sea), 100);
+        assert_eq!(to_height(0.6, 0.6), SEA_LEVEL);
+        assert_eq!(to_height(0.5999, 0.6), SEA_LEVEL - 1);
+        assert_eq!(to_height(0.3, 0.6), 10);
+        assert_eq!(to_height(0.8, 0.6), 60);
+        assert_eq!(to_height(0.5, 0.0), 60);
+    }
+
+    /// A left-to-right ramp resamples onto the mesh as heights that follow
+    /// `x`, and bilinear sampling reproduces interior values exactly.
+    #[test]
+    fn ramp_resamples_by_cell_position() {
+        let mesh = mesh::build(2000, 4);
+        let (w, h) = (64u32, 16u32);
+        let data: Vec<u8> = (0..h).flat_map(|_| (0..w).map(|c| (c * 4) as u8)).collect();
+        let img = GrayImage::from_raw(
+            &data,
+            &RawFormat {
+                width: w,
+                height: h,
+                bit_depth: 8,
+                little_endian: true,
+            },
+        )
+        .unwrap();
+        // Between pixel centres 10 and 11 (values 40 and 44).
+        assert!((img.sample(11.0, 3.0, false) * 255.0 - 42.0).abs() < 1e-9);
+        let heights = heights_from_image(&mesh, &img, 0.2);
+        for (k, &[x, _]) in mesh.points.iter().enumerate() {
+            let u = (x / mesh.world_w * w as f64 - 0.5).clamp(0.0, (w - 1) as f64);
+            // Straight ramp, except water just under the threshold stays water.
+            let mut expected = u * 4.0 / 255.0 * 100.0;
+            if expected < SEA_LEVEL as f64 {
+                expected = expected.min(SEA_LEVEL as f64 - 1.0);
+            }
+            assert!(
+                (heights[k] as f64 - expected).abs() <= 0.5 + 1e-9,
+                "cell {k}: {} vs {expected}",
+                heights[k]
+            );
+        }
+    }
+
+    /// On a wrapping topology the columns wrap: the left edge blends with
+    /// the rightmost column instead of clamping.
+    #[test]
+    fn wrap_blends_across_the_seam() {
+        let img = GrayImage {
+            width: 4,
+            height: 1,
+            max: 100,
+            samples: vec![0, 0, 0, 100],
+        };
+        assert_eq!(img.sample(0.0, 0.5, false), 0.0);
+        assert!((img.sample(0.0, 0.5, true) - 0.5).abs() < 1e-12);
+    }
+
+    fn export(grid: &Grid, pack: Option<&Pack>, layer: Layer, sampling: Sampling) -> Raster {
+        let opts = ExportOpts {
+            layer,
+            width: 200,
+            height: 160,
+            sampling,
+        };
+        rasterize(grid, pack, &opts).unwrap()
+    }
+
+    /// A 16-bit height export read back through the importer lands on the
+    /// same heights, cell for cell.
+    #[test]
+    fn height_export_round_trips() {
+        let grid = crate::generate_world_inner(7, 1500, &crate::climate::ClimateOpts::default());
+        let png = export(&grid, None, Layer::H, Sampling::Nearest).to_png();
+        let img = GrayImage::decode(&png).unwrap();
+        assert_eq!((img.width, img.height, img.max), (200, 160, 65535));
+        // Sample straight at each cell's own pixel: the nearest-cell raster
+        // returns that cell's height there.
+        let index = SpatialIndex::build(&grid.mesh);
+        for (c, &[x, y]) in grid.mesh.points.iter().enumerate() {
+            let col = (x / grid.mesh.world_w * 200.0) as usize;
+            let row = (y / grid.mesh.world_h * 160.0) as usize;
+            let (cx, cy) = (
+                (col as f64 + 0.5) * grid.mesh.world_w / 200.0,
+                (row as f64 + 0.5) * grid.mesh.world_h / 160.0,
+            );
+            if index.nearest(&grid.mesh, cx, cy) != Some(c as u32) {
+                continue;
+            }
+            let v = img.samples[row * 200 + col] as f64 / 655.35;
+            assert!((v - grid.cells.h[c] as f64).abs() < 0.01, "cell {c}");
+        }
+    }
+
+    /// State pixels carry the pack's colors; unassigned cells are
+    /// transparent, and ids the pack doesn't list fall back to gray.
+    #[test]
+    fn state_layer_uses_pack_colors() {
+        let mut grid = Grid::from_mesh(&mesh::build(800, 3), 3);
+        for (c, s) in grid.cells.state.iter_mut().enumerate() {
+            *s = [-1, 1, 2][c % 3];
+        }
+        let pack = Pack {
+            states: vec![crate::entities::State {
+                id: 1,
+                color: 0x123456,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let raster = export(&grid, Some(&pack), Layer::State, Sampling::Interpolated);
+        let Raster::Paletted {
+            palette, indices, ..
+        } = &raster
+        else {
+            panic!("categorical layers are paletted");
+        };
+        assert_eq!(
+            palette,
+            &vec![
+                [0, 0, 0, 0],
+                [0x12, 0x34, 0x56, 255],
+                [0x80, 0x80, 0x80, 255]
+            ]
+        );
+        assert!((0..3u16).all(|i| indices.contains(&i)));
+
+        let png = raster.to_png();
+        let mut decoder = png::Decoder::new(png.as_slice());
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        assert_eq!(&buf[..info.buffer_size()], raster.to_rgba().as_slice());
+    }
+
+    /// Interpolated sampling is deterministic and smooths the nearest-cell
+    /// steps; categorical layers ignore it.
+    #[test]
+    fn interpolated_sampling_smooths_continuous_layers() {
+        let grid = crate::generate_world_inner(11, 1200, &crate::climate::ClimateOpts::default());
+        let near = export(&grid, None, Layer::Temp, Sampling::Nearest);
+        let a = export(&grid, None, Layer::Temp, Sampling::Interpolated);
+        let b = export(&grid, None, Layer::Temp, Sampling::Interpolated);
+        assert_eq!(a, b);
+        assert_ne!(a, near);
+        assert_eq!(
+            export(&grid, None, Layer::Biome, Sampling::Interpolated),
+            export(&grid, None, Layer::Biome, Sampling::Nearest)
+        );
+    }
+
+    #[test]
+    fn bad_sizes_are_rejected() {
+        let grid = Grid::from_mesh(&mesh::build(200, 1), 1);
+        for (width, height) in [
+            (0, 10),
+            (10, 0),
+            (MAX_EXPORT_SIDE + 1, 1),
+            (MAX_EXPORT_SIDE, MAX_EXPORT_SIDE),
+        ] {
+            let opts = ExportOpts {
+                layer: Layer::H,
+                width,
+                height,
+                sampling: Sampling::Nearest,
+            };
+            assert_eq!(
+                rasterize(&grid, None, &opts),
+                Err(ExportError::BadSize { width, height })
+            );
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Regional refinement — regenerate a sub-area of a world at higher cell
+//! density.
+//!
+//! Takes a parent `Grid` and a region (a world-space rectangle, or a cell set
+//! whose bounding box is used) and builds a child `Grid` over just that area:
+//!
+//! 1. A fresh flat `Mesh` over the region's `w × h` via `mesh::build_region`,
+//!    in the child's local coordinates `[0, w) × [0, h)`. The child keeps
+//!    `origin` so callers can map back (`parent = origin + local`).
+//! 2. Continuous layers (`h`, `temp`, `prec`) are inverse-distance weighted
+//!    from the nearest parent cell and its ring of neighbours, so the child is
+//!    a smooth resampling rather than a blocky upscale.
+//! 3. Seeded detail noise perturbs `h` (mid-frequency: per-cell jitter
+//!    smoothed over the child adjacency), giving coastlines and hillsides the
+//!    detail the parent resolution could not carry.
+//! 4. Categorical layers (`biome` and the entity ids) come from the nearest
+//!    parent cell. Cells whose land/water status the noise flipped are
+//!    repaired: new water drops biome and ownership (the `repair_entities`
+//!    convention), new land gets a fresh biome and its owner from an adjacent
+//!    inherited land cell.
+//! 5. Rivers are re-traced, not resampled: every parent river cell is joined
+//!    to the cell it drains into, and that segment is rasterized onto the
+//!    child cells along it with interpolated flux, so rivers stay thin lines
+//!    at the new resolution. Burgs land on the child cell nearest to their
+//!    parent cell.
+//!
+//! Determinism: the child mesh is seeded with `seed`, the detail noise with a
+//! salted `StdRng::seed_from_u64(seed)`; every loop runs in cell-id order.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::biomes;
+use crate::grid::Grid;
+use crate::heightmap::SEA_LEVEL;
+use crate::mesh;
+use crate::spatial::SpatialIndex;
+
+/// Peak height change of the detail noise, in `cells.h` units.
+const DETAIL_AMPLITUDE: f64 = 4.0;
+
+/// Salt for the detail-noise RNG so it does not replay the child mesh's
+/// point-sampling stream (both are seeded from the same `seed`).
+const DETAIL_NOISE_SALT: u64 = 0x5EED_DE7A_11ED;
+
+/// The area of the parent world to refine.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind")]
+pub enum Region {
+    /// World-space rectangle `[x0, x1] × [y0, y1]` (any corner order).
+    Rect { x0: f64, y0: f64, x1: f64, y1: f64 },
+    /// A set of parent cells; the child covers their polygons' bounding box.
+    Cells { cells: Vec<u32> },
+}
+
+/// A refined child world.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RegionGrid {
+    /// The child world, in local coordinates.
+    pub grid: Grid,
+    /// Parent-world position of the child's local `(0, 0)`.
+    pub origin: [f64; 2],
+    /// Nearest parent cell of every child cell.
+    pub parent_cell: Vec<u32>,
+}
+
+/// Resolve `region` to a parent-world rectangle `[x0, y0, x1, y1]`, clipped
+/// to the parent world. `None` if it is empty or thinner than one unit.
+pub fn region_bounds(parent: &Grid, region: &Region) -> Option<[f64; 4]> {
+    let (w, h) = (parent.mesh.world_w, parent.mesh.world_h);
+    let [x0, y0, x1, y1] = match region {
+        Region::Rect { x0, y0, x1, y1 } => [x0.min(*x1), y0.min(*y1), x0.max(*x1), y0.max(*y1)],
+        Region::Cells { cells } => {
+            let mut b = [
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ];
+            let csr = &parent.mesh.cells;
+            for &cell in cells {
+                let cell = cell as usize;
+                if cell + 1 >= csr.i.len() {
+                    continue;
+                }
+                for &v in &csr.v[csr.i[cell] as usize..csr.i[cell + 1] as usize] {
+                    let [x, y] = parent.mesh.vertices.p[v as usize];
+                    b = [b[0].min(x), b[1].min(y), b[2].max(x), b[3].max(y)];
+                }
+            }
+            b
+        }
+    };
+    let b = [x0.max(0.0), y0.max(0.0), x1.min(w), y1.min(h)];
+    if !(b[2] - b[0] >= 1.0 && b[3] - b[1] >= 1.0) {
+        return None;
+    }
+    Some(b)
+}
+
+/// Build a `cell_count`-cell child world over `region` of `parent`.
+/// Returns `None` if the region resolves to an empty rectangle.
+pub fn refine_region(
+    parent: &Grid,
+    region: &Region,
+    cell_count: u32,
+    seed: u32,
+) -> Option<RegionGrid> {
+    let [x0, y0, x1, y1] = region_bounds(parent, region)?;
+    if parent.mesh.points.is_empty() {
+        return None;
+    }
+    let child_mesh = mesh::build_region(cell_count, seed, x1 - x0, y1 - y0);
+    let mut grid = Grid::from_mesh(&child_mesh, seed as u64);
+    let n = grid.cell_count();
+    let pm = &parent.mesh;
+    let pc = &parent.cells;
+    let (parent_index, child_index) = (SpatialIndex::build(pm), SpatialIndex::build(&grid.mesh));
+
+    let parent_cell: Vec<u32> = grid
+        .mesh
+        .points
+        .iter()
+        .map(|&[x, y]| {
+            parent_index
+                .nearest(pm, x0 + x, y0 + y)
+                .expect("parent has cells")
+        })
+        .collect();
+
+    // Continuous layers: IDW over the nearest parent cell and its neighbours.
+    let parent_spacing = (pm.world_w * pm.world_h / pm.points.len() as f64).sqrt();
+    let eps = (0.05 * parent_spacing).powi(2);
+    let mut h = vec![0.0f64; n];
+    for c in 0..n {
+        let [x, y] = grid.mesh.points[c];
+        let q = [x0 + x, y0 + y];
+        let p = parent_cell[c] as usize;
+        let (mut sw, mut sh, mut st, mut sp) = (0.0, 0.0, 0.0, 0.0);
+        for k in std::iter::once(p as u32).chain(pm.cells.neighbors_of_cell(p).iter().copied()) {
+            let k = k as usize;
+            let w = 1.0 / (pm.distance_sq(q, pm.points[k]) + eps);
+            sw += w;
+            sh += w * pc.h[k] as f64;
+            st += w * pc.temp[k] as f64;
+            sp += w * pc.prec[k] as f64;
+        }
+        h[c] = sh / sw;
+        grid.cells.temp[c] = (st / sw).round() as i8;
+        grid.cells.prec[c] = (sp / sw).round() as u8;
+    }
+
+    // Detail noise: per-cell jitter, smoothed twice over the child adjacency
+    // and renormalized to ±1 (mid-frequency bumps a few cells across).
+    let mut rng = StdRng::seed_from_u64(seed as u64 ^ DETAIL_NOISE_SALT);
+    let mut noise: Vec<f64> = (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect();
+    for _ in 0..2 {
+        noise = (0..n)
+            .map(|c| {
+                let neigh = grid.mesh.cells.neighbors_of_cell(c);
+                let sum: f64 = neigh.iter().map(|&k| noise[k as usize]).sum::<f64>() + noise[c];
+                sum / (neigh.len() + 1) as f64
+            })
+            .collect();
+    }
+    let peak = noise.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(1e-9);
+    for c in 0..n {
+        let v = h[c] + DETAIL_AMPLITUDE * noise[c] / peak;
+        grid.cells.h[c] = v.round().clamp(0.0, 100.0) as u8;
+    }
+
+    // Categorical layers from the nearest parent cell.
+    for (c, &p) in parent_cell.iter().enumerate() {
+        let p = p as usize;
+        grid.cells.biome[c] = pc.biome[p];
+        grid.cells.state[c] = pc.state[p];
+        grid.cells.province[c] = pc.province[p];
+        grid.cells.culture[c] = pc.culture[p];
+        grid.cells.religion[c] = pc.religion[p];
+    }
+
+    // Burgs: on the child cell nearest the parent burg cell, kept dry.
+    for p in 0..pm.points.len() {
+        if pc.burg[p] <= 0 {
+            continue;
+        }
+        let [px, py] = pm.points[p];
+        if px < x0 || px > x1 || py < y0 || py > y1 {
+            continue;
+        }
+        let c = child_index
+            .nearest(&grid.mesh, px - x0, py - y0)
+            .expect("child has cells") as usize;
+        grid.cells.burg[c] = pc.burg[p];
+        grid.cells.h[c] = grid.cells.h[c].max(SEA_LEVEL);
+    }
+
+    repair_flipped_cells(&mut grid, parent, &parent_cell);
+    trace_rivers(&mut grid, &child_index, parent, [x0, y0, x1, y1]);
+
+    Some(RegionGrid {
+        grid,
+        origin: [x0, y0],
+        parent_cell,
+    })
+}
+
+/// Fix cells whose land/water status differs from their parent cell after
+/// the detail noise.
+fn repair_flipped_cells(grid: &mut Grid, parent: &Grid, parent_cell: &[u32]) {
+    let mut new_land: Vec<u32> = Vec::new();
+    for (c, &p) in parent_cell.iter().enumerate() {
+        let was_land = parent.cells.h[p as usize] >= SEA_LEVEL;
+        let is_land = grid.cells.h[c] >= SEA_LEVEL;
+        if was_land && !is_land {
+            grid.cells.biome[c] = 0;
+            grid.cells.state[c] = -1;
+            grid.cells.province[c] = -1;
+            grid.cells.culture[c] = -1;
+            grid.cells.religion[c] = -1;
+        } else if is_land && !was_land {
+            new_land.push(c as u32);
+        }
+    }
+    biomes::recompute_biome_local(grid, &new_land);
+    // Ownership from the first inherited land neighbour (CSR order).
+    for &c in &new_land {
+        let c = c as usize;
+        let owner = grid
+            .mesh
+            .cells
+            .neighbors_of_cell(c)
+            .iter()
+            .map(|&k| k as usize)
+            .find(|&k| grid.cells.h[k] >= SEA_LEVEL && grid.cells.state[k] >= 0);
+        if let Some(k) = owner {
+            grid.cells.state[c] = grid.cells.state[k];
+            grid.cells.province[c] = grid.cells.province[k];
+            grid.cells.culture[c] = grid.cells.culture[k];
+            grid.cells.religion[c] = grid.cells.religion[k];
+        }
+    }
+}
+
+/// Rasterize the parent's river network onto the child: each parent river
+/// cell is joined to the cell it drains into (the lowest neighbour that
+/// carries more flux on a river, or water — then the segment stops at the
+/// shore midpoint), and child land cells along the segment take the river id
+/// and linearly interpolated flux. `index` is the spatial index over the
+/// child mesh.
+fn trace_rivers(grid: &mut Grid, index: &SpatialIndex, parent: &Grid, bounds: [f64; 4]) {
+    let [x0, y0, x1, y1] = bounds;
+    let pm = &parent.mesh;
+    let pc = &parent.cells;
+    let child_spacing =
+        (grid.mesh.world_w * grid.mesh.world_h / grid.cell_count().max(1) as f64).sqrt();
+    let margin = 2.0 * (pm.world_w * pm.world_h / pm.points.len() as f64).sqrt();
+    for a in 0..pm.points.len() {
+        let rid = pc.r[a];
+        if rid == 0 || pc.h[a] < SEA_LEVEL {
+            continue;
+        }
+        let pa = pm.points[a];
+        if pa[0] < x0 - margin || pa[0] > x1 + margin || pa[1] < y0 - margin || pa[1] > y1 + margin
+        {
+            continue;
+        }
+        let Some(b) = pm
+            .cells
+            .neighbors_of_cell(a)
+            .iter()
+            .map(|&k| k as usize)
+            .filter(|&k| pc.h[k] < SEA_LEVEL || (pc.r[k] != 0 && pc.fl[k] > pc.fl[a]))
+            .min_by_key(|&k| (pc.h[k], k))
+        else {
+            continue;
+        };
+        let to_water = pc.h[b] < SEA_LEVEL;
+        // Unwrapped to `a`'s side of the seam on wrapping topologies.
+        let pb = [pa[0] + pm.delta_x(pa[0], pm.points[b][0]), pm.points[b][1]];
+        let pb = if to_water {
+            [(pa[0] + pb[0]) / 2.0, (pa[1] + pb[1]) / 2.0]
+        } else {
+            pb
+        };
+        let (fa, fb) = (
+            pc.fl[a] as f64,
+            if to_water { pc.fl[a] } else { pc.fl[b] } as f64,
+        );
+        let len = ((pb[0] - pa[0]).powi(2) + (pb[1] - pa[1]).powi(2)).sqrt();
+        let steps = ((len / (0.5 * child_spacing)).ceil() as usize).max(1);
+        for s in 0..=steps {
+            let t = s as f64 / steps as f64;
+            let qx = pa[0] + (pb[0] - pa[0]) * t - x0;
+            let qy = pa[1] + (pb[1] - pa[1]) * t - y0;
+            if qx < 0.0 || qy < 0.0 || qx > x1 - x0 || qy > y1 - y0 {
+                continue;
+            }
+            let c = index.nearest(&grid.mesh, qx, qy).expect("child has cells") as usize;
+            if grid.cells.h[c] < SEA_LEVEL {
+                continue;
+            }
+            let fl = (fa + (fb - fa) * t).round().clamp(0.0, u16::MAX as f64) as u16;
+            if grid.cells.r[c] == 0 || fl > grid.cells.fl[c] {
+                grid.cells.r[c] = rid;
+                grid.cells.fl[c] = fl;
+            }
+        }
+        // Confluence flag travels with the downstream parent cell.
+        if !to_water && pc.conf[b] > 0 {
+            let [bx, by] = pm.points[b];
+            if bx >= x0 && bx <= x1 && by >= y0 && by <= y1 {
+                let c = index
+                    .nearest(&grid.mesh, bx - x0, by - y0)
+                    .expect("child has cells") as usize;
+                grid.cells.conf[c] = pc.conf[b];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::climate;
+    use crate::generate_world_inner;
+
+    fn parent() -> Grid {
+        generate_world_inner(42, 3000, &climate::ClimateOpts::default())
+    }
+
+    #[test]
+    fn child_covers_region_with_requested_density() {
+        let p = parent();
+        let region = Region::Rect {
+            x0: 2000.0,
+            y0: 1500.0,
+            x1: 5000.0,
+            y1: 4000.0,
+        };
+        let child = refine_region(&p, &region, 4000, 7).expect("non-empty region");
+        assert_eq!(child.origin, [2000.0, 1500.0]);
+        assert_eq!(child.grid.mesh.world_w, 3000.0);
+        assert_eq!(child.grid.mesh.world_h, 2500.0);
+        assert_eq!(child.grid.cell_count(), 4000);
+        assert_eq!(child.parent_cell.len(), 4000);
+        for &[x, y] in &child.grid.mesh.points {
+            assert!((0.0..=3000.0).contains(&x) && (0.0..=2500.0).contains(&y));
+        }
+    }
+
+    /// Heights stay close to the parent's (interpolation + bounded noise) and
+    /// the land fraction is preserved to within a few percent.
+    #[test]
+    fn child_inherits_parent_layers() {
+        let p = parent();
+        let region = Region::Rect {
+            x0: 1000.0,
+            y0: 1000.0,
+            x1: 9000.0,
+            y1: 7000.0,
+        };
+        let child = refine_region(&p, &region, 6000, 3).unwrap();
+        let g = &child.grid;
+        let mut agree = 0;
+        for c in 0..g.cell_count() {
+            let pc = child.parent_cell[c] as usize;
+            if (g.cells.h[c] >= SEA_LEVEL) == (p.cells.h[pc] >= SEA_LEVEL) {
+                agree += 1;
+            }
+            if g.cells.h[c] >= SEA_LEVEL && g.cells.state[c] >= 0 {
+                assert_ne!(g.cells.biome[c], 0, "owned land cell {c} has water biome");
+            }
+        }
+        assert!(
+            agree as f64 / g.cell_count() as f64 > 0.9,
+            "only {agree} agree"
+        );
+        // Rivers inside the region are re-traced onto the child.
+        let parent_rivers = (0..p.cell_count()).any(|c| {
+            let [x, y] = p.mesh.points[c];
+            p.cells.r[c] != 0 && (1000.0..=9000.0).contains(&x) && (1000.0..=7000.0).contains(&y)
+        });
+        assert!(parent_rivers, "test world has no rivers in the region");
+        assert!(g.cells.r.iter().any(|&r| r != 0));
+    }
+
+    #[test]
+    fn refinement_is_deterministic() {
+        let p = parent();
+        let region = Region::Cells {
+            cells: (0..p.cell_count() as u32).step_by(7).take(40).collect(),
+        };
+        let a = refine_region(&p, &region, 2000, 11).unwrap();
+        let b = refine_region(&p, &region, 2000, 11).unwrap();
+        assert_eq!(a.grid.cells.h, b.grid.cells.h);
+        assert_eq!(a.grid.cells.r, b.grid.cells.r);
+        assert_eq!(a.grid.cells.state, b.grid.cells.state);
+        let c = refine_region(&p, &region, 2000, 12).unwrap();
+        assert_ne!(a.grid.cells.h, c.grid.cells.h);
+    }
+
+    #[test]
+    fn empty_region_is_rejected() {
+        let p = parent();
+        let degenerate = Region::Rect {
+            x0: 500.0,
+            y0: 500.0,
+            x1: 500.0,
+            y1: 900.0,
+        };
+        assert!(refine_region(&p, &degenerate, 1000, 1).is_none());
+        assert!(refine_region(&p, &Region::Cells { cells: vec![] }, 1000, 1).is_none());
+    }
+}
@@ -31,6 +31,10 @@
 //!    dropped (too short to render); river ids written back to `cells.r`;
 //!    confluence cells flagged in `cells.conf`.
 //!
+//! [`update_drainage`] is the incremental variant for brush strokes: it
+//! re-runs these steps only over the catchments an edit can reach and
+//! splices them into the previous result, keeping river ids elsewhere.
+//!
 //! **Determinism.** No RNG is used. All cell traversal uses sorted-by-id
 //! / sorted-by-height order; the cell sort uses a total-order comparator
 //! (`height.then(id)`) per the determinism contract §4 rule 4. Lake geometry
@@ -112,7 +116,7 @@ pub fn compute_drainage(mesh: &Mesh, h: &[u8], temp: &[i8], prec: &[u8]) -> Drai
     let mut h_eff = alter_heights(mesh, h, temp);
 
     // 2. resolve_depressions (also collects lake cells).
-    let lakes = resolve_depressions(mesh, &mut h_eff);
+    let lakes = resolve_depressions(mesh, &mut h_eff, None);
 
     // 3. detect_close_lakes (BFS, marks closed lakes).
     let lakes = detect_close_lakes(mesh, &h_eff, lakes);
@@ -138,6 +142,7 @@ pub fn compute_drainage(mesh: &Mesh, h: &[u8], temp: &[i8], prec: &[u8]) -> Drai
         &mut river_parents,
         &mut river_next,
         &lakes,
+        None,
     );
 
     // 5. define_rivers (drop short rivers, write ids back, build RiverGeo).
@@ -161,34 +166,208 @@ pub fn compute_drainage(mesh: &Mesh, h: &[u8], temp: &[i8], prec: &[u8]) -> Drai
     }
 }
 
+/// Incremental [`compute_drainage`] after editing the `edited` cells (their
+/// `h`, and `temp` from the local patch). `prev` is the drainage of the grid
+/// before the edit.
+///
+/// Only the catchments the edit can reach are recomputed: those of the
+/// edited cells and their neighbours, grown by any catchment the new surface
+/// spills into or captures cells from, until none changes its route. A
+/// catchment is every land cell draining to the same mouth or pit, so
+/// `fl`/`r`/`conf` outside the region, and every river not in it, are kept
+/// as they are (ids included). Ids of the rivers that were rebuilt are
+/// handed out again in order before new ones are taken, so re-running an
+/// unchanged region reproduces `prev` exactly. Water cells fed by the region
+/// get their inflow recounted.
+///
+/// Precipitation is read for the region only; shifts elsewhere wait for the
+/// next full pass. Lake outlets can route water between catchments, so with
+/// lakes on either side this falls back to [`compute_drainage`].
+///
+/// Returns the spliced result and the recomputed cells (ascending).
+pub fn update_drainage(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    prec: &[u8],
+    prev: &DrainageResult,
+    edited: &[u32],
+) -> (DrainageResult, Vec<u32>) {
+    let n = mesh.points.len();
+    let full = || {
+        (
+            compute_drainage(mesh, h, temp, prec),
+            (0..n as u32).collect(),
+        )
+    };
+    if prev.h_eff.len() != n || !prev.lakes.is_empty() {
+        return full();
+    }
+    let sea = SEA_LEVEL as f64;
+    let n_mod = ((n as f64) / 10000.0).powf(0.25).max(1.0);
+
+    let mut region = vec![false; n];
+    let mut cells: Vec<usize> = Vec::new();
+    let mut outlets = std::collections::BTreeSet::new();
+    for &e in edited.iter().filter(|&&e| (e as usize) < n) {
+        let e = e as usize;
+        let neighbours = mesh.cells.neighbors_of_cell(e).iter().map(|&d| d as usize);
+        for c in std::iter::once(e).chain(neighbours) {
+            if prev.h_eff[c] >= sea {
+                add_catchment(mesh, &prev.h_eff, c, &mut outlets, &mut region, &mut cells);
+            } else if !region[c] && c == e {
+                region[c] = true;
+                cells.push(c);
+            }
+        }
+    }
+
+    let mut h_eff = prev.h_eff.clone();
+    loop {
+        for &c in &cells {
+            h_eff[c] = altered_height(mesh, h, temp, c);
+        }
+        if !resolve_depressions(mesh, &mut h_eff, Some(&region)).is_empty() {
+            return full();
+        }
+        // Outside cells whose route the region changed, and outside
+        // catchments it now drains into.
+        let mut spill: Vec<usize> = Vec::new();
+        for &c in &cells {
+            for &d in mesh.cells.neighbors_of_cell(c) {
+                let d = d as usize;
+                if !region[d]
+                    && h_eff[d] >= sea
+                    && receiver(mesh, &h_eff, d) != receiver(mesh, &prev.h_eff, d)
+                {
+                    spill.push(d);
+                }
+            }
+            if h_eff[c] >= sea {
+                if let Some(to) = receiver(mesh, &h_eff, c) {
+                    if !region[to] && h_eff[to] >= sea {
+                        spill.push(to);
+                    }
+                }
+            }
+        }
+        if spill.is_empty() {
+            break;
+        }
+        for c in spill {
+            add_catchment(mesh, &prev.h_eff, c, &mut outlets, &mut region, &mut cells);
+        }
+    }
+
+    let mut fl = vec![0u16; n];
+    let mut r = vec![0u16; n];
+    let mut conf = vec![0u16; n];
+    let mut rivers_data: std::collections::BTreeMap<u32, Vec<i32>> =
+        std::collections::BTreeMap::new();
+    let mut river_parents: std::collections::BTreeMap<u32, u32> = std::collections::BTreeMap::new();
+    let mut river_next: u32 = 1;
+    drain_water(
+        mesh,
+        &h_eff,
+        prec,
+        &n_mod,
+        &mut fl,
+        &mut r,
+        &mut conf,
+        &mut rivers_data,
+        &mut river_parents,
+        &mut river_next,
+        &[],
+        Some(&region),
+    );
+    let rebuilt = define_rivers(
+        mesh,
+        &h_eff,
+        &fl,
+        &mut r,
+        &mut conf,
+        rivers_data,
+        river_parents,
+    );
+
+    // Rivers of the region give their ids back; the rebuilt ones take them
+    // in order, then fresh ones.
+    let (mut rivers, dropped): (Vec<RiverGeo>, Vec<RiverGeo>) = prev
+        .rivers
+        .iter()
+        .cloned()
+        .partition(|river| !river.cells.iter().any(|&c| c >= 0 && region[c as usize]));
+    let mut free = dropped.iter().map(|river| river.id).collect::<Vec<_>>();
+    free.sort_unstable();
+    let mut free = free.into_iter();
+    let mut next_id = prev.rivers.iter().map(|river| river.id).max().unwrap_or(0) + 1;
+    let mut ids = std::collections::BTreeMap::new();
+    for mut river in rebuilt {
+        let id = free.next().unwrap_or_else(|| {
+            next_id += 1;
+            next_id - 1
+        });
+        ids.insert(river.id, id);
+        river.id = id;
+        rivers.push(river);
+    }
+    rivers.sort_by_key(|river| river.id);
+
+    let mut out = DrainageResult {
+        h_eff,
+        fl: prev.fl.clone(),
+        r: prev.r.clone(),
+        conf: prev.conf.clone(),
+        lakes: Vec::new(),
+        rivers,
+    };
+    let mut fed = std::collections::BTreeSet::new();
+    for &c in &cells {
+        out.fl[c] = fl[c];
+        out.r[c] = ids.get(&(r[c] as u32)).map_or(0, |&id| id as u16);
+        out.conf[c] = conf[c];
+        for surface in [&out.h_eff, &prev.h_eff] {
+            let to = if surface[c] >= sea {
+                receiver(mesh, surface, c)
+            } else {
+                Some(c)
+            };
+            fed.extend(to.filter(|&w| out.h_eff[w] < sea));
+        }
+    }
+    for w in fed {
+        out.fl[w] = water_inflow(mesh, &out.h_eff, &out.fl, w);
+    }
+    cells.sort_unstable();
+    (out, cells.into_iter().map(|c| c as u32).collect())
+}
+
 /// FMG `alterHeights()`: add `t[i]/100 + mean(t[neighbors])/10000` to land
 /// cells so a warm ridge drains ahead of a cold lowland (temperature is a
 /// proxy for air density / uplift). Water cells keep their original height.
 fn alter_heights(mesh: &Mesh, h: &[u8], temp: &[i8]) -> Vec<f64> {
     let n = mesh.points.len();
-    let i = &mesh.cells.i;
-    let c = &mesh.cells.c;
-    let mut out = vec![0f64; n];
-    for cell in 0..n {
-        let h_i = h[cell] as f64;
-        if h_i < SEA_LEVEL as f64 {
-            out[cell] = h_i;
-            continue;
-        }
-        let t_i = temp[cell] as f64;
-        // mean(temp of neighbors)
-        let lo = i[cell] as usize;
-        let hi = i[cell + 1] as usize;
-        let mut sum = 0.0f64;
-        let mut count = 0usize;
-        for &nb in &c[lo..hi] {
-            sum += temp[nb as usize] as f64;
-            count += 1;
-        }
-        let mean_t = if count > 0 { sum / count as f64 } else { 0.0 };
-        out[cell] = h_i + t_i / 100.0 + mean_t / 10000.0;
+    (0..n)
+        .map(|cell| altered_height(mesh, h, temp, cell))
+        .collect()
+}
+
+/// `alter_heights` for one cell.
+fn altered_height(mesh: &Mesh, h: &[u8], temp: &[i8], cell: usize) -> f64 {
+    let h_i = h[cell] as f64;
+    if h_i < SEA_LEVEL as f64 {
+        return h_i;
     }
-    out
+    let t_i = temp[cell] as f64;
+    // mean(temp of neighbors)
+    let mut sum = 0.0f64;
+    let mut count = 0usize;
+    for &nb in mesh.cells.neighbors_of_cell(cell) {
+        sum += temp[nb as usize] as f64;
+        count += 1;
+    }
+    let mean_t = if count > 0 { sum / count as f64 } else { 0.0 };
+    h_i + t_i / 100.0 + mean_t / 10000.0
 }
 
 /// Priority-flood depression filling (FMG `resolveDepressions`). Walk land
@@ -196,8 +375,9 @@ fn alter_heights(mesh: &Mesh, h: &[u8], temp: &[i8]) -> Vec<f64> {
 /// cell, raise the cell to `min_neighbor + 0.1`. Iterate until no change.
 /// Lakes are detected implicitly: a cell that was water (h < SEA_LEVEL) and
 /// got *raised* by the fill is a lake cell. We collect those as [`LakeGeo`]
-/// entries with their shoreline.
-fn resolve_depressions(mesh: &Mesh, h_eff: &mut [f64]) -> Vec<LakeGeo> {
+/// entries with their shoreline. With a `region`, only cells inside it are
+/// filled; the rest keep their heights and act as fixed walls.
+fn resolve_depressions(mesh: &Mesh, h_eff: &mut [f64], region: Option<&[bool]>) -> Vec<LakeGeo> {
     let n = mesh.points.len();
     let i = &mesh.cells.i;
     let c = &mesh.cells.c;
@@ -206,7 +386,10 @@ fn resolve_depressions(mesh: &Mesh, h_eff: &mut [f64]) -> Vec<LakeGeo> {
     // Land cells (excluding near-border), sorted lowest-first.
     // Determinism: total-order comparator (height, then id).
     let mut land: Vec<u32> = (0..n as u32)
-        .filter(|&idx| h_eff[idx as usize] >= SEA_LEVEL as f64 && b[idx as usize] == 0)
+        .filter(|&idx| {
+            let cell = idx as usize;
+            h_eff[cell] >= SEA_LEVEL as f64 && b[cell] == 0 && region.is_none_or(|r| r[cell])
+        })
         .collect();
     land.sort_by(|&a, &b| {
         let ha = h_eff[a as usize];
@@ -411,7 +594,8 @@ fn detect_close_lakes(mesh: &Mesh, h_eff: &[f64], mut lakes: Vec<LakeGeo>) -> Ve
 /// [`MIN_FLUX_TO_FORM_RIVER`]. The river's path is the sequence of cells we
 /// push to `rivers_data` as we proclaim river ids and route through lake
 /// outlets. Handles lake outlet routing, near-border pour-off, and
-/// confluences (parent link tracking).
+/// confluences (parent link tracking). With a `region`, only land cells
+/// inside it are drained.
 #[allow(clippy::too_many_arguments)]
 fn drain_water(
     mesh: &Mesh,
@@ -425,6 +609,7 @@ fn drain_water(
     river_parents: &mut std::collections::BTreeMap<u32, u32>,
     river_next: &mut u32,
     lakes: &[LakeGeo],
+    region: Option<&[bool]>,
 ) {
     let n = mesh.points.len();
     let i = &mesh.cells.i;
@@ -458,7 +643,7 @@ fn drain_water(
     // Land cells, sorted highest-first (FMG iterates this order so flux collects
     // from peaks down).
     let mut land: Vec<u32> = (0..n as u32)
-        .filter(|&idx| h_eff[idx as usize] >= sea)
+        .filter(|&idx| h_eff[idx as usize] >= sea && region.is_none_or(|r| r[idx as usize]))
         .collect();
     land.sort_by(|&a, &b| {
         let ha = h_eff[a as usize];
@@ -708,6 +893,81 @@ fn define_rivers(
     rivers
 }
 
+/// Where `drain_water` sends the flux of land cell `cell`: its lowest
+/// neighbour, if lower than the cell itself (`None` for a pit).
+fn receiver(mesh: &Mesh, h_eff: &[f64], cell: usize) -> Option<usize> {
+    let mut to = None;
+    let mut min_h = h_eff[cell];
+    for &nb in mesh.cells.neighbors_of_cell(cell) {
+        if h_eff[nb as usize] < min_h {
+            min_h = h_eff[nb as usize];
+            to = Some(nb as usize);
+        }
+    }
+    to
+}
+
+/// Add the catchment of land cell `cell` under `h_eff` to `region`: walk
+/// down to the cell where its water leaves the land (a mouth or a pit), then
+/// collect every land cell draining there. `outlets` records the catchments
+/// already added.
+fn add_catchment(
+    mesh: &Mesh,
+    h_eff: &[f64],
+    cell: usize,
+    outlets: &mut std::collections::BTreeSet<usize>,
+    region: &mut [bool],
+    cells: &mut Vec<usize>,
+) {
+    let sea = SEA_LEVEL as f64;
+    let mut outlet = cell;
+    while let Some(to) = receiver(mesh, h_eff, outlet).filter(|&to| h_eff[to] >= sea) {
+        outlet = to;
+    }
+    if !outlets.insert(outlet) {
+        return;
+    }
+    let mut stack = vec![outlet];
+    region[outlet] = true;
+    cells.push(outlet);
+    while let Some(to) = stack.pop() {
+        for &d in mesh.cells.neighbors_of_cell(to) {
+            let d = d as usize;
+            if !region[d] && h_eff[d] >= sea && receiver(mesh, h_eff, d) == Some(to) {
+                region[d] = true;
+                cells.push(d);
+                stack.push(d);
+            }
+        }
+    }
+}
+
+/// The flux `drain_water` leaves in water cell `w`: rivers pour in from
+/// their mouths highest first, and one no stronger than what is already
+/// there joins it without adding to it (see `route_downward`).
+fn water_inflow(mesh: &Mesh, h_eff: &[f64], fl: &[u16], w: usize) -> u16 {
+    let sea = SEA_LEVEL as f64;
+    let mut mouths: Vec<usize> = mesh
+        .cells
+        .neighbors_of_cell(w)
+        .iter()
+        .map(|&m| m as usize)
+        .filter(|&m| {
+            h_eff[m] >= sea
+                && fl[m] as u32 >= MIN_FLUX_TO_FORM_RIVER
+                && receiver(mesh, h_eff, m) == Some(w)
+        })
+        .collect();
+    mouths.sort_by(|&a, &b| h_eff[b].total_cmp(&h_eff[a]).then(a.cmp(&b)));
+    let mut total = 0u16;
+    for (k, &m) in mouths.iter().enumerate() {
+        if k == 0 || fl[m] > total {
+            total = total.saturating_add(fl[m]);
+        }
+    }
+    total
+}
+
 // ===========================================================================//
 // Tests — verification gate for the rivers/lakes drainage module.
 // ===========================================================================//
@@ -758,6 +1018,7 @@ mod tests {
             vertices: Vertices { p: vec![] },
             world_w: 10000.0,
             world_h: 8000.0,
+            ..Default::default()
         }
     }
 
@@ -807,6 +1068,7 @@ mod tests {
             vertices: Vertices { p: vec![] },
             world_w: 10000.0,
             world_h: 8000.0,
+            ..Default::default()
         }
     }
 
@@ -1073,4 +1335,100 @@ mod tests {
             assert_eq!(result.r[i], 0, "water cell {i} should not have a river id");
         }
     }
+
+    // ---- update_drainage ----------------------------------------------------
+
+    /// A rippled island on a real mesh: many small catchments.
+    fn island() -> (Mesh, Vec<u8>, Vec<i8>, Vec<u8>) {
+        let mesh = crate::mesh::build(3000, 8);
+        let h = mesh
+            .points
+            .iter()
+            .map(|&[x, y]| {
+                let d = ((x - 5000.0).powi(2) + (y - 4000.0).powi(2)).sqrt();
+                let ripple = 6.0 * (x / 700.0).sin() * (y / 900.0).cos();
+                (75.0 - d / 50.0 + ripple).clamp(5.0, 100.0) as u8
+            })
+            .collect::<Vec<_>>();
+        let temp = vec![10i8; h.len()];
+        let prec = vec![8u8; h.len()];
+        (mesh, h, temp, prec)
+    }
+
+    /// Re-running an unedited region gives back exactly the previous pass.
+    #[test]
+    fn update_drainage_unchanged_region_reproduces_prev() {
+        let (mesh, h, temp, prec) = island();
+        let prev = compute_drainage(&mesh, &h, &temp, &prec);
+        let edited = [
+            crate::heightmap::pick_cell(&mesh, 5000.0, 4000.0).unwrap(),
+            10,
+            900,
+        ];
+        let (out, region) = update_drainage(&mesh, &h, &temp, &prec, &prev, &edited);
+        assert!(!region.is_empty() && region.len() < h.len());
+        assert_eq!(out.h_eff, prev.h_eff);
+        assert_eq!(
+            (&out.fl, &out.r, &out.conf),
+            (&prev.fl, &prev.r, &prev.conf)
+        );
+        let ids = |d: &DrainageResult| {
+            d.rivers
+                .iter()
+                .map(|r| (r.id, r.cells.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&out), ids(&prev));
+    }
+
+    /// An edit only touches its catchments: everything else, river ids
+    /// included, is kept, and the region's land flux matches a full pass.
+    #[test]
+    fn update_drainage_splices_affected_catchments() {
+        let (mesh, mut h, temp, prec) = island();
+        let prev = compute_drainage(&mesh, &h, &temp, &prec);
+        let edited =
+            crate::spatial::SpatialIndex::build(&mesh).within_radius(&mesh, 6200.0, 4300.0, 400.0);
+        for &c in &edited {
+            h[c as usize] = h[c as usize].saturating_add(25).min(100);
+        }
+        let (out, region) = update_drainage(&mesh, &h, &temp, &prec, &prev, &edited);
+        let full = compute_drainage(&mesh, &h, &temp, &prec);
+        assert!(
+            region.len() < h.len() / 2,
+            "{} of {}",
+            region.len(),
+            h.len()
+        );
+
+        let sea = SEA_LEVEL as f64;
+        let mut in_region = vec![false; h.len()];
+        for &c in &region {
+            in_region[c as usize] = true;
+        }
+        for (c, &inside) in in_region.iter().enumerate() {
+            if out.h_eff[c] < sea {
+                continue;
+            }
+            if inside {
+                assert_eq!(out.fl[c], full.fl[c], "cell {c}");
+            } else {
+                assert_eq!((out.fl[c], out.r[c]), (prev.fl[c], prev.r[c]), "cell {c}");
+            }
+        }
+        for river in &prev.rivers {
+            if !river.cells.iter().any(|&c| c >= 0 && in_region[c as usize]) {
+                assert!(out
+                    .rivers
+                    .iter()
+                    .any(|r| r.id == river.id && r.cells == river.cells));
+            }
+        }
+        let land_rivers = |d: &DrainageResult| d.r.iter().filter(|&&r| r != 0).count();
+        assert_eq!(land_rivers(&out), land_rivers(&full));
+        for river in &out.rivers {
+            let source = river.source as usize;
+            assert_eq!(out.r[source] as u32, river.id, "river {}", river.id);
+        }
+    }
 }
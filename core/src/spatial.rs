@@ -0,0 +1,362 @@
+//! Spatial index over a mesh's cell centres.
+//!
+//! A uniform bucket grid (≈ one cell per bucket) with the cell ids of each
+//! bucket stored CSR-style (`start` offsets into `ids`, ids ascending within
+//! a bucket). The caller builds one with `SpatialIndex::build(&mesh)` and
+//! keeps it for as long as that mesh's points stay put; it is never
+//! serialized — the JS side only ever sees query results.
+//!
+//! Unlike the FMG `cells.spacing` grid (one representative cell per slot,
+//! refined by an adjacency walk) every query here is exact:
+//!
+//! - `nearest` — ring search outward from the query's bucket until no closer
+//!   bucket can exist. Ties go to the lower cell id.
+//! - `within_radius` / `in_rect` / `in_polygon` — scan the buckets the shape
+//!   covers and test each cell centre. Results are ascending cell ids, so they
+//!   are deterministic and independent of the mesh's adjacency (a radius query
+//!   at a concave coastline returns cells a BFS would never reach).
+//!
+//! On wrapping topologies (`Topology::wraps_x`) bucket columns wrap and
+//! distances use `Mesh::distance_sq`, so shapes straddling the seam select
+//! cells on both sides.
+
+use crate::mesh::Mesh;
+
+/// Bucket grid over cell centres. See the module docs.
+#[derive(Clone, Debug, Default)]
+pub struct SpatialIndex {
+    cols: usize,
+    rows: usize,
+    /// Bucket width / height in world units.
+    bw: f64,
+    bh: f64,
+    /// CSR offsets into `ids`, length `cols * rows + 1`.
+    start: Vec<u32>,
+    ids: Vec<u32>,
+    wrap_x: bool,
+}
+
+impl SpatialIndex {
+    /// Bucket every cell centre of `mesh`. O(N).
+    pub fn build(mesh: &Mesh) -> Self {
+        let n = mesh.points.len();
+        let w = mesh.world_w.max(f64::MIN_POSITIVE);
+        let h = mesh.world_h.max(f64::MIN_POSITIVE);
+        let cols = ((n as f64 * w / h).sqrt().ceil() as usize).max(1);
+        let rows = n.div_ceil(cols).max(1);
+        let mut index = SpatialIndex {
+            cols,
+            rows,
+            bw: w / cols as f64,
+            bh: h / rows as f64,
+            start: vec![0; cols * rows + 1],
+            ids: vec![0; n],
+            wrap_x: mesh.topology.wraps_x(),
+        };
+        // Counting sort by bucket; a stable fill keeps ids ascending per bucket.
+        let buckets: Vec<usize> = mesh
+            .points
+            .iter()
+            .map(|&[x, y]| index.bucket(index.col(x), index.row(y)))
+            .collect();
+        for &b in &buckets {
+            index.start[b + 1] += 1;
+        }
+        for b in 0..cols * rows {
+            index.start[b + 1] += index.start[b];
+        }
+        let mut fill = index.start.clone();
+        for (cell, &b) in buckets.iter().enumerate() {
+            index.ids[fill[b] as usize] = cell as u32;
+            fill[b] += 1;
+        }
+        index
+    }
+
+    /// Exact nearest cell centre to `(x, y)` (any point, in or out of the
+    /// world). `None` only for an empty mesh.
+    pub fn nearest(&self, mesh: &Mesh, x: f64, y: f64) -> Option<u32> {
+        if self.ids.is_empty() {
+            return None;
+        }
+        let (qc, qr) = (self.col(x) as isize, self.row(y) as isize);
+        let step = self.bw.min(self.bh);
+        let mut best = (f64::INFINITY, u32::MAX);
+        for k in 0..=self.cols.max(self.rows) as isize {
+            // Every bucket in ring `k` is at least `(k - 1) * step` away.
+            if k > 0 && best.0 <= ((k - 1) as f64 * step).powi(2) {
+                break;
+            }
+            for r in qr - k..=qr + k {
+                if r < 0 || r >= self.rows as isize {
+                    continue;
+                }
+                let edge_row = r == qr - k || r == qr + k;
+                let cols: &[isize] = if edge_row { &[] } else { &[qc - k, qc + k] };
+                let span = qc - k..=qc + k;
+                let mut visit = |c: isize| {
+                    let Some(c) = self.wrap_col(c) else {
+                        return;
+                    };
+                    for &id in self.bucket_ids(c, r as usize) {
+                        let d2 = mesh.distance_sq(mesh.points[id as usize], [x, y]);
+                        if (d2, id) < best {
+                            best = (d2, id);
+                        }
+                    }
+                };
+                if edge_row {
+                    span.for_each(&mut visit);
+                } else {
+                    cols.iter().copied().for_each(&mut visit);
+                }
+            }
+        }
+        Some(best.1)
+    }
+
+    /// Cells whose centre lies within `radius` of `(x, y)`, ascending.
+    pub fn within_radius(&self, mesh: &Mesh, x: f64, y: f64, radius: f64) -> Vec<u32> {
+        if radius.is_nan() || radius < 0.0 {
+            return Vec::new();
+        }
+        let r2 = radius * radius;
+        let mut out = Vec::new();
+        self.scan(x - radius, y - radius, x + radius, y + radius, |id| {
+            if mesh.distance_sq(mesh.points[id as usize], [x, y]) <= r2 {
+                out.push(id);
+            }
+        });
+        out.sort_unstable();
+        out
+    }
+
+    /// Cells whose centre lies in the rectangle spanned by the two corners
+    /// (inclusive), ascending. On wrapping topologies the rectangle may extend
+    /// past either side of the seam.
+    pub fn in_rect(&self, mesh: &Mesh, x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<u32> {
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (y0, y1) = (y0.min(y1), y0.max(y1));
+        let mut out = Vec::new();
+        self.scan(x0, y0, x1, y1, |id| {
+            let [px, py] = mesh.points[id as usize];
+            let px = self.unwrap_x(mesh, px, x0);
+            if px >= x0 && px <= x1 && py >= y0 && py <= y1 {
+                out.push(id);
+            }
+        });
+        out.sort_unstable();
+        out
+    }
+
+    /// Cells whose centre lies inside `polygon` (even-odd rule, any winding,
+    /// implicitly closed), ascending. Fewer than 3 vertices select nothing.
+    pub fn in_polygon(&self, mesh: &Mesh, polygon: &[[f64; 2]]) -> Vec<u32> {
+        if polygon.len() < 3 {
+            return Vec::new();
+        }
+        let mut b = [
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ];
+        for &[x, y] in polygon {
+            b = [b[0].min(x), b[1].min(y), b[2].max(x), b[3].max(y)];
+        }
+        let mut out = Vec::new();
+        self.scan(b[0], b[1], b[2], b[3], |id| {
+            let [px, py] = mesh.points[id as usize];
+            if point_in_polygon([self.unwrap_x(mesh, px, b[0]), py], polygon) {
+                out.push(id);
+            }
+        });
+        out.sort_unstable();
+        out
+    }
+
+    /// Visit every cell in the buckets overlapping `[x0, x1] × [y0, y1]`,
+    /// each bucket once.
+    fn scan(&self, x0: f64, y0: f64, x1: f64, y1: f64, mut f: impl FnMut(u32)) {
+        let (r0, r1) = (self.row(y0), self.row(y1));
+        let cols: Vec<usize> = if self.wrap_x {
+            let world_w = self.bw * self.cols as f64;
+            let span = x1 - x0;
+            if span.is_nan() || span >= world_w {
+                (0..self.cols).collect()
+            } else {
+                // Move the span into the first world width before converting,
+                // so huge coordinates can't saturate the column range.
+                let x0 = x0.rem_euclid(world_w);
+                let c0 = (x0 / self.bw).floor() as isize;
+                let c1 = ((x0 + span) / self.bw).floor() as isize;
+                (c0..=c1)
+                    .map(|c| c.rem_euclid(self.cols as isize) as usize)
+                    .collect()
+            }
+        } else {
+            (self.col(x0)..=self.col(x1)).collect()
+        };
+        for r in r0..=r1 {
+            for &c in &cols {
+                self.bucket_ids(c, r).iter().copied().for_each(&mut f);
+            }
+        }
+    }
+
+    /// `px` shifted by a world width so it lies at or right of `x0` when the
+    /// map wraps (shapes are tested in their own unwrapped frame).
+    fn unwrap_x(&self, mesh: &Mesh, px: f64, x0: f64) -> f64 {
+        if self.wrap_x {
+            x0 + (px - x0).rem_euclid(mesh.world_w)
+        } else {
+            px
+        }
+    }
+
+    fn col(&self, x: f64) -> usize {
+        let c = (x / self.bw).floor();
+        if self.wrap_x && c.is_finite() {
+            (c as isize).rem_euclid(self.cols as isize) as usize
+        } else {
+            (c.max(0.0) as usize).min(self.cols - 1)
+        }
+    }
+
+    fn row(&self, y: f64) -> usize {
+        ((y / self.bh).floor().max(0.0) as usize).min(self.rows - 1)
+    }
+
+    /// Ring-search column: wrapped into range, or `None` off a flat edge.
+    fn wrap_col(&self, c: isize) -> Option<usize> {
+        if self.wrap_x {
+            Some(c.rem_euclid(self.cols as isize) as usize)
+        } else if c >= 0 && c < self.cols as isize {
+            Some(c as usize)
+        } else {
+            None
+        }
+    }
+
+    fn bucket(&self, col: usize, row: usize) -> usize {
+        row * self.cols + col
+    }
+
+    fn bucket_ids(&self, col: usize, row: usize) -> &[u32] {
+        let b = self.bucket(col, row);
+        &self.ids[self.start[b] as usize..self.start[b + 1] as usize]
+    }
+}
+
+/// Even-odd point-in-polygon test (ray cast towards +x).
+fn point_in_polygon([x, y]: [f64; 2], polygon: &[[f64; 2]]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let [xi, yi] = polygon[i];
+        let [xj, yj] = polygon[j];
+        if (yi > y) != (yj > y) && x < xj + (y - yj) * (xi - xj) / (yi - yj) {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::{self, Topology};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn brute_nearest(mesh: &Mesh, x: f64, y: f64) -> u32 {
+        (0..mesh.points.len() as u32)
+            .min_by(|&a, &b| {
+                let da = mesh.distance_sq(mesh.points[a as usize], [x, y]);
+                let db = mesh.distance_sq(mesh.points[b as usize], [x, y]);
+                da.total_cmp(&db).then(a.cmp(&b))
+            })
+            .unwrap()
+    }
+
+    /// Nearest matches a brute-force scan everywhere, including outside the
+    /// world and across the seam of a wrapping mesh.
+    #[test]
+    fn nearest_matches_brute_force() {
+        for mesh in [
+            mesh::build(3000, 42),
+            mesh::build_with_topology(2000, 5, Topology::Cylinder),
+        ] {
+            let index = SpatialIndex::build(&mesh);
+            let mut rng = StdRng::seed_from_u64(7);
+            for _ in 0..500 {
+                let x = rng.gen_range(-500.0..mesh.world_w + 500.0);
+                let y = rng.gen_range(-500.0..mesh.world_h + 500.0);
+                assert_eq!(index.nearest(&mesh, x, y), Some(brute_nearest(&mesh, x, y)));
+            }
+        }
+    }
+
+    #[test]
+    fn radius_rect_and_polygon_match_brute_force() {
+        let mesh = mesh::build(3000, 42);
+        let index = SpatialIndex::build(&mesh);
+        let all = 0..mesh.points.len() as u32;
+        let (cx, cy, r) = (4200.0, 3100.0, 900.0);
+        let expected: Vec<u32> = all
+            .clone()
+            .filter(|&c| mesh.distance_sq(mesh.points[c as usize], [cx, cy]) <= r * r)
+            .collect();
+        assert_eq!(index.within_radius(&mesh, cx, cy, r), expected);
+
+        let expected: Vec<u32> = all
+            .clone()
+            .filter(|&c| {
+                let [x, y] = mesh.points[c as usize];
+                (1000.0..=2500.0).contains(&x) && (500.0..=4000.0).contains(&y)
+            })
+            .collect();
+        assert_eq!(
+            index.in_rect(&mesh, 2500.0, 4000.0, 1000.0, 500.0),
+            expected
+        );
+
+        // Triangle: x ≥ 2000, y ≥ 2000, x + y ≤ 6000.
+        let tri = [[2000.0, 2000.0], [4000.0, 2000.0], [2000.0, 4000.0]];
+        let expected: Vec<u32> = all
+            .filter(|&c| {
+                let [x, y] = mesh.points[c as usize];
+                x > 2000.0 && y > 2000.0 && x + y < 6000.0
+            })
+            .collect();
+        assert_eq!(index.in_polygon(&mesh, &tri), expected);
+    }
+
+    /// A radius query centred on the seam selects cells on both edges.
+    #[test]
+    fn radius_wraps_on_cylinder() {
+        let mesh = mesh::build_with_topology(2000, 5, Topology::Cylinder);
+        let index = SpatialIndex::build(&mesh);
+        let cells = index.within_radius(&mesh, 0.0, mesh.world_h / 2.0, 600.0);
+        let xs: Vec<f64> = cells.iter().map(|&c| mesh.points[c as usize][0]).collect();
+        assert!(xs.iter().any(|&x| x < 600.0));
+        assert!(xs.iter().any(|&x| x > mesh.world_w - 600.0));
+        let rect = index.in_rect(&mesh, -300.0, 0.0, 300.0, mesh.world_h);
+        assert!(rect
+            .iter()
+            .any(|&c| mesh.points[c as usize][0] > mesh.world_w - 300.0));
+    }
+
+    /// Infinite or huge shapes on a wrapping mesh select every cell in range
+    /// instead of overflowing the column span.
+    #[test]
+    fn unbounded_shapes_on_cylinder() {
+        let mesh = mesh::build_with_topology(500, 5, Topology::Cylinder);
+        let index = SpatialIndex::build(&mesh);
+        let all: Vec<u32> = (0..mesh.points.len() as u32).collect();
+        assert_eq!(index.within_radius(&mesh, 10.0, 10.0, f64::INFINITY), all);
+        assert_eq!(index.within_radius(&mesh, 10.0, 10.0, 1e300), all);
+        let far = index.within_radius(&mesh, 1e300, 10.0, 200.0);
+        assert!(far.len() < all.len());
+    }
+}
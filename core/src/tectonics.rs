@@ -0,0 +1,491 @@
+//! Plate-tectonic heightmap generator — an alternative to the template
+//! floods in `heightmap`.
+//!
+//! Same output contract as `heightmap::generate`: a `cells.h` of length N,
+//! `0..=100`, `< SEA_LEVEL` is water. Deterministic per `(mesh, seed, opts)`:
+//! the single `StdRng::seed_from_u64(seed)` is threaded through in a fixed
+//! draw order and all queues break ties by cell id.
+//!
+//! ## Algorithm
+//!
+//! 1. **Plates.** `plates` seed cells grow over `cells.c` as a multi-source
+//!    Dijkstra whose step cost is the edge length times a per-cell roughness,
+//!    so plate borders wander instead of being straight Voronoi bisectors.
+//!    Each plate gets a motion vector and a buoyancy; plates are marked
+//!    continental in a shuffled order until `continental_fraction` of the
+//!    cells are continental crust, the rest oceanic.
+//! 2. **Boundaries.** For every cell touching another plate, the relative
+//!    motion projected on the direction to the neighbour classifies the
+//!    boundary as convergent, divergent or transform, with a strength in
+//!    `0..=1`. Of a convergent pair the more buoyant side overrides:
+//!    continental over oceanic, otherwise the higher buoyancy.
+//! 3. **Relief.** Each boundary profile spreads inward over its own plate
+//!    (Dijkstra by distance), and a cell takes the profile of its nearest
+//!    boundary, shaped by distance in units of `belt_width`:
+//!    - continent–continent collision: a broad mountain belt on both sides;
+//!    - ocean under continent: a coastal trench, cordillera a little inland;
+//!    - ocean under ocean: a trench, and a broken volcanic island arc on the
+//!      overriding plate;
+//!    - divergent: a rift valley in continental crust, a low mid-ocean ridge
+//!      in oceanic crust;
+//!    - transform: nothing beyond the background.
+//! 4. **Crust.** Continental crust sits a few units above sea level, oceanic
+//!    crust well below it, both perturbed by a smoothed random field so
+//!    coasts and basins don't trace the plate borders exactly. One smoothing
+//!    pass finishes the map.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::heightmap::SEA_LEVEL;
+use crate::mesh::Mesh;
+
+/// Tuning knobs. All fields are optional on the wire via `#[serde(default)]`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct TectonicOpts {
+    /// Number of plates (clamped to `1..=cells`).
+    pub plates: u32,
+    /// Share of the cells carried by continental plates, `0..=1`.
+    pub continental_fraction: f64,
+    /// Half-width of mountain belts, trenches and rifts as a fraction of
+    /// `sqrt(world_w * world_h)`.
+    pub belt_width: f64,
+    /// Multiplier on boundary relief (mountains, trenches, arcs, rifts).
+    pub relief: f64,
+}
+
+impl Default for TectonicOpts {
+    fn default() -> Self {
+        TectonicOpts {
+            plates: 14,
+            continental_fraction: 0.4,
+            belt_width: 0.035,
+            relief: 1.0,
+        }
+    }
+}
+
+/// One generated plate.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Plate {
+    /// The cell the plate grew from.
+    pub seed_cell: u32,
+    /// Motion vector in world units per unit time (speed `0.3..=1`).
+    pub velocity: [f64; 2],
+    /// Continental (`true`) or oceanic crust.
+    pub continental: bool,
+    /// Number of cells on the plate.
+    pub area: u32,
+}
+
+/// A tectonic heightmap with the plate layout that shaped it.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Tectonics {
+    /// `cells.h`, `0..=100`.
+    pub h: Vec<u8>,
+    /// Per-cell index into `plates`.
+    pub plate: Vec<u32>,
+    pub plates: Vec<Plate>,
+}
+
+/// Height of continental crust away from any boundary.
+const CONTINENT_BASE: f64 = SEA_LEVEL as f64 + 6.0;
+/// Height of oceanic crust away from any boundary.
+const OCEAN_BASE: f64 = 9.0;
+/// Amplitude of the smoothed background field.
+const BACKGROUND_AMPLITUDE: f64 = 9.0;
+/// Relative speed along the boundary normal below which a boundary counts
+/// as transform.
+const TRANSFORM_SPEED: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Boundary {
+    /// Plates moving together; `overriding` if this side rides over the other.
+    Convergent {
+        overriding: bool,
+    },
+    Divergent,
+    Transform,
+}
+
+/// What a boundary cell contributes, spread to the cells behind it.
+#[derive(Clone, Copy, Debug)]
+struct Profile {
+    kind: Boundary,
+    /// `0..=1`.
+    strength: f64,
+    continental: bool,
+    other_continental: bool,
+}
+
+/// A Dijkstra frontier entry, ordered by cost then cell (min-heap via
+/// `Reverse`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Frontier {
+    cost: f64,
+    cell: usize,
+    source: usize,
+}
+
+impl Eq for Frontier {}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.cell.cmp(&other.cell))
+            .then(self.source.cmp(&other.source))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Multi-source Dijkstra over `cells.c`. `sources` are `(cell, source id)`;
+/// `step(from, to)` is the cost of an edge, `None` where it may not be
+/// crossed. Returns each cell's nearest source (`usize::MAX` if unreached)
+/// and its cost.
+fn spread(
+    mesh: &Mesh,
+    sources: &[(usize, usize)],
+    step: impl Fn(usize, usize) -> Option<f64>,
+) -> (Vec<usize>, Vec<f64>) {
+    let n = mesh.points.len();
+    let mut owner = vec![usize::MAX; n];
+    let mut best = vec![f64::INFINITY; n];
+    let mut heap = BinaryHeap::new();
+    for &(cell, source) in sources {
+        best[cell] = 0.0;
+        heap.push(Reverse(Frontier {
+            cost: 0.0,
+            cell,
+            source,
+        }));
+    }
+    while let Some(Reverse(Frontier { cost, cell, source })) = heap.pop() {
+        if owner[cell] != usize::MAX {
+            continue;
+        }
+        owner[cell] = source;
+        for &nb in mesh.cells.neighbors_of_cell(cell) {
+            let nb = nb as usize;
+            if nb >= n || owner[nb] != usize::MAX {
+                continue;
+            }
+            let Some(w) = step(cell, nb) else { continue };
+            let next = cost + w;
+            if next < best[nb] {
+                best[nb] = next;
+                heap.push(Reverse(Frontier {
+                    cost: next,
+                    cell: nb,
+                    source,
+                }));
+            }
+        }
+    }
+    (owner, best)
+}
+
+/// `exp(-(x / w)²)` — a bell of half-width `w`.
+fn bell(x: f64, w: f64) -> f64 {
+    (-(x / w).powi(2)).exp()
+}
+
+/// Relief (height offset) at distance `x` (in belt widths) behind a
+/// boundary with profile `p`, before `relief` scaling. `jitter` in `0..1`
+/// breaks island arcs into separate peaks.
+fn relief_at(p: &Profile, x: f64, jitter: f64) -> f64 {
+    let s = p.strength;
+    match p.kind {
+        Boundary::Convergent { .. } if p.continental && p.other_continental => {
+            65.0 * s * bell(x, 1.2)
+        }
+        Boundary::Convergent { overriding: true } if p.continental => {
+            55.0 * s * bell(x - 0.6, 0.6) - 6.0 * s * bell(x, 0.25)
+        }
+        Boundary::Convergent { overriding: true } => {
+            // Island arc: tall enough to break the surface, but only in spots.
+            let arc = (SEA_LEVEL as f64 - OCEAN_BASE + 10.0) * s * bell(x - 0.5, 0.35);
+            arc * (0.25 + 1.1 * jitter)
+        }
+        Boundary::Convergent { overriding: false } => -12.0 * s * bell(x, 0.35),
+        Boundary::Divergent if p.continental => -16.0 * s * bell(x, 0.5),
+        Boundary::Divergent => 7.0 * s * bell(x, 0.8),
+        Boundary::Transform => 0.0,
+    }
+}
+
+/// Generate a tectonic heightmap over `mesh`.
+pub fn generate(mesh: &Mesh, seed: u64, opts: &TectonicOpts) -> Tectonics {
+    let n = mesh.points.len();
+    let mut rng = StdRng::seed_from_u64(seed);
+    if n == 0 {
+        return Tectonics {
+            h: Vec::new(),
+            plate: Vec::new(),
+            plates: Vec::new(),
+        };
+    }
+    let edge = |a: usize, b: usize| mesh.distance_sq(mesh.points[a], mesh.points[b]).sqrt();
+
+    // 1. Plates: seeds, motion, roughness-weighted growth.
+    let count = (opts.plates.max(1) as usize).min(n);
+    let mut seeds: Vec<usize> = Vec::with_capacity(count);
+    while seeds.len() < count {
+        let c = rng.gen_range(0..n);
+        if !seeds.contains(&c) {
+            seeds.push(c);
+        }
+    }
+    let velocity: Vec<[f64; 2]> = (0..count)
+        .map(|_| {
+            let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+            let speed = rng.gen_range(0.3..=1.0);
+            [speed * angle.cos(), speed * angle.sin()]
+        })
+        .collect();
+    let buoyancy: Vec<f64> = (0..count).map(|_| rng.gen::<f64>()).collect();
+    let roughness: Vec<f64> = (0..n).map(|_| rng.gen_range(0.5..1.5)).collect();
+    let sources: Vec<(usize, usize)> = seeds.iter().copied().zip(0..).collect();
+    let (plate, _) = spread(mesh, &sources, |a, b| Some(edge(a, b) * roughness[b]));
+
+    let mut area = vec![0u32; count];
+    for &p in &plate {
+        area[p] += 1;
+    }
+    let mut order: Vec<usize> = (0..count).collect();
+    for k in (1..count).rev() {
+        order.swap(k, rng.gen_range(0..=k));
+    }
+    let target = (opts.continental_fraction.clamp(0.0, 1.0) * n as f64).round() as u32;
+    let mut continental = vec![false; count];
+    let mut covered = 0u32;
+    for &p in &order {
+        if covered >= target {
+            break;
+        }
+        continental[p] = true;
+        covered += area[p];
+    }
+
+    // 2. Boundary profiles: per boundary cell, its strongest interaction.
+    let mut profiles: Vec<Profile> = Vec::new();
+    let mut boundary_sources: Vec<(usize, usize)> = Vec::new();
+    for c in 0..n {
+        let pc = plate[c];
+        let mut best: Option<(f64, Profile)> = None;
+        for &d in mesh.cells.neighbors_of_cell(c) {
+            let d = d as usize;
+            if d >= n || plate[d] == pc {
+                continue;
+            }
+            let pd = plate[d];
+            let [x0, y0] = mesh.points[c];
+            let [x1, y1] = mesh.points[d];
+            let (nx, ny) = (mesh.delta_x(x0, x1), y1 - y0);
+            let len = (nx * nx + ny * ny).sqrt().max(f64::EPSILON);
+            let rel = [
+                velocity[pc][0] - velocity[pd][0],
+                velocity[pc][1] - velocity[pd][1],
+            ];
+            let closing = (rel[0] * nx + rel[1] * ny) / len;
+            let kind = if closing > TRANSFORM_SPEED {
+                let overriding = match (continental[pc], continental[pd]) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    _ => buoyancy[pc] >= buoyancy[pd],
+                };
+                Boundary::Convergent { overriding }
+            } else if closing < -TRANSFORM_SPEED {
+                Boundary::Divergent
+            } else {
+                Boundary::Transform
+            };
+            let profile = Profile {
+                kind,
+                strength: (closing.abs() / 2.0).min(1.0),
+                continental: continental[pc],
+                other_continental: continental[pd],
+            };
+            if best.is_none_or(|(s, _)| closing.abs() > s) {
+                best = Some((closing.abs(), profile));
+            }
+        }
+        if let Some((_, profile)) = best {
+            boundary_sources.push((c, profiles.len()));
+            profiles.push(profile);
+        }
+    }
+
+    // 3. Spread each profile inward over its own plate.
+    let (nearest, distance) = spread(mesh, &boundary_sources, |a, b| {
+        (plate[a] == plate[b]).then(|| edge(a, b))
+    });
+    let belt = opts.belt_width.max(1e-6) * (mesh.world_w * mesh.world_h).sqrt();
+
+    // 4. Background field: white noise smoothed into broad swells.
+    let mut field: Vec<f64> = (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect();
+    for _ in 0..6 {
+        field = (0..n)
+            .map(|c| {
+                let ring = mesh.cells.neighbors_of_cell(c);
+                let (sum, k) = ring
+                    .iter()
+                    .map(|&d| d as usize)
+                    .filter(|&d| d < n)
+                    .fold((field[c], 1.0), |(s, k), d| (s + field[d], k + 1.0));
+                sum / k
+            })
+            .collect();
+    }
+    let spread_max = field.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(1e-9);
+
+    let mut h: Vec<f64> = (0..n)
+        .map(|c| {
+            let base = if continental[plate[c]] {
+                CONTINENT_BASE
+            } else {
+                OCEAN_BASE
+            };
+            let background = BACKGROUND_AMPLITUDE * field[c] / spread_max;
+            let jitter = rng.gen::<f64>();
+            let boundary = match nearest[c] {
+                usize::MAX => 0.0,
+                s => relief_at(&profiles[s], distance[c] / belt, jitter),
+            };
+            base + background + opts.relief * boundary
+        })
+        .collect();
+
+    // One light smoothing pass to soften single-cell spikes.
+    h = (0..n)
+        .map(|c| {
+            let (sum, k) = mesh
+                .cells
+                .neighbors_of_cell(c)
+                .iter()
+                .map(|&d| d as usize)
+                .filter(|&d| d < n)
+                .fold((0.0, 0.0), |(s, k), d| (s + h[d], k + 1.0));
+            if k == 0.0 {
+                h[c]
+            } else {
+                (2.0 * h[c] + sum / k) / 3.0
+            }
+        })
+        .collect();
+
+    Tectonics {
+        h: h.iter()
+            .map(|v| v.clamp(0.0, 100.0).round() as u8)
+            .collect(),
+        plate: plate.iter().map(|&p| p as u32).collect(),
+        plates: (0..count)
+            .map(|p| Plate {
+                seed_cell: seeds[p] as u32,
+                velocity: velocity[p],
+                continental: continental[p],
+                area: area[p],
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh;
+
+    #[test]
+    fn deterministic_and_seed_sensitive() {
+        let mesh = mesh::build(3000, 5);
+        let opts = TectonicOpts::default();
+        let a = generate(&mesh, 5, &opts);
+        assert_eq!(a, generate(&mesh, 5, &opts));
+        assert_ne!(a.h, generate(&mesh, 6, &opts).h);
+        assert_eq!(a.h.len(), mesh.points.len());
+        assert_eq!(a.plates.len(), 14);
+        assert_eq!(
+            a.plates.iter().map(|p| p.area).sum::<u32>() as usize,
+            mesh.points.len()
+        );
+    }
+
+    /// Every plate is one connected region containing its seed.
+    #[test]
+    fn plates_are_contiguous() {
+        let mesh = mesh::build(2000, 9);
+        let t = generate(&mesh, 9, &TectonicOpts::default());
+        for (p, plate) in t.plates.iter().enumerate() {
+            let seed = plate.seed_cell as usize;
+            assert_eq!(t.plate[seed], p as u32);
+            let mut seen = vec![false; mesh.points.len()];
+            let mut stack = vec![seed];
+            seen[seed] = true;
+            let mut reached = 0u32;
+            while let Some(c) = stack.pop() {
+                reached += 1;
+                for &d in mesh.cells.neighbors_of_cell(c) {
+                    let d = d as usize;
+                    if d < seen.len() && !seen[d] && t.plate[d] == p as u32 {
+                        seen[d] = true;
+                        stack.push(d);
+                    }
+                }
+            }
+            assert_eq!(reached, plate.area, "plate {p}");
+        }
+    }
+
+    /// Continental crust stands mostly above sea level, oceanic crust mostly
+    /// below, and the highest peaks sit on convergent boundaries.
+    #[test]
+    fn crust_types_set_the_sea_level() {
+        let mesh = mesh::build(4000, 21);
+        let t = generate(&mesh, 21, &TectonicOpts::default());
+        let share_land = |continental: bool| {
+            let cells: Vec<usize> = (0..mesh.points.len())
+                .filter(|&c| t.plates[t.plate[c] as usize].continental == continental)
+                .collect();
+            cells.iter().filter(|&&c| t.h[c] >= SEA_LEVEL).count() as f64 / cells.len() as f64
+        };
+        assert!(share_land(true) > 0.7, "continents {}", share_land(true));
+        assert!(share_land(false) < 0.2, "oceans {}", share_land(false));
+        assert!(*t.h.iter().max().unwrap() >= 50);
+    }
+
+    /// A head-on collision of two continental plates raises a belt along the
+    /// boundary; pulling them apart opens a rift instead.
+    #[test]
+    fn convergence_raises_and_divergence_rifts() {
+        let p = |kind| Profile {
+            kind,
+            strength: 1.0,
+            continental: true,
+            other_continental: true,
+        };
+        let collide = p(Boundary::Convergent { overriding: true });
+        assert!(relief_at(&collide, 0.0, 0.5) > 40.0);
+        assert!(relief_at(&collide, 0.0, 0.5) > relief_at(&collide, 2.0, 0.5));
+        assert!(relief_at(&p(Boundary::Divergent), 0.0, 0.5) < -10.0);
+        assert_eq!(relief_at(&p(Boundary::Transform), 0.0, 0.5), 0.0);
+        // Subducting ocean: trench; the continent above it: inland cordillera.
+        let under = Profile {
+            continental: false,
+            ..p(Boundary::Convergent { overriding: false })
+        };
+        assert!(relief_at(&under, 0.0, 0.5) < 0.0);
+        let over = Profile {
+            other_continental: false,
+            ..collide
+        };
+        assert!(relief_at(&over, 0.6, 0.5) > relief_at(&over, 0.0, 0.5));
+    }
+}
@@ -5,11 +5,17 @@
 //! `Event[]` (design §3.3, §3.4). Projection is O(events ≤ Y) and cheap, so the
 //! React scrubber (Phase 5) gets a `WorldAt` every frame at 60fps.
 //!
+//! The climate drifts over the era too: `ClimateKeyframe`s (ice ages, warm
+//! periods) shift the year-0 temperature and precipitation, and
+//! `project_climate` yields the `ClimateAt(Y)` temp / prec / biome fields to
+//! show alongside `WorldAt(Y)`.
+//!
 //! See `agent/worldgen-implementation-plan.md` §Step 4.1 for the gate criteria.
 
 use serde::{Deserialize, Serialize};
 
 use crate::entities::{Army, Burg, Culture, Pack, Religion, State};
+use crate::mesh::Mesh;
 
 // ---------------------------------------------------------------------------//
 // EntityType — which kind of entity an `Event` targets.
@@ -663,6 +669,117 @@ fn apply_event(world: &mut WorldAt, ev: &Event) {
         }
     }
 }
+
+// ---------------------------------------------------------------------------//
+// Climate drift — era-level temperature / precipitation keyframes.
+// ---------------------------------------------------------------------------//
+
+/// One keyframe of the era climate: how far it has drifted from the year-0
+/// climate (the generated `cells.temp` / `cells.prec`) by `year`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClimateKeyframe {
+    /// In-universe year (can be negative, like `Event::year`).
+    pub year: i32,
+    /// Added to every cell's year-0 temperature (°C).
+    pub temp_offset: f64,
+    /// Multiplies every cell's year-0 precipitation.
+    pub prec_scale: f64,
+}
+
+/// Keyframed era climate (e.g. an ice age, a warm period or a Long Winter).
+/// Keyframes are expected sorted by year; `climate_shift` sorts defensively.
+/// Empty means the year-0 climate holds for the whole era.
+pub type ClimateDrift = Vec<ClimateKeyframe>;
+
+/// The era climate's departure from year 0 at a given year.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ClimateShift {
+    /// Added to every cell's year-0 temperature (°C).
+    pub temp_offset: f64,
+    /// Multiplies every cell's year-0 precipitation.
+    pub prec_scale: f64,
+}
+
+impl Default for ClimateShift {
+    fn default() -> Self {
+        ClimateShift {
+            temp_offset: 0.0,
+            prec_scale: 1.0,
+        }
+    }
+}
+
+/// The era climate at `year`: linear between the keyframes either side,
+/// holding the first keyframe before it and the last one after it.
+pub fn climate_shift(drift: &[ClimateKeyframe], year: i32) -> ClimateShift {
+    let mut keys: Vec<&ClimateKeyframe> = drift.iter().collect();
+    keys.sort_by_key(|k| k.year);
+    let shift = |k: &ClimateKeyframe| ClimateShift {
+        temp_offset: k.temp_offset,
+        prec_scale: k.prec_scale,
+    };
+    let Some(after) = keys.iter().position(|k| k.year > year) else {
+        return keys.last().map_or_else(ClimateShift::default, |k| shift(k));
+    };
+    if after == 0 {
+        return shift(keys[0]);
+    }
+    let (a, b) = (keys[after - 1], keys[after]);
+    let t = (year - a.year) as f64 / (b.year - a.year) as f64;
+    ClimateShift {
+        temp_offset: a.temp_offset + (b.temp_offset - a.temp_offset) * t,
+        prec_scale: a.prec_scale + (b.prec_scale - a.prec_scale) * t,
+    }
+}
+
+/// The projected climate at year `Y`, alongside `WorldAt(Y)`: the year-0
+/// `temp` / `prec` shifted by the era climate, and the biomes they make.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ClimateAt {
+    /// The year this snapshot is projected for.
+    pub year: i32,
+    /// The era climate at `year`.
+    pub shift: ClimateShift,
+    /// Per-cell temperature (°C) at year Y.
+    pub temp: Vec<i8>,
+    /// Per-cell precipitation at year Y.
+    pub prec: Vec<u8>,
+    /// Per-cell biome id at year Y (`biomes::BIOMES`).
+    pub biome: Vec<u8>,
+}
+
+/// Project the climate at `target_year` from the year-0 `temp` / `prec`.
+/// Temperatures move by the era's offset and precipitation by its scale; the
+/// biomes are then reclassified from the shifted values (`generate_biomes`),
+/// so a cold era spreads tundra and glacier where the map had forest. With no
+/// drift the year-0 fields come back unchanged.
+pub fn project_climate(
+    mesh: &Mesh,
+    h: &[u8],
+    temp: &[i8],
+    prec: &[u8],
+    drift: &[ClimateKeyframe],
+    target_year: i32,
+) -> ClimateAt {
+    let shift = climate_shift(drift, target_year);
+    let temp: Vec<i8> = temp
+        .iter()
+        .map(|&t| (t as f64 + shift.temp_offset).round().clamp(-128.0, 127.0) as i8)
+        .collect();
+    let prec: Vec<u8> = prec
+        .iter()
+        .map(|&p| (p as f64 * shift.prec_scale).round().clamp(0.0, 255.0) as u8)
+        .collect();
+    let biome = crate::biomes::generate_biomes(mesh, h, &temp, &prec);
+    ClimateAt {
+        year: target_year,
+        shift,
+        temp,
+        prec,
+        biome,
+    }
+}
+
 //----------------------------------------------------------------------------//
 // small helpers
 // ---------------------------------------------------------------------------//
@@ -1171,4 +1288,64 @@ mod tests {
             "delta projection must match full"
         );
     }
+
+    /// The Long Winter of our histories: four decades, twenty years to set in
+    /// and twenty to lift.
+    fn long_winter() -> ClimateDrift {
+        [
+            (300, 0.0, 1.0),
+            (320, -10.0, 0.7),
+            (360, -10.0, 0.7),
+            (380, 0.0, 1.0),
+        ]
+        .into_iter()
+        .map(|(year, temp_offset, prec_scale)| ClimateKeyframe {
+            year,
+            temp_offset,
+            prec_scale,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn climate_shift_interpolates_between_keyframes() {
+        assert_eq!(climate_shift(&[], 500), ClimateShift::default());
+
+        let mut drift = long_winter();
+        drift.reverse(); // sorted defensively
+        let at = |year| climate_shift(&drift, year);
+        assert_eq!(at(0), ClimateShift::default());
+        assert_eq!(at(310).temp_offset, -5.0);
+        assert!((at(310).prec_scale - 0.85).abs() < 1e-12);
+        assert_eq!(at(340).temp_offset, -10.0);
+        assert_eq!(at(370).temp_offset, -5.0);
+        assert_eq!(at(1000), ClimateShift::default());
+    }
+
+    #[test]
+    fn long_winter_shows_on_the_map() {
+        let grid = crate::generate_world_inner(7, 2000, &crate::climate::ClimateOpts::default());
+        let cells = &grid.cells;
+        let drift = long_winter();
+        let at =
+            |year| project_climate(&grid.mesh, &cells.h, &cells.temp, &cells.prec, &drift, year);
+
+        // Before and after, the year-0 climate.
+        let base = crate::biomes::generate_biomes(&grid.mesh, &cells.h, &cells.temp, &cells.prec);
+        for year in [0, 400] {
+            let climate = at(year);
+            assert_eq!(climate.temp, cells.temp);
+            assert_eq!(climate.prec, cells.prec);
+            assert_eq!(climate.biome, base);
+        }
+
+        // In the depths of it, ten degrees colder, and the ice spreads.
+        let winter = at(340);
+        assert_eq!(winter.year, 340);
+        for (&t, &t0) in winter.temp.iter().zip(&cells.temp) {
+            assert_eq!(t as i32, (t0 as i32 - 10).max(-128));
+        }
+        let frozen = |biome: &[u8]| biome.iter().filter(|&&b| b == 10 || b == 11).count();
+        assert!(frozen(&winter.biome) > frozen(&base));
+    }
 }